@@ -0,0 +1,62 @@
+import json
+import math
+
+NUMBERS = 40
+DRAWN = 10
+MAX_PICKS = 10
+RTP = 0.99
+
+# per risk level: part of the picks that has to be hit to win anything,
+# growth of the multiplier with every hit and the max multiplier
+RISKS = [
+    (0.25, 2.0, 1000),
+    (0.34, 3.5, 5000),
+    (0.5, 6.0, 10000),
+]
+
+
+def probability(picks, hits):
+    return (
+        math.comb(picks, hits)
+        * math.comb(NUMBERS - picks, DRAWN - hits)
+        / math.comb(NUMBERS, DRAWN)
+    )
+
+
+def rtp(picks, multipliers):
+    return sum(probability(picks, hits) * m for hits, m in enumerate(multipliers))
+
+
+def paytable(picks, min_part, growth, max_multiplier):
+    min_hits = max(1, math.ceil(picks * min_part))
+    weights = [
+        growth ** (hits - min_hits) if hits >= min_hits else 0
+        for hits in range(0, picks + 1)
+    ]
+
+    # the multipliers are capped, so the scale is searched instead of solved
+    low, high = 0.0, float(max_multiplier)
+    for _ in range(200):
+        scale = (low + high) / 2
+        if rtp(picks, [min(scale * w, max_multiplier) for w in weights]) > RTP:
+            high = scale
+        else:
+            low = scale
+
+    # rounded down to keep the RTP under the target
+    return [
+        math.floor(min(low * w, max_multiplier) * 10000) / 10000 for w in weights
+    ]
+
+
+multipliers = []
+for risk, (min_part, growth, max_multiplier) in enumerate(RISKS):
+    tables = []
+    for picks in range(1, MAX_PICKS + 1):
+        table = paytable(picks, min_part, growth, max_multiplier)
+        print(f"risk {risk} picks {picks}: RTP {rtp(picks, table):.6f}")
+        tables.append([str(m) for m in table])
+    multipliers.append(tables)
+
+
+print(json.dumps({"multipliers": multipliers}))
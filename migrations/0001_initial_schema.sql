@@ -0,0 +1,269 @@
+-- Baseline of the schema previously created by `db_scheme/scheme.sql`
+--
+-- The tables are the ones of the old script, with every statement made idempotent, so
+-- databases created from the old script adopt this migration without changes. The columns and
+-- the tables added since then come from the next migrations.
+
+DO $$ BEGIN
+    CREATE TYPE oauth_provider AS ENUM ('local', 'google', 'facebook', 'twitter');
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS Users(
+    id BIGSERIAL PRIMARY KEY,
+    registration_time TIMESTAMP DEFAULT NOW(),
+
+    login TEXT NOT NULL UNIQUE,
+    username TEXT NOT NULL,
+    password char(128) NOT NULL,
+    provider oauth_provider DEFAULT 'local',
+    user_level BIGINT DEFAULT 1
+);
+
+CREATE TABLE IF NOT EXISTS Achievement(
+    id BIGSERIAL PRIMARY KEY,
+
+    achieving_time TIMESTAMP DEFAULT NOW(),
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+
+    achievement_name TEXT NOT NULL,
+    level_cost SMALLINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS RefreshToken (
+    token TEXT PRIMARY KEY,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    creation_date TIMESTAMP DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS Coin(
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    price NUMERIC(1000, 4) NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS Amount(
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    coin_id BIGSERIAL NOT NULL REFERENCES Coin(id) ON DELETE CASCADE,
+
+    amount NUMERIC(1000, 4) DEFAULT 0
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS amount_unique_idx ON Amount(user_id, coin_id);
+
+CREATE TABLE IF NOT EXISTS Game(
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+
+    parameters TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS UserSeed(
+    id BIGSERIAL PRIMARY KEY,
+    --relative_id BIGINT NOT NULL,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+
+    user_seed char(64) NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS user_seed_unique_idx ON UserSeed(user_id, user_seed);
+
+CREATE TABLE IF NOT EXISTS ServerSeed(
+    id BIGSERIAL PRIMARY KEY,
+    --relative_id BIGINT NOT NULL,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+
+    server_seed char(128) NOT NULL,
+    revealed boolean NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS Bet(
+    id BIGSERIAL PRIMARY KEY,
+    --relative_id BIGINT,
+    timestamp TIMESTAMP DEFAULT NOW(),
+    amount NUMERIC(1000, 4),
+    profit NUMERIC(1000, 4),
+    num_games INTEGER NOT NULL,
+    outcomes TEXT NOT NULL,
+    profits TEXT NOT NULL,
+
+    bet_info TEXT NOT NULL,
+    state TEXT,
+    uuid TEXT NOT NULL,
+
+    game_id BIGSERIAL NOT NULL REFERENCES Game(id) ON DELETE CASCADE,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    coin_id BIGSERIAL NOT NULL REFERENCES Coin(id) ON DELETE CASCADE,
+    userseed_id BIGSERIAL NOT NULL REFERENCES UserSeed(id) ON DELETE CASCADE,
+    serverseed_id BIGSERIAL NOT NULL REFERENCES ServerSeed(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS Payout(
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMP DEFAULT NOW(),
+    amount NUMERIC(1000, 4),
+    status INTEGER DEFAULT 0,
+    additional_data TEXT NOT NULL,
+
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS GameState(
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMP DEFAULT NOW(),
+    amount NUMERIC(1000, 4),
+
+    bet_info TEXT NOT NULL,
+    state TEXT NOT NULL,
+    uuid TEXT NOT NULL,
+
+    game_id BIGSERIAL NOT NULL REFERENCES Game(id) ON DELETE CASCADE,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    coin_id BIGSERIAL NOT NULL REFERENCES Coin(id) ON DELETE CASCADE,
+    userseed_id BIGSERIAL NOT NULL REFERENCES UserSeed(id) ON DELETE CASCADE,
+    serverseed_id BIGSERIAL NOT NULL REFERENCES ServerSeed(id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS state_unique_idx ON GameState(game_id, user_id, coin_id, userseed_id, serverseed_id);
+
+
+CREATE TABLE IF NOT EXISTS Invoice(
+    id TEXT NOT NULL PRIMARY KEY,
+    merchant_id TEXT NOT NULL,
+    order_id TEXT NOT NULL UNIQUE,
+    create_date TIMESTAMP DEFAULT NOW(),
+    status INTEGER NOT NULL,
+    pay_url TEXT NOT NULL,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    amount NUMERIC(1000, 4),
+    currency TEXT NOT NULL
+);
+
+DO $$ BEGIN
+    CREATE TYPE billine_status AS ENUM ('pending', 'success', 'failed' );
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS InvoiceBilline(
+    id TEXT NOT NULL PRIMARY KEY,
+    merchant_id TEXT NOT NULL,
+    order_id TEXT NOT NULL UNIQUE,
+    create_date TIMESTAMP DEFAULT NOW(),
+    status billine_status DEFAULT 'pending',
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    amount NUMERIC(1000, 4),
+    currency TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS TokensToTrack(
+    id TEXT NOT NULL PRIMARY KEY,
+    tokens TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS Referal(
+    id BIGSERIAL PRIMARY KEY,
+    refer_to BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE UNIQUE,
+    link_name VARCHAR(8) NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS Referals(
+    id BIGSERIAL PRIMARY KEY,
+    refer_to BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    refer_name BIGSERIAL NOT NULL REFERENCES Referal(id) ON DELETE CASCADE,
+    referal BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    create_date TIMESTAMP DEFAULT NOW()
+);
+CREATE UNIQUE INDEX IF NOT EXISTS referals_unique_idx ON Referals(refer_to, referal);
+
+-- Partner
+DO $$ BEGIN
+    CREATE TYPE PartnerProgram AS ENUM(
+    'firstMonth',
+    'novice',
+    'beginner',
+    'intermediate',
+    'advanced',
+    'pro',
+    'god'
+);
+EXCEPTION
+    WHEN duplicate_object THEN NULL;
+END $$;
+
+CREATE TABLE IF NOT EXISTS Partner(
+    --id BIGSERIAL PRIMARY KEY,
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    country TEXT NOT NULL,
+    traffic_source TEXT NOT NULL,
+    users_amount_a_month BIGINT NOT NULL,
+    program PartnerProgram NOT NULL,
+    is_verified boolean NOT NULL,
+    login varchar(25) UNIQUE,
+    password char(128) NOT NULL,
+    registration_time TIMESTAMP DEFAULT Now(),
+    language TEXT
+);
+
+CREATE TABLE IF NOT EXISTS PartnerContact(
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    partner_id BIGSERIAL NOT NULL REFERENCES Partner(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS PartnerSite(
+    internal_id BIGSERIAL PRIMARY KEY, 
+    id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    
+    partner_id BIGSERIAL NOT NULL REFERENCES Partner(id) ON DELETE CASCADE
+);
+CREATE UNIQUE INDEX IF NOT EXISTS partnersite_id_unique_idx ON PartnerSite(id, partner_id);
+
+CREATE TABLE IF NOT EXISTS SiteSubId(
+    internal_id BIGSERIAL PRIMARY KEY, 
+    id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    url TEXT,
+    
+    site_id BIGINT NOT NULL REFERENCES PartnerSite(internal_id) ON DELETE CASCADE,
+    partner_id BIGSERIAL NOT NULL REFERENCES Partner(id) ON DELETE CASCADE
+);
+CREATE UNIQUE INDEX IF NOT EXISTS subid_unique_idx ON SiteSubId(id, site_id);
+
+CREATE TABLE IF NOT EXISTS RefClick(
+    id BIGSERIAL PRIMARY KEY,
+    --clicks BIGINT NOT NULL,
+    timestamp TIMESTAMP NOT NULL,
+    
+    --sub_id BIGINT NOT NULL,
+    sub_id_internal BIGINT NOT NULL REFERENCES SiteSubId(internal_id) ON DELETE CASCADE,
+    partner_id BIGSERIAL NOT NULL REFERENCES Partner(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS ConnectedUsers(
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMP NOT NULL,
+
+    --sub_id BIGINT NOT NULL,
+    sub_id_internal BIGINT NOT NULL REFERENCES SiteSubId(internal_id) ON DELETE CASCADE,
+    partner_id BIGSERIAL NOT NULL REFERENCES Partner(id) ON DELETE CASCADE,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS Withdrawal(
+    id BIGSERIAL PRIMARY KEY,
+    start_time TIMESTAMP DEFAULT NOW(),
+
+    token varchar(20) NOT NULL,
+    network varchar(30) NOT NULL,
+    wallet_address varchar(200) NOT NULL,
+    status TEXT DEFAULT 'waiting', --waiting/accepted/rejected,
+    amount TEXT NOT NULL,
+
+    partner_id BIGSERIAL NOT NULL REFERENCES Partner(id) ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+-- Steps of the stateful games, kept for the verification of the finished games
+
+ALTER TABLE Bet ADD COLUMN steps TEXT;
+
+ALTER TABLE GameState ADD COLUMN steps TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,60 @@
+-- Nonce of every bet and game state, taken from the next nonce of its seed pair
+
+CREATE TABLE SeedNonce(
+    userseed_id BIGINT NOT NULL REFERENCES UserSeed(id) ON DELETE CASCADE,
+    serverseed_id BIGINT NOT NULL REFERENCES ServerSeed(id) ON DELETE CASCADE,
+    nonce BIGINT NOT NULL,
+
+    PRIMARY KEY(userseed_id, serverseed_id)
+);
+
+-- the existing bets and states are numbered in the order they were played on their pair
+ALTER TABLE Bet ADD COLUMN nonce BIGINT;
+
+UPDATE Bet SET nonce = Numbered.nonce
+FROM (
+    SELECT
+        id,
+        ROW_NUMBER() OVER (PARTITION BY userseed_id, serverseed_id ORDER BY id) - 1 AS nonce
+    FROM Bet
+) AS Numbered
+WHERE Bet.id = Numbered.id;
+
+ALTER TABLE Bet ALTER COLUMN nonce SET NOT NULL;
+
+ALTER TABLE GameState ADD COLUMN nonce BIGINT;
+
+UPDATE GameState SET nonce = Numbered.nonce
+FROM (
+    SELECT
+        GameState.id,
+        COALESCE(Played.nonce, -1) + ROW_NUMBER() OVER (
+            PARTITION BY GameState.userseed_id, GameState.serverseed_id
+            ORDER BY GameState.id
+        ) AS nonce
+    FROM GameState
+    LEFT JOIN (
+        SELECT userseed_id, serverseed_id, MAX(nonce) AS nonce
+        FROM Bet
+        GROUP BY userseed_id, serverseed_id
+    ) AS Played
+    ON Played.userseed_id = GameState.userseed_id
+        AND Played.serverseed_id = GameState.serverseed_id
+) AS Numbered
+WHERE GameState.id = Numbered.id;
+
+ALTER TABLE GameState ALTER COLUMN nonce SET NOT NULL;
+
+-- the next nonce of a pair follows the ones already used
+INSERT INTO SeedNonce(
+    userseed_id,
+    serverseed_id,
+    nonce
+)
+SELECT userseed_id, serverseed_id, MAX(nonce)
+FROM (
+    SELECT userseed_id, serverseed_id, nonce FROM Bet
+    UNION ALL
+    SELECT userseed_id, serverseed_id, nonce FROM GameState
+) AS Used
+GROUP BY userseed_id, serverseed_id;
@@ -0,0 +1,12 @@
+-- Notifies the server about the changes of the games, so it reloads their parameters
+
+CREATE FUNCTION notify_game_changed() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('game_changed', TG_OP);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER game_changed_trigger
+AFTER INSERT OR UPDATE OR DELETE ON Game
+FOR EACH STATEMENT EXECUTE FUNCTION notify_game_changed();
@@ -0,0 +1,13 @@
+-- Bet limits of every game and coin
+
+CREATE TABLE GameLimits(
+    game_id BIGINT NOT NULL REFERENCES Game(id) ON DELETE CASCADE,
+    coin_id BIGINT NOT NULL REFERENCES Coin(id) ON DELETE CASCADE,
+
+    min_bet NUMERIC(1000, 4) NOT NULL,
+    max_bet NUMERIC(1000, 4) NOT NULL,
+    max_num_games BIGINT NOT NULL,
+    max_payout NUMERIC(1000, 4) NOT NULL,
+
+    PRIMARY KEY(game_id, coin_id)
+);
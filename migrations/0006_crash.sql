@@ -0,0 +1,21 @@
+-- Hash chains of the multiplayer crash game and the rounds played from them
+
+CREATE TABLE CrashChain(
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMP DEFAULT NOW(),
+
+    seed TEXT NOT NULL,
+    commitment TEXT NOT NULL,
+    length BIGINT NOT NULL,
+    position BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE CrashRound(
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMP DEFAULT NOW(),
+
+    chain_id BIGINT NOT NULL REFERENCES CrashChain(id) ON DELETE CASCADE,
+    position BIGINT NOT NULL,
+    hash TEXT NOT NULL,
+    crash_point NUMERIC(1000, 4) NOT NULL
+);
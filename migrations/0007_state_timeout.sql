@@ -0,0 +1,3 @@
+-- Seconds after the last step of an unfinished game it's resolved, never if NULL
+
+ALTER TABLE Game ADD COLUMN state_timeout BIGINT;
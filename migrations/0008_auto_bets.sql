@@ -0,0 +1,26 @@
+-- Server side auto bet sessions, one active session per game and coin of the user
+
+CREATE TABLE AutoBet(
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMP DEFAULT NOW(),
+
+    game_id BIGSERIAL NOT NULL REFERENCES Game(id) ON DELETE CASCADE,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    coin_id BIGSERIAL NOT NULL REFERENCES Coin(id) ON DELETE CASCADE,
+    uuid TEXT NOT NULL,
+
+    data TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    base_amount NUMERIC(1000, 4) NOT NULL,
+    amount NUMERIC(1000, 4) NOT NULL,
+    max_bets BIGINT NOT NULL,
+    stop_win NUMERIC(1000, 4) NOT NULL,
+    stop_loss NUMERIC(1000, 4) NOT NULL,
+
+    bets BIGINT NOT NULL DEFAULT 0,
+    profit NUMERIC(1000, 4) NOT NULL DEFAULT 0,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    stop_reason TEXT
+);
+
+CREATE UNIQUE INDEX autobet_active_unique_idx ON AutoBet(game_id, user_id, coin_id) WHERE active;
@@ -0,0 +1,16 @@
+-- Progressive jackpot of every coin and its wins
+
+CREATE TABLE Jackpot(
+    coin_id BIGINT PRIMARY KEY REFERENCES Coin(id) ON DELETE CASCADE,
+    amount NUMERIC(1000, 4) NOT NULL DEFAULT 0
+);
+
+CREATE TABLE JackpotWin(
+    id BIGSERIAL PRIMARY KEY,
+    timestamp TIMESTAMP DEFAULT NOW(),
+
+    bet_id BIGINT NOT NULL REFERENCES Bet(id) ON DELETE CASCADE,
+    user_id BIGSERIAL NOT NULL REFERENCES Users(id) ON DELETE CASCADE,
+    coin_id BIGSERIAL NOT NULL REFERENCES Coin(id) ON DELETE CASCADE,
+    amount NUMERIC(1000, 4) NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Public salt mixed into the hashes of the rounds of the crash chain, `NULL` until the salt is
+-- published for the chain
+
+ALTER TABLE CrashChain ADD COLUMN salt TEXT;
+
+-- the rounds of the existing chains were played without a salt
+UPDATE CrashChain SET salt = '';
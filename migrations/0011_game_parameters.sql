@@ -0,0 +1,30 @@
+-- Parameters and state timeouts of the games changed since the baseline, the games missing in
+-- the database are inserted by the data migrations
+
+-- multiplayer rounds replaced the single player Crash
+UPDATE Game SET parameters = '{"house_edge":"0.01",
+     "betting_window":10000,
+     "tick":100,
+     "cooldown":3000,
+     "growth_rate":0.00006,
+     "max_multiplier":"1000000",
+     "chain_length":100000}'
+WHERE name = 'Crash';
+
+-- every bet of the wheel is covered, paying 36 divided by the numbers it covers
+UPDATE Game SET parameters = '{
+        "wheel":"SingleZero",
+        "zero_coef":"36",
+        "num_coef":"36",
+        "num2_coef":"18",
+        "num3_coef":"12",
+        "num4_coef":"9",
+        "num5_coef":"7",
+        "num6_coef":"6",
+        "num12_coef":"3",
+        "num18_coef":"2"
+    }'
+WHERE name = 'Roulette';
+
+UPDATE Game SET state_timeout = 3600
+WHERE name IN ('Mines', 'Poker', 'Apples', 'BigSlots') AND state_timeout IS NULL;
@@ -0,0 +1,17 @@
+-- Coins missing in the database, the prices of the existing coins are kept
+
+INSERT INTO Coin(
+    name,
+    price
+) VALUES (
+    'DraxBonus',
+    1000
+) ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO Coin(
+    name,
+    price
+) VALUES (
+    'Drax',
+    10
+) ON CONFLICT (name) DO NOTHING;
@@ -0,0 +1,12 @@
+-- Default limits of the new games and coins, the limits already set are kept
+
+INSERT INTO GameLimits(
+    game_id,
+    coin_id,
+    min_bet,
+    max_bet,
+    max_num_games,
+    max_payout
+) SELECT Game.id, Coin.id, 0, 50 * Coin.price, 100, 50000 * Coin.price
+FROM Game CROSS JOIN Coin
+ON CONFLICT (game_id, coin_id) DO NOTHING;
@@ -6,6 +6,7 @@ use utoipa::{
 #[allow(unused_imports)]
 use utoipa_swagger_ui::Config;
 
+use crate::games::registry::GAMES;
 use crate::handlers;
 use crate::models::{db_models, json_requests, json_responses, LeaderboardType};
 
@@ -31,8 +32,10 @@ use warp::{
             handlers::generate_qr,
             handlers::get_client_seed,
             handlers::get_server_seed,
+            handlers::get_seed_history,
             handlers::get_bets_for_game,
             handlers::get_all_last_bets,
+            handlers::verify_bet,
             handlers::get_all_games,
             handlers::get_all_coins,
             handlers::crypto_prices,
@@ -81,18 +84,26 @@ use warp::{
             json_responses::InfoText,
             json_responses::AccessToken,
             json_responses::Seed,
+            json_responses::SeedPairInfo,
+            json_responses::SeedHistory,
             json_responses::UserStripped,
             json_responses::Games,
+            json_responses::GameSchema,
             json_responses::Coins,
             json_responses::LatestGames,
             json_responses::OneTimeToken,
             json_responses::BillineCreateInvoiceResponse,
             json_responses::PromTokens,
+            json_responses::BetVerification,
+            json_responses::BetRejected,
+            json_responses::BetRejectionReason,
+            json_responses::CrashRoundUpdate,
 
             db_models::User,
             db_models::Coin,
             db_models::Amount,
             db_models::Game,
+            db_models::GameLimits,
             db_models::UserSeed,
             db_models::ServerSeed,
             db_models::Bet,
@@ -118,10 +129,24 @@ use warp::{
         )),
         tags(
             (name = "Core REST API", description = "Core REST API")
-        )
+        ),
+        modifiers(&GameDataSchemas)
     )]
 pub struct ApiDoc;
 
+/// Adds the bet data schemas of all the registered games
+struct GameDataSchemas;
+
+impl Modify for GameDataSchemas {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            for game in GAMES {
+                components.schemas.extend(game.schemas());
+            }
+        }
+    }
+}
+
 pub async fn serve_swagger(
     full_path: FullPath,
     tail: Tail,
@@ -0,0 +1,273 @@
+use crate::communication::*;
+use crate::games::registry::{find_game, GameParser};
+use crate::models::db_models::AutoBet;
+use crate::models::json_requests::{AutoBetStart, AutoBetStrategy, PropagatedBet};
+use crate::models::json_responses::BetRejectionReason;
+use crate::DB;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::cmp::Ordering;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// Max amount of bets of one session
+pub const MAX_AUTO_BETS: i64 = 10_000;
+/// Amount of the latest sessions returned to the player
+pub const AUTO_BETS_HISTORY: i64 = 20;
+/// Pause between the bets of a session
+const AUTO_BET_INTERVAL: Duration = Duration::from_millis(500);
+
+impl AutoBetStrategy {
+    /// Stake of the next bet after the bet played with `amount` paid out `payout`, a push
+    /// keeps the stake
+    pub fn next_amount(&self, base_amount: Decimal, amount: Decimal, payout: Decimal) -> Decimal {
+        let won = match payout.cmp(&amount) {
+            Ordering::Equal => return amount,
+            Ordering::Greater => true,
+            Ordering::Less => false,
+        };
+        match self {
+            AutoBetStrategy::Fixed => base_amount,
+            AutoBetStrategy::Martingale if won => base_amount,
+            AutoBetStrategy::Martingale => amount * Decimal::TWO,
+            AutoBetStrategy::ReverseMartingale if won => amount * Decimal::TWO,
+            AutoBetStrategy::ReverseMartingale => base_amount,
+            AutoBetStrategy::IncreaseOnLoss { .. } if won => base_amount,
+            AutoBetStrategy::IncreaseOnLoss { percent } => {
+                (amount * (Decimal::ONE_HUNDRED + percent) / Decimal::ONE_HUNDRED)
+                    .round_dp_with_strategy(4, RoundingStrategy::ToZero)
+            }
+        }
+    }
+}
+
+/// Validates the request and records the new session of the player
+pub async fn start_session(
+    db: &DB,
+    request: &AutoBetStart,
+) -> Result<AutoBet, (BetRejectionReason, String)> {
+    if request.amount <= Decimal::ZERO {
+        return Err((
+            BetRejectionReason::InvalidBet,
+            "The stake should be positive".into(),
+        ));
+    }
+
+    if !(1..=MAX_AUTO_BETS).contains(&request.max_bets) {
+        return Err((
+            BetRejectionReason::InvalidBet,
+            format!("The amount of bets should be from 1 to {}", MAX_AUTO_BETS),
+        ));
+    }
+
+    if request.stop_win < Decimal::ZERO || request.stop_loss > Decimal::ZERO {
+        return Err((
+            BetRejectionReason::InvalidBet,
+            "Stop on profit can't be negative and stop on loss can't be positive".into(),
+        ));
+    }
+
+    if matches!(request.strategy, AutoBetStrategy::IncreaseOnLoss { percent } if percent <= Decimal::ZERO)
+    {
+        return Err((
+            BetRejectionReason::InvalidBet,
+            "The increase should be positive".into(),
+        ));
+    }
+
+    let game = match db.fetch_game(request.game_id).await {
+        Ok(Some(game)) => game,
+        _ => {
+            return Err((BetRejectionReason::GameNotFound, "Game not found".into()));
+        }
+    };
+    if !matches!(
+        find_game(&game.name).map(|game| &game.parser),
+        Some(GameParser::Stateless(_))
+    ) {
+        return Err((
+            BetRejectionReason::InvalidBet,
+            "Only stateless games can be auto played".into(),
+        ));
+    }
+
+    db.new_auto_bet(request, &serde_json::to_string(&request.strategy).unwrap())
+        .await
+        .map_err(|e| {
+            error!("Error starting auto bet session {:?}: {:?}", request, e);
+            (
+                BetRejectionReason::InternalError,
+                "Error starting the session".to_string(),
+            )
+        })?
+        .ok_or((
+            BetRejectionReason::GameAlreadyStarted,
+            "An auto bet session is already running for the game".into(),
+        ))
+}
+
+/// Reason to stop the session before its next bet
+fn limit_reached(session: &AutoBet) -> Option<&'static str> {
+    if session.bets >= session.max_bets {
+        Some("Reached the amount of bets")
+    } else if !session.stop_win.is_zero() && session.profit >= session.stop_win {
+        Some("Reached the stop on profit")
+    } else if !session.stop_loss.is_zero() && session.profit <= session.stop_loss {
+        Some("Reached the stop on loss")
+    } else {
+        None
+    }
+}
+
+/// Plays the bets of the session one by one through the engine until the session is stopped
+///
+/// The session is left active if the engine goes away, so it's resumed after a restart
+async fn run_session(
+    db: DB,
+    engine_sender: EngineBetSender,
+    mut session: AutoBet,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let reason = match serde_json::from_str::<AutoBetStrategy>(&session.strategy) {
+        Ok(strategy) => loop {
+            if !matches!(stop.try_recv(), Err(oneshot::error::TryRecvError::Empty)) {
+                return;
+            }
+
+            if let Some(reason) = limit_reached(&session) {
+                break reason.to_string();
+            }
+
+            let (result_sender, result_receiver) = oneshot::channel();
+            let bet = PropagatedBet {
+                game_id: session.game_id,
+                amount: session.amount,
+                coin_id: session.coin_id,
+                user_id: Some(session.user_id),
+                uuid: Some(session.uuid.clone()),
+                data: session.data.clone(),
+                stop_loss: Decimal::ZERO,
+                stop_win: Decimal::ZERO,
+                num_games: 1,
+            };
+            if engine_sender
+                .send(EnginePropagatedBet::AutoBet(bet, result_sender))
+                .await
+                .is_err()
+            {
+                warn!(
+                    "Engine channel closed, pausing auto bet session {}",
+                    session.id
+                );
+                return;
+            }
+
+            let bet = match result_receiver.await {
+                Ok(Ok(bet)) => bet,
+                Ok(Err((_, message))) => break message,
+                Err(_) => {
+                    warn!("Engine stopped, pausing auto bet session {}", session.id);
+                    return;
+                }
+            };
+
+            session.bets += 1;
+            session.profit += bet.profit - bet.amount;
+            session.amount = strategy.next_amount(session.base_amount, session.amount, bet.profit);
+            match db
+                .update_auto_bet(session.id, session.amount, session.bets, session.profit)
+                .await
+            {
+                Ok(uuid) => session.uuid = uuid,
+                Err(e) => {
+                    error!("Error updating auto bet session {}: {:?}", session.id, e);
+                }
+            }
+
+            sleep(AUTO_BET_INTERVAL).await;
+        },
+        Err(e) => {
+            error!(
+                "Error parsing auto bet strategy `{:?}`: {:?}",
+                session.strategy, e
+            );
+            "Bad strategy".into()
+        }
+    };
+
+    info!("Stopping auto bet session {}: {}", session.id, reason);
+    if let Err(e) = db.stop_auto_bet(session.id, session.user_id, &reason).await {
+        error!("Error stopping auto bet session {}: {:?}", session.id, e);
+    }
+}
+
+/// Runs the auto bet sessions of the players
+///
+/// Sessions are kept in the db, so they go on without the player being connected and are
+/// resumed after a restart. The bets of a session go to the feed the session is bound to, the
+/// feed of the socket that started it or last fetched the sessions
+pub struct AutoBetManager {
+    db: DB,
+    engine_sender: EngineBetSender,
+    autobet_receiver: AutoBetEventReceiver,
+    sessions: HashMap<i64, oneshot::Sender<()>>,
+}
+
+impl AutoBetManager {
+    pub fn new(
+        db: DB,
+        engine_sender: EngineBetSender,
+        autobet_receiver: AutoBetEventReceiver,
+    ) -> Self {
+        Self {
+            db,
+            engine_sender,
+            autobet_receiver,
+            sessions: HashMap::new(),
+        }
+    }
+
+    fn spawn_session(&mut self, session: AutoBet) {
+        // forget the sessions that finished on their own
+        self.sessions.retain(|_, stop| !stop.is_closed());
+
+        let (stop_sender, stop_receiver) = oneshot::channel();
+        self.sessions.insert(session.id, stop_sender);
+        tokio::spawn(run_session(
+            self.db.clone(),
+            self.engine_sender.clone(),
+            session,
+            stop_receiver,
+        ));
+    }
+
+    pub async fn run(mut self) {
+        info!("Starting auto bet manager");
+
+        match self.db.fetch_active_auto_bets().await {
+            Ok(sessions) => {
+                for session in sessions {
+                    info!("Resuming auto bet session {}", session.id);
+                    self.spawn_session(session);
+                }
+            }
+            Err(e) => {
+                error!("Error fetching active auto bet sessions: {:?}", e);
+            }
+        }
+
+        while let Some(event) = self.autobet_receiver.recv().await {
+            match event {
+                AutoBetEvent::Start(session) => self.spawn_session(session),
+                AutoBetEvent::Stop(id) => {
+                    if let Some(stop) = self.sessions.remove(&id) {
+                        let _ = stop.send(());
+                    }
+                }
+            }
+        }
+
+        warn!("Auto bet events channel closed");
+    }
+}
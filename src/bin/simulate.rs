@@ -0,0 +1,363 @@
+//! Monte-Carlo RTP simulator
+//!
+//! Plays a game through the same `GameEng`/`StatefulGameEng` implementations and
+//! random number generation the engines use, and reports the RTP, variance,
+//! hit frequency and max win of the given strategy.
+//!
+//! ```text
+//! simulate --game Dice --params dice.json --data '{"roll_over":true,"multiplier":"2"}'
+//! simulate --game Mines --data '{"num_mines":3,"tiles":[...],"cashout":false}' \
+//!     --continue '{"tiles":[...],"cashout":false}' --continue '{"tiles":null,"cashout":true}'
+//! ```
+//!
+//! Without `--params` the parameters are read from the `Game` table using the
+//! same `DB_*` env variables as the server.
+use std::{env, fs, process};
+
+use backend::config::DatabaseSettings;
+use backend::db::DB;
+use backend::game_engine::{generate_random_numbers, parse_statefull_game, parse_stateless_game};
+use backend::games::{GameEng, StatefulGameEng};
+use backend::models::db_models::GameState;
+use backend::models::json_requests::{ContinueGame, PropagatedBet};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+const USAGE: &str = "Usage: simulate --game <name> --data <bet data json> [options]
+
+Options:
+    --params <file>       Game parameters json, read from the Game table if omitted
+    --continue <json>     Continue data for stateful games, can be repeated.
+                          Steps use the given data in order, the last one is
+                          repeated until the game is finished
+    --rounds <n>          Amount of rounds to play [default: 1000000]
+    --amount <decimal>    Stake of every round [default: 1]
+    --max-steps <n>       Steps after which a stateful round is abandoned [default: 100]
+    --client-seed <seed>  Client seed used for the random numbers [default: simulate]
+    --server-seed <seed>  Server seed used for the random numbers [default: simulate]";
+
+struct Args {
+    game: String,
+    params: Option<String>,
+    data: String,
+    continue_data: Vec<String>,
+    rounds: u64,
+    amount: Decimal,
+    max_steps: u64,
+    client_seed: String,
+    server_seed: String,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut game = None;
+        let mut params = None;
+        let mut data = None;
+        let mut continue_data = Vec::new();
+        let mut rounds = 1_000_000;
+        let mut amount = Decimal::ONE;
+        let mut max_steps = 100;
+        let mut client_seed = String::from("simulate");
+        let mut server_seed = String::from("simulate");
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(String::new());
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for `{}`", arg))?;
+            match arg.as_str() {
+                "--game" => game = Some(value),
+                "--params" => {
+                    params = Some(
+                        fs::read_to_string(&value)
+                            .map_err(|e| format!("Error reading `{}`: {}", value, e))?,
+                    )
+                }
+                "--data" => data = Some(value),
+                "--continue" => continue_data.push(value),
+                "--rounds" => {
+                    rounds = value
+                        .parse()
+                        .map_err(|e| format!("Bad `--rounds` value: {}", e))?
+                }
+                "--amount" => {
+                    amount = value
+                        .parse()
+                        .map_err(|e| format!("Bad `--amount` value: {}", e))?
+                }
+                "--max-steps" => {
+                    max_steps = value
+                        .parse()
+                        .map_err(|e| format!("Bad `--max-steps` value: {}", e))?
+                }
+                "--client-seed" => client_seed = value,
+                "--server-seed" => server_seed = value,
+                _ => return Err(format!("Unknown argument `{}`", arg)),
+            }
+        }
+
+        Ok(Self {
+            game: game.ok_or("Missing `--game`")?,
+            params,
+            data: data.ok_or("Missing `--data`")?,
+            continue_data,
+            rounds,
+            amount,
+            max_steps,
+            client_seed,
+            server_seed,
+        })
+    }
+}
+
+/// Accumulated results of the simulated rounds
+#[derive(Default)]
+struct Stats {
+    rounds: u64,
+    abandoned: u64,
+    hits: u64,
+    wagered: Decimal,
+    returned: Decimal,
+    max_win: Decimal,
+    // running mean and sum of squared deviations of the round multiplier
+    mean: f64,
+    m2: f64,
+}
+
+impl Stats {
+    fn add_round(&mut self, wagered: Decimal, returned: Decimal) {
+        self.rounds += 1;
+        self.wagered += wagered;
+        self.returned += returned;
+        if !returned.is_zero() {
+            self.hits += 1;
+        }
+        if returned > self.max_win {
+            self.max_win = returned;
+        }
+
+        let multiplier = (returned / wagered).to_f64().unwrap_or_default();
+        let delta = multiplier - self.mean;
+        self.mean += delta / self.rounds as f64;
+        self.m2 += delta * (multiplier - self.mean);
+    }
+
+    fn report(&self, amount: Decimal) {
+        let rounds = self.rounds as f64;
+        let variance = if self.rounds > 1 {
+            self.m2 / (rounds - 1.0)
+        } else {
+            0.0
+        };
+        let rtp = if self.wagered.is_zero() {
+            0.0
+        } else {
+            (self.returned / self.wagered).to_f64().unwrap_or_default()
+        };
+        let margin = 1.96 * (variance / rounds).sqrt();
+
+        println!("Rounds:        {}", self.rounds);
+        println!("Abandoned:     {}", self.abandoned);
+        println!("Wagered:       {}", self.wagered.normalize());
+        println!("Returned:      {}", self.returned.normalize());
+        println!(
+            "RTP:           {:.4}% (±{:.4}% at 95%)",
+            rtp * 100.0,
+            margin * 100.0
+        );
+        println!("House edge:    {:.4}%", (1.0 - rtp) * 100.0);
+        println!("Variance:      {:.6}", variance);
+        println!("Std deviation: {:.6}", variance.sqrt());
+        println!(
+            "Hit frequency: {:.4}%",
+            self.hits as f64 / rounds.max(1.0) * 100.0
+        );
+        println!(
+            "Max win:       {} ({}x)",
+            self.max_win.normalize(),
+            (self.max_win / amount).normalize()
+        );
+    }
+}
+
+fn bet(args: &Args) -> PropagatedBet {
+    PropagatedBet {
+        game_id: 0,
+        amount: args.amount,
+        coin_id: 0,
+        user_id: None,
+        uuid: None,
+        data: args.data.clone(),
+        stop_loss: Decimal::ZERO,
+        stop_win: Decimal::ZERO,
+        num_games: 1,
+    }
+}
+
+fn simulate_stateless(game_eng: &dyn GameEng, args: &Args) -> Result<Stats, String> {
+    let bet = bet(args);
+    let mut stats = Stats::default();
+
+    for nonce in 0..args.rounds {
+        let random_numbers = generate_random_numbers(
+            &args.client_seed,
+            &args.server_seed,
+            nonce,
+            game_eng.numbers_per_bet(),
+        );
+        let game_result = game_eng
+            .play(&bet, &random_numbers)
+            .ok_or_else(|| format!("The game rejected the bet data `{}`", bet.data))?;
+
+        stats.add_round(bet.amount, game_result.total_profit);
+    }
+
+    Ok(stats)
+}
+
+fn simulate_stateful(game_eng: &dyn StatefulGameEng, args: &Args) -> Result<Stats, String> {
+    let bet = bet(args);
+    let mut stats = Stats::default();
+    let mut nonce = 0;
+
+    for _ in 0..args.rounds {
+        let random_numbers = generate_random_numbers(
+            &args.client_seed,
+            &args.server_seed,
+            nonce,
+            game_eng.numbers_per_bet(),
+        );
+        nonce += 1;
+
+        let mut game_result = game_eng
+            .start_playing(&bet, &random_numbers)
+            .ok_or_else(|| format!("The game rejected the bet data `{}`", bet.data))?;
+        let mut state = GameState {
+            amount: bet.amount,
+            bet_info: bet.data.clone(),
+            ..Default::default()
+        };
+
+        let mut step = 0;
+        while !game_result.finished {
+            let data = if let Some(data) = args
+                .continue_data
+                .get(step)
+                .or_else(|| args.continue_data.last())
+            {
+                data
+            } else {
+                return Err(String::from(
+                    "The game is not finished after the first step, provide `--continue` data",
+                ));
+            };
+
+            if step as u64 >= args.max_steps {
+                break;
+            }
+
+            state.state = game_result.data;
+            let random_numbers = generate_random_numbers(
+                &args.client_seed,
+                &args.server_seed,
+                nonce,
+                game_eng.numbers_per_bet(),
+            );
+            nonce += 1;
+
+            game_result = game_eng
+                .continue_playing(
+                    &state,
+                    &ContinueGame {
+                        game_id: 0,
+                        coin_id: 0,
+                        user_id: None,
+                        uuid: None,
+                        data: data.clone(),
+                    },
+                    &random_numbers,
+                )
+                .ok_or_else(|| format!("The game rejected the continue data `{}`", data))?;
+            step += 1;
+        }
+
+        if game_result.finished {
+            stats.add_round(bet.amount, game_result.total_profit);
+        } else {
+            stats.abandoned += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+async fn fetch_params(game: &str) -> Result<String, String> {
+    dotenvy::dotenv().ok();
+    let var = |name: &str| env::var(name).map_err(|_| format!("Missing env variable `{}`", name));
+    let db = DB::new(&DatabaseSettings {
+        username: var("DB_USERNAME")?,
+        password: var("DB_PASSWORD")?,
+        host: var("DB_HOST")?,
+        port: var("DB_PORT")?
+            .parse()
+            .map_err(|e| format!("Bad `DB_PORT`: {}", e))?,
+        database_name: var("DB_NAME")?,
+    })
+    .await;
+
+    db.fetch_all_games()
+        .await
+        .map_err(|e| format!("Error fetching games: {}", e))?
+        .into_iter()
+        .find(|g| g.name == game)
+        .map(|g| g.parameters)
+        .ok_or_else(|| format!("Game `{}` was not found", game))
+}
+
+async fn run(args: Args) -> Result<Stats, String> {
+    let params = match &args.params {
+        Some(params) => params.clone(),
+        None => fetch_params(&args.game).await?,
+    };
+
+    if let Some(game_eng) = parse_stateless_game(&args.game, &params)
+        .map_err(|e| format!("Error parsing `{}` parameters: {}", args.game, e))?
+    {
+        return simulate_stateless(game_eng.as_ref(), &args);
+    }
+
+    if let Some(game_eng) = parse_statefull_game(&args.game, &params)
+        .map_err(|e| format!("Error parsing `{}` parameters: {}", args.game, e))?
+    {
+        return simulate_stateful(game_eng.as_ref(), &args);
+    }
+
+    Err(format!("Unknown game `{}`", args.game))
+}
+
+#[tokio::main]
+async fn main() {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{}\n", e);
+            }
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    };
+    let amount = args.amount;
+
+    match run(args).await {
+        Ok(stats) => stats.report(amount),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
@@ -1,12 +1,21 @@
 use crate::db::DB;
-use crate::models::db_models::{GameState, Invoice};
-use crate::models::json_requests::{ChatMessage, ContinueGame, PropagatedBet};
-use crate::models::json_responses::{BetExpanded, PropagatedChatMessage};
+use crate::metrics::{STATEFUL_ENGINE_QUEUE_DEPTH, WS_FEEDS, WS_SUBSCRIPTIONS};
+use crate::models::db_models::{AutoBet, GameState, Invoice, Jackpot, JackpotWin};
+use crate::models::json_requests::{
+    ChatMessage, ContinueGame, CrashBet, CrashCashout, PropagatedBet,
+};
+use crate::models::json_responses::{
+    BetExpanded, BetRejected, BetRejectionReason, CrashRoundUpdate, PropagatedChatMessage,
+};
 use crate::{errors::ManagerError, models::json_requests::WebsocketsIncommingMessage};
 pub use async_channel::{Receiver, Sender};
+use std::collections::hash_map::DefaultHasher;
 pub use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
+use tokio::sync::mpsc::error::SendError;
 pub use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
 use tracing::{debug, error, info};
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
@@ -14,6 +23,8 @@ pub enum ChannelType {
     Bets(i64),
     ChatRoom(i64),
     Invoice(i64),
+    CrashRound,
+    Jackpot,
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +34,10 @@ pub enum WsData {
     StateUpdate(GameState),
     NewMessage(PropagatedChatMessage),
     Invoice(Invoice),
+    BetRejected(BetRejected),
+    CrashRound(CrashRoundUpdate),
+    Jackpot(Jackpot),
+    JackpotWin(JackpotWin),
 }
 
 pub type WsDataFeedReceiver = UnboundedReceiver<WsData>;
@@ -31,9 +46,15 @@ pub type WsDataFeedSender = UnboundedSender<WsData>;
 pub type WsEventReceiver = UnboundedReceiver<WebsocketsIncommingMessage>;
 pub type WsEventSender = UnboundedSender<WebsocketsIncommingMessage>;
 
+pub type AutoBetResultSender = oneshot::Sender<Result<BetExpanded, (BetRejectionReason, String)>>;
+
 pub enum EnginePropagatedBet {
     NewBet(PropagatedBet),
+    /// Bet of an auto bet session, the result is sent back to the session
+    AutoBet(PropagatedBet, AutoBetResultSender),
     ContinueGame(ContinueGame),
+    /// Resolves the game of the expired state on behalf of the player
+    ResolveGame(GameState),
 }
 
 pub type EngineBetReciever = Receiver<EnginePropagatedBet>;
@@ -42,6 +63,69 @@ pub type EngineBetSender = Sender<EnginePropagatedBet>;
 pub type StatefulEngineBetReciever = UnboundedReceiver<EnginePropagatedBet>;
 pub type StatefulEngineBetSender = UnboundedSender<EnginePropagatedBet>;
 
+impl EnginePropagatedBet {
+    /// The game state the bet is played on
+    pub fn state_key(&self) -> (Option<i64>, i64, i64) {
+        match self {
+            EnginePropagatedBet::NewBet(bet) | EnginePropagatedBet::AutoBet(bet, _) => {
+                (bet.user_id, bet.game_id, bet.coin_id)
+            }
+            EnginePropagatedBet::ContinueGame(bet) => (bet.user_id, bet.game_id, bet.coin_id),
+            EnginePropagatedBet::ResolveGame(state) => {
+                (Some(state.user_id), state.game_id, state.coin_id)
+            }
+        }
+    }
+}
+
+/// Partitions the stateful bets between the stateful engines by the game
+/// state they are played on, so the steps of one game are processed in order
+#[derive(Debug, Clone)]
+pub struct StatefulEngineBetRouter {
+    senders: Vec<StatefulEngineBetSender>,
+}
+
+impl StatefulEngineBetRouter {
+    pub fn new(senders: Vec<StatefulEngineBetSender>) -> Self {
+        assert!(
+            !senders.is_empty(),
+            "At least one stateful engine is required"
+        );
+        Self { senders }
+    }
+
+    pub fn send(&self, bet: EnginePropagatedBet) -> Result<(), SendError<EnginePropagatedBet>> {
+        let mut hasher = DefaultHasher::new();
+        bet.state_key().hash(&mut hasher);
+        let shard = (hasher.finish() % self.senders.len() as u64) as usize;
+
+        // counted before sending, so the engine never sees the bet before it's counted
+        let queue_depth = STATEFUL_ENGINE_QUEUE_DEPTH.with_label_values(&[&shard.to_string()]);
+        queue_depth.inc();
+        self.senders[shard]
+            .send(bet)
+            .inspect_err(|_| queue_depth.dec())
+    }
+}
+
+pub enum CrashEvent {
+    Bet(CrashBet),
+    Cashout(CrashCashout),
+}
+
+pub type CrashEventReceiver = UnboundedReceiver<CrashEvent>;
+pub type CrashEventSender = UnboundedSender<CrashEvent>;
+
+pub enum AutoBetEvent {
+    /// Runs the newly started session
+    Start(AutoBet),
+    /// Stops the session already marked as stopped in the db
+    Stop(i64),
+}
+
+pub type AutoBetEventReceiver = UnboundedReceiver<AutoBetEvent>;
+pub type AutoBetEventSender = UnboundedSender<AutoBetEvent>;
+
 #[derive(Debug)]
 pub enum WsManagerEvent {
     SubscribeFeed {
@@ -76,6 +160,11 @@ pub enum WsManagerEvent {
     PropagateBet(BetExpanded),
     PropagateState(GameState),
     PropagateInvoice(Invoice),
+    RejectBet(BetRejected),
+    PropagateCrashRound(CrashRoundUpdate),
+    PropagateJackpot(Jackpot),
+    /// Announces the win to all the feeds
+    PropagateJackpotWin(JackpotWin),
 }
 
 pub type WsManagerEventReceiver = UnboundedReceiver<WsManagerEvent>;
@@ -86,6 +175,8 @@ pub struct Manager {
     subscriptions_bets: HashMap<i64, HashSet<String>>,
     subscriptions_chat: HashMap<i64, HashSet<String>>,
     subscriptions_invoices: HashMap<i64, HashSet<String>>,
+    subscriptions_crash: HashSet<String>,
+    subscriptions_jackpot: HashSet<String>,
     manager_rx: WsManagerEventReceiver,
 }
 
@@ -102,6 +193,8 @@ impl Manager {
             subscriptions_bets: subscriptions.clone(),
             subscriptions_chat: subscriptions,
             subscriptions_invoices: HashMap::default(),
+            subscriptions_crash: HashSet::default(),
+            subscriptions_jackpot: HashSet::default(),
             manager_rx,
         }
     }
@@ -204,6 +297,79 @@ impl Manager {
         Ok(())
     }
 
+    fn reject_bet(&self, rejected: &BetRejected) -> Result<(), ManagerError> {
+        match self.feeds.get(&rejected.uuid) {
+            Some(feed) => {
+                if let Err(e) = feed.send(WsData::BetRejected(rejected.clone())) {
+                    error!(
+                        "Error propagating bet rejection to feed `{:?}`: `{:?}`",
+                        rejected.uuid, e
+                    );
+                }
+            }
+            None => return Err(ManagerError::FeedDoesntExist(rejected.uuid.clone())),
+        }
+        Ok(())
+    }
+
+    fn propagate_crash_round(&self, update: &CrashRoundUpdate) {
+        for sub in self.subscriptions_crash.iter() {
+            if let Some(feed) = self.feeds.get(sub) {
+                if let Err(e) = feed.send(WsData::CrashRound(update.clone())) {
+                    error!(
+                        "Error propagating crash round to feed `{:?}`: `{:?}`",
+                        sub, e
+                    );
+                }
+            }
+        }
+    }
+
+    fn propagate_jackpot(&self, jackpot: &Jackpot) {
+        for sub in self.subscriptions_jackpot.iter() {
+            if let Some(feed) = self.feeds.get(sub) {
+                if let Err(e) = feed.send(WsData::Jackpot(jackpot.clone())) {
+                    error!("Error propagating jackpot to feed `{:?}`: `{:?}`", sub, e);
+                }
+            }
+        }
+    }
+
+    fn propagate_jackpot_win(&self, win: &JackpotWin) {
+        for (id, feed) in self.feeds.iter() {
+            if let Err(e) = feed.send(WsData::JackpotWin(win.clone())) {
+                error!(
+                    "Error propagating jackpot win to feed `{:?}`: `{:?}`",
+                    id, e
+                );
+            }
+        }
+    }
+
+    fn record_metrics(&self) {
+        WS_FEEDS.set(self.feeds.len() as i64);
+        for (channel, subs) in [
+            (
+                "bets",
+                self.subscriptions_bets.values().map(HashSet::len).sum(),
+            ),
+            (
+                "chat",
+                self.subscriptions_chat.values().map(HashSet::len).sum(),
+            ),
+            (
+                "invoice",
+                self.subscriptions_invoices.values().map(HashSet::len).sum(),
+            ),
+            ("crash", self.subscriptions_crash.len()),
+            ("jackpot", self.subscriptions_jackpot.len()),
+        ] {
+            WS_SUBSCRIPTIONS
+                .with_label_values(&[channel])
+                .set(subs as i64);
+        }
+    }
+
     fn process_event(&mut self, event: &WsManagerEvent) -> Result<(), ManagerError> {
         debug!("Got event: {:?}", event);
         match event {
@@ -214,6 +380,8 @@ impl Manager {
                         self.subscriptions_bets.iter_mut().for_each(|(_, ids)| {
                             ids.remove(id);
                         });
+                        self.subscriptions_crash.remove(id);
+                        self.subscriptions_jackpot.remove(id);
                     }
                     None => {}
                 }
@@ -222,6 +390,8 @@ impl Manager {
                 self.subscriptions_bets.iter_mut().for_each(|(_, ids)| {
                     ids.remove(id);
                 });
+                self.subscriptions_crash.remove(id);
+                self.subscriptions_jackpot.remove(id);
                 self.feeds.remove(id);
             }
             WsManagerEvent::SubscribeChannel { id, channel } => {
@@ -253,6 +423,12 @@ impl Manager {
                             })
                             .or_insert(HashSet::from([id.clone()]));
                     }
+                    ChannelType::CrashRound => {
+                        self.subscriptions_crash.insert(id.to_owned());
+                    }
+                    ChannelType::Jackpot => {
+                        self.subscriptions_jackpot.insert(id.to_owned());
+                    }
                 }
             }
             WsManagerEvent::UnsubscribeChannel { id, channel } => {
@@ -290,6 +466,12 @@ impl Manager {
                             self.subscriptions_invoices.remove(&user_id);
                         }
                     }
+                    ChannelType::CrashRound => {
+                        self.subscriptions_crash.remove(id);
+                    }
+                    ChannelType::Jackpot => {
+                        self.subscriptions_jackpot.remove(id);
+                    }
                 }
             }
             WsManagerEvent::PropagateBet(bet) => {
@@ -338,6 +520,18 @@ impl Manager {
             WsManagerEvent::PropagateInvoice(invoice) => {
                 self.propagate_invoice(invoice)?;
             }
+            WsManagerEvent::RejectBet(rejected) => {
+                self.reject_bet(rejected)?;
+            }
+            WsManagerEvent::PropagateCrashRound(update) => {
+                self.propagate_crash_round(update);
+            }
+            WsManagerEvent::PropagateJackpot(jackpot) => {
+                self.propagate_jackpot(jackpot);
+            }
+            WsManagerEvent::PropagateJackpotWin(win) => {
+                self.propagate_jackpot_win(win);
+            }
         }
         Ok(())
     }
@@ -360,6 +554,7 @@ impl Manager {
             }
 
             events.clear();
+            self.record_metrics();
         }
     }
 }
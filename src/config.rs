@@ -1,6 +1,7 @@
 use std::{env, net::Ipv4Addr};
 
 use lazy_static::lazy_static;
+use rust_decimal::Decimal;
 use serde::Deserialize;
 
 // Env variables
@@ -31,6 +32,15 @@ lazy_static! {
     pub static ref HCAPTCHA_SECRET: String = env::var("HCAPTCHA_SECRET").unwrap();
 
     pub static ref ENGINES: u16 = env::var("ENGINES").unwrap().parse().unwrap();
+    pub static ref STATEFUL_ENGINES: u16 = env::var("STATEFUL_ENGINES").unwrap().parse().unwrap();
+
+    // max payout of a bet in any game, multiplied by the price of the coin
+    pub static ref MAX_PAYOUT: Decimal = env::var("MAX_PAYOUT").unwrap().parse().unwrap();
+
+    // part of every settled stake added to the jackpot pool of the coin
+    pub static ref JACKPOT_SHARE: Decimal = env::var("JACKPOT_SHARE").unwrap().parse().unwrap();
+    // a bet with the stake of the coin price wins the jackpot with `1 / JACKPOT_ODDS` chance
+    pub static ref JACKPOT_ODDS: Decimal = env::var("JACKPOT_ODDS").unwrap().parse().unwrap();
 
     pub static ref GOOGLE_CLIENT_ID: String = env::var("GOOGLE_CLIENT_ID").unwrap();
     pub static ref GOOGLE_SECRET_KEY: String = env::var("GOOGLE_SECRET_KEY").unwrap();
@@ -0,0 +1,766 @@
+use crate::communication::*;
+use crate::errors::CrashError;
+use crate::game_engine::{check_limits, check_payout, reject_bet};
+use crate::metrics;
+use crate::models::db_models::{Bet, CrashChain, Game, GameState};
+use crate::models::json_requests::{CrashBet, CrashCashout};
+use crate::models::json_responses::{BetExpanded, BetRejectionReason, CrashRoundUpdate};
+use crate::tools::{blake_hash, blake_hash_256_u64};
+use crate::DB;
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep_until, Instant, MissedTickBehavior};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+pub const CRASH_GAME_NAME: &str = "Crash";
+
+lazy_static! {
+    static ref U64_RANGE: Decimal = Decimal::from(u64::MAX) + Decimal::ONE;
+    static ref HUNDRED: Decimal = Decimal::from(100);
+}
+
+/// Parameters of the crash game stored in the `Game` table
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CrashParameters {
+    pub house_edge: Decimal,
+    /// Duration of the betting window in milliseconds
+    pub betting_window: u64,
+    /// Interval between the multiplier ticks in milliseconds
+    pub tick: u64,
+    /// Pause between the bust and the next round in milliseconds
+    pub cooldown: u64,
+    /// The multiplier is `e^(growth_rate * ms)` after `ms` milliseconds of the round
+    pub growth_rate: f64,
+    pub max_multiplier: Decimal,
+    /// Amount of rounds in a newly generated hash chain
+    pub chain_length: i64,
+    /// Public salt of a new hash chain, a value nobody knows before the commitment of the chain
+    /// is published, e.g. the hash of a later block
+    ///
+    /// A new chain is not played until a salt not used by the previous chains is set
+    #[serde(default)]
+    pub salt: String,
+}
+
+/// Bet info of a crash bet stored in the game state
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CrashBetInfo {
+    pub auto_cashout: Option<Decimal>,
+}
+
+/// State of a crash bet stored in the game state and the settled bet
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CrashBetState {
+    pub round_id: i64,
+    pub cashout: Option<Decimal>,
+}
+
+/// Calculates the crash point of the round from its hash and the salt of the chain
+///
+/// The round busts as soon as its multiplier reaches the crash point, so a cash out, auto or
+/// manual, only wins at a multiplier below the crash point
+pub fn crash_point(
+    hash: &str,
+    salt: &str,
+    house_edge: Decimal,
+    max_multiplier: Decimal,
+) -> Decimal {
+    crash_multiplier(
+        blake_hash_256_u64(&format!("{}{}", hash, salt)),
+        house_edge,
+        max_multiplier,
+    )
+}
+
+/// Maps the random number uniformly to `u` in `[0, 1)`, the multiplier is
+/// `(1 - house_edge) / (1 - u)` rounded down to 2 decimals, so any multiplier `m` is reached
+/// with `(1 - house_edge) / m` probability
+pub fn crash_multiplier(rng: u64, house_edge: Decimal, max_multiplier: Decimal) -> Decimal {
+    let u = Decimal::from(rng) / *U64_RANGE;
+
+    ((Decimal::ONE - house_edge) / (Decimal::ONE - u))
+        .round_dp_with_strategy(2, RoundingStrategy::ToZero)
+        .max(Decimal::ONE)
+        .min(max_multiplier)
+}
+
+/// Multiplier of the round after `elapsed` time
+pub fn multiplier_at(elapsed: Duration, growth_rate: f64) -> Decimal {
+    Decimal::from_f64((growth_rate * elapsed.as_millis() as f64).exp())
+        .unwrap_or(Decimal::MAX)
+        .round_dp_with_strategy(2, RoundingStrategy::ToZero)
+}
+
+/// Hashes of the rounds, every hash is the blake hash of the next round's hash
+///
+/// The rounds are played from the end of the chain, so revealing the hash of a round proves the
+/// hashes of all the previous rounds and the `commitment` proves the first one
+pub struct HashChain {
+    id: i64,
+    commitment: String,
+    salt: Option<String>,
+    hashes: Vec<String>,
+    position: usize,
+}
+
+impl HashChain {
+    pub fn new(chain: CrashChain) -> Self {
+        let mut hashes = Vec::with_capacity(chain.length as usize);
+        let mut hash = blake_hash(&chain.seed);
+        for _ in 0..chain.length {
+            let next = blake_hash(&hash);
+            hashes.push(hash);
+            hash = next;
+        }
+
+        Self {
+            id: chain.id,
+            commitment: chain.commitment,
+            salt: chain.salt,
+            hashes,
+            position: chain.position as usize,
+        }
+    }
+
+    /// Commitment of the chain generated from the seed
+    pub fn commitment(seed: &str, length: i64) -> String {
+        (0..=length).fold(seed.to_string(), |hash, _| blake_hash(&hash))
+    }
+
+    fn is_used_up(&self) -> bool {
+        self.position >= self.hashes.len()
+    }
+
+    /// Position and hash of the next round
+    fn next_hash(&mut self) -> Option<(i64, String)> {
+        let hash = self
+            .hashes
+            .len()
+            .checked_sub(self.position + 1)
+            .map(|index| self.hashes[index].clone())?;
+        let position = self.position as i64;
+        self.position += 1;
+
+        Some((position, hash))
+    }
+}
+
+struct CrashPlayer {
+    state: GameState,
+    auto_cashout: Option<Decimal>,
+}
+
+fn propagate(manager_sender: &WsManagerEventSender, update: CrashRoundUpdate) {
+    if let Err(e) = manager_sender.send(WsManagerEvent::PropagateCrashRound(update)) {
+        error!("Error propagating crash round: {:?}", e);
+    }
+}
+
+/// Settles the bet of the player, `cashout` is `None` if the bet is lost at `multiplier`
+async fn settle(
+    db: DB,
+    manager_sender: WsManagerEventSender,
+    round_id: i64,
+    player: CrashPlayer,
+    cashout: Option<Decimal>,
+    multiplier: Decimal,
+) {
+    let state = player.state;
+    let profit = cashout
+        .map(|cashout| state.amount * cashout)
+        .unwrap_or_default();
+    let timestamp = chrono::offset::Utc::now();
+    let outcomes = format!(
+        "{:?}",
+        [(multiplier * *HUNDRED).to_u64().unwrap_or_default()]
+    );
+    let profits = format!("{:?}", [profit]);
+    let bet_state = serde_json::to_string(&CrashBetState { round_id, cashout }).unwrap();
+
+    let timer = metrics::SETTLEMENT_DURATION
+        .with_label_values(&["crash"])
+        .start_timer();
+    let settled = db
+        .settle_bet(
+            Decimal::ZERO,
+            profit,
+            &Bet {
+                id: 0,
+                timestamp,
+                amount: state.amount,
+                profit,
+                num_games: 1,
+                outcomes: outcomes.clone(),
+                profits: profits.clone(),
+                bet_info: state.bet_info.clone(),
+                state: Some(bet_state.clone()),
+                steps: None,
+                uuid: state.uuid.clone(),
+                nonce: state.nonce,
+                game_id: state.game_id,
+                user_id: state.user_id,
+                coin_id: state.coin_id,
+                userseed_id: state.userseed_id,
+                serverseed_id: state.serverseed_id,
+            },
+            true,
+            None,
+        )
+        .await;
+    timer.observe_duration();
+    let bet_id = match settled {
+        Ok(Some(settled)) => {
+            metrics::bet_processed(state.game_id);
+            settled.bet_id
+        }
+        Ok(None) => {
+            warn!("Crash bet was already settled: {:?}", state);
+            return;
+        }
+        Err(e) => {
+            error!("Error settling the crash bet {:?}: {:?}", state, e);
+            return;
+        }
+    };
+
+    if let Some(cashout) = cashout {
+        propagate(
+            &manager_sender,
+            CrashRoundUpdate::CashedOut {
+                round_id,
+                user_id: state.user_id,
+                coin_id: state.coin_id,
+                multiplier: cashout,
+                profit,
+            },
+        );
+    }
+
+    let user = if let Ok(Some(user)) = db.fetch_user(state.user_id).await {
+        user
+    } else {
+        error!("Unable to find user: {:?}", state.user_id);
+        return;
+    };
+
+    if let Err(e) = manager_sender.send(WsManagerEvent::PropagateBet(BetExpanded {
+        id: bet_id,
+        timestamp,
+        amount: state.amount,
+        profit,
+        num_games: 1,
+        outcomes,
+        profits,
+        bet_info: state.bet_info,
+        state: Some(bet_state),
+        uuid: state.uuid,
+        game_id: state.game_id,
+        user_id: state.user_id,
+        username: user.username,
+        coin_id: state.coin_id,
+        userseed_id: state.userseed_id,
+        serverseed_id: state.serverseed_id,
+        nonce: state.nonce,
+    })) {
+        error!("Error propagating bet: {:?}", e);
+    }
+}
+
+/// Plays the multiplayer crash rounds
+///
+/// A round accepts bets during the betting window, then the multiplier grows until it reaches
+/// the crash point of the round. Bets are cashed out on request or at their auto cash out
+/// multiplier, the rest is settled as lost at the bust.
+pub struct CrashEngine {
+    db: DB,
+    manager_sender: WsManagerEventSender,
+    crash_receiver: CrashEventReceiver,
+    chain: Option<HashChain>,
+}
+
+impl CrashEngine {
+    pub fn new(
+        db: DB,
+        manager_sender: WsManagerEventSender,
+        crash_receiver: CrashEventReceiver,
+    ) -> Self {
+        Self {
+            db,
+            manager_sender,
+            crash_receiver,
+            chain: None,
+        }
+    }
+
+    fn propagate(&self, update: CrashRoundUpdate) {
+        propagate(&self.manager_sender, update);
+    }
+
+    /// Refunds the bets of the rounds that were not finished before a restart
+    async fn refund_unfinished_bets(&self) -> Result<(), CrashError> {
+        let game = self
+            .db
+            .fetch_game_by_name(CRASH_GAME_NAME)
+            .await
+            .map_err(CrashError::DbError)?
+            .ok_or(CrashError::GameDoesntExist)?;
+
+        for state in self
+            .db
+            .fetch_game_states_by_game(game.id)
+            .await
+            .map_err(CrashError::DbError)?
+        {
+            info!("Refunding unfinished crash bet {:?}", state);
+            self.db
+                .refund_game_state(&state)
+                .await
+                .map_err(CrashError::DbError)?;
+        }
+
+        Ok(())
+    }
+
+    /// Chain id, position and hash of the next round, the salt of a new chain is taken from the
+    /// parameters
+    async fn next_hash(
+        &mut self,
+        params: &CrashParameters,
+    ) -> Result<(i64, i64, String), CrashError> {
+        if self.chain.as_ref().is_none_or(HashChain::is_used_up) {
+            let chain = match self
+                .db
+                .fetch_active_crash_chain()
+                .await
+                .map_err(CrashError::DbError)?
+            {
+                Some(chain) => chain,
+                None => {
+                    let seed = blake_hash(&format!(
+                        "{}{}{}",
+                        Uuid::new_v4(),
+                        chrono::offset::Utc::now(),
+                        *crate::config::PASSWORD_SALT
+                    ));
+                    info!(
+                        "Generating new crash hash chain of length {}",
+                        params.chain_length
+                    );
+                    let chain = self
+                        .db
+                        .new_crash_chain(
+                            &seed,
+                            &HashChain::commitment(&seed, params.chain_length),
+                            params.chain_length,
+                        )
+                        .await
+                        .map_err(CrashError::DbError)?;
+                    info!(
+                        "Crash hash chain with the commitment `{}` waits for a new salt",
+                        chain.commitment
+                    );
+                    chain
+                }
+            };
+            self.chain = Some(HashChain::new(chain));
+        }
+        let chain = self.chain.as_mut().unwrap();
+
+        if chain.salt.is_none() {
+            if params.salt.is_empty()
+                || !self
+                    .db
+                    .set_crash_chain_salt(chain.id, &params.salt)
+                    .await
+                    .map_err(CrashError::DbError)?
+            {
+                return Err(CrashError::SaltNotPublished(chain.commitment.clone()));
+            }
+            info!(
+                "Crash hash chain `{}` is salted with `{}`",
+                chain.commitment, params.salt
+            );
+            chain.salt = Some(params.salt.clone());
+        }
+
+        let (position, hash) = chain.next_hash().ok_or(CrashError::EmptyChain)?;
+
+        Ok((chain.id, position, hash))
+    }
+
+    /// Rejects all the crash events until the deadline
+    async fn idle(&mut self, deadline: Instant, message: &str) -> Result<(), CrashError> {
+        loop {
+            tokio::select! {
+                _ = sleep_until(deadline) => {
+                    return Ok(());
+                }
+                event = self.crash_receiver.recv() => {
+                    let uuid = match event.ok_or(CrashError::ChannelClosed)? {
+                        CrashEvent::Bet(bet) => bet.uuid,
+                        CrashEvent::Cashout(cashout) => cashout.uuid,
+                    };
+                    reject_bet(
+                        &self.manager_sender,
+                        &uuid,
+                        BetRejectionReason::BettingClosed,
+                        message,
+                    );
+                }
+            }
+        }
+    }
+
+    async fn place_bet(
+        &self,
+        game: &Game,
+        params: &CrashParameters,
+        round_id: i64,
+        bet: &CrashBet,
+        players: &mut HashMap<(i64, i64), CrashPlayer>,
+    ) -> Result<(), (BetRejectionReason, String)> {
+        let user_id = bet.user_id.unwrap();
+
+        if players.contains_key(&(user_id, bet.coin_id)) {
+            return Err((
+                BetRejectionReason::GameAlreadyStarted,
+                "The bet is already placed in the round".into(),
+            ));
+        }
+
+        if matches!(bet.auto_cashout, Some(auto_cashout) if auto_cashout <= Decimal::ONE) {
+            return Err((
+                BetRejectionReason::InvalidBet,
+                "Auto cash out should be greater than 1".into(),
+            ));
+        }
+
+        let limits = match self.db.fetch_game_limits(game.id, bet.coin_id).await {
+            Ok(Some(limits)) => limits,
+            _ => {
+                return Err((
+                    BetRejectionReason::LimitsNotFound,
+                    "The game is not available for the coin".into(),
+                ))
+            }
+        };
+        check_limits(&limits, bet.amount, 1)?;
+        // the round busts at the max multiplier at the latest
+        let max_cashout = bet
+            .auto_cashout
+            .unwrap_or(params.max_multiplier)
+            .min(params.max_multiplier);
+        check_payout(&limits, Some(bet.amount * max_cashout))?;
+
+        let user_seed = self
+            .db
+            .fetch_current_user_seed(user_id)
+            .await
+            .map_err(|_| {
+                (
+                    BetRejectionReason::MissingSeed,
+                    "User seed not found".into(),
+                )
+            })?;
+        let server_seed = self
+            .db
+            .fetch_current_server_seed(user_id)
+            .await
+            .map_err(|_| {
+                (
+                    BetRejectionReason::MissingSeed,
+                    "Server seed not found".into(),
+                )
+            })?;
+
+        let state = GameState {
+            id: 0,
+            timestamp: chrono::offset::Utc::now(),
+            amount: bet.amount,
+            bet_info: serde_json::to_string(&CrashBetInfo {
+                auto_cashout: bet.auto_cashout,
+            })
+            .unwrap(),
+            state: serde_json::to_string(&CrashBetState {
+                round_id,
+                cashout: None,
+            })
+            .unwrap(),
+            steps: "[]".into(),
+            uuid: bet.uuid.clone().unwrap(),
+            nonce: 0,
+            game_id: game.id,
+            user_id,
+            coin_id: bet.coin_id,
+            userseed_id: user_seed.id,
+            serverseed_id: server_seed.id,
+        };
+
+        match self.db.start_game_state(bet.amount, &state).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err((
+                    BetRejectionReason::InsufficientBalance,
+                    "Insufficient balance".into(),
+                ))
+            }
+            Err(e) => {
+                error!("Error placing crash bet {:?}: {:?}", bet, e);
+                return Err((
+                    BetRejectionReason::InternalError,
+                    "Error placing the bet".into(),
+                ));
+            }
+        }
+
+        players.insert(
+            (user_id, bet.coin_id),
+            CrashPlayer {
+                state,
+                auto_cashout: bet.auto_cashout,
+            },
+        );
+
+        self.propagate(CrashRoundUpdate::NewBet {
+            round_id,
+            user_id,
+            coin_id: bet.coin_id,
+            amount: bet.amount,
+        });
+
+        Ok(())
+    }
+
+    /// Settles the bet of the player in its own task, so the ticks of the round don't wait for
+    /// the db
+    fn settle(
+        &self,
+        round_id: i64,
+        player: CrashPlayer,
+        cashout: Option<Decimal>,
+        multiplier: Decimal,
+    ) -> JoinHandle<()> {
+        tokio::spawn(settle(
+            self.db.clone(),
+            self.manager_sender.clone(),
+            round_id,
+            player,
+            cashout,
+            multiplier,
+        ))
+    }
+
+    async fn play_round(&mut self) -> Result<(), CrashError> {
+        let game = self
+            .db
+            .fetch_game_by_name(CRASH_GAME_NAME)
+            .await
+            .map_err(CrashError::DbError)?
+            .ok_or(CrashError::GameDoesntExist)?;
+        let params: CrashParameters =
+            serde_json::from_str(&game.parameters).map_err(CrashError::BadParameters)?;
+
+        let (chain_id, position, hash) = self.next_hash(&params).await?;
+        let chain = self.chain.as_ref().unwrap();
+        let chain_commitment = chain.commitment.clone();
+        let chain_salt = chain.salt.clone().unwrap_or_default();
+        let crash_point = crash_point(&hash, &chain_salt, params.house_edge, params.max_multiplier);
+        let round_id = self
+            .db
+            .new_crash_round(chain_id, position, &hash, crash_point)
+            .await
+            .map_err(CrashError::DbError)?;
+
+        // betting
+        self.propagate(CrashRoundUpdate::Betting {
+            round_id,
+            chain_commitment,
+            chain_salt,
+            betting_window: params.betting_window,
+        });
+
+        let mut players: HashMap<(i64, i64), CrashPlayer> = HashMap::new();
+        let betting_deadline = Instant::now() + Duration::from_millis(params.betting_window);
+        loop {
+            tokio::select! {
+                _ = sleep_until(betting_deadline) => {
+                    break;
+                }
+                event = self.crash_receiver.recv() => {
+                    match event.ok_or(CrashError::ChannelClosed)? {
+                        CrashEvent::Bet(bet) => {
+                            if let Err((reason_code, message)) =
+                                self.place_bet(&game, &params, round_id, &bet, &mut players).await
+                            {
+                                reject_bet(&self.manager_sender, &bet.uuid, reason_code, &message);
+                            }
+                        }
+                        CrashEvent::Cashout(cashout) => {
+                            reject_bet(
+                                &self.manager_sender,
+                                &cashout.uuid,
+                                BetRejectionReason::GameNotStarted,
+                                "The round is not started yet",
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // flying
+        self.propagate(CrashRoundUpdate::Started { round_id });
+
+        let mut settlements: Vec<JoinHandle<()>> = Vec::new();
+        let started = Instant::now();
+        let mut ticker = interval(Duration::from_millis(params.tick));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let multiplier = multiplier_at(started.elapsed(), params.growth_rate);
+
+                    let auto_cashouts: Vec<(i64, i64)> = players
+                        .iter()
+                        .filter(|(_, player)| {
+                            matches!(
+                                player.auto_cashout,
+                                Some(auto_cashout)
+                                    if auto_cashout <= multiplier && auto_cashout < crash_point
+                            )
+                        })
+                        .map(|(key, _)| *key)
+                        .collect();
+                    for key in auto_cashouts {
+                        let player = players.remove(&key).unwrap();
+                        let auto_cashout = player.auto_cashout;
+                        settlements.push(self.settle(
+                            round_id,
+                            player,
+                            auto_cashout,
+                            auto_cashout.unwrap(),
+                        ));
+                    }
+
+                    if multiplier >= crash_point {
+                        break;
+                    }
+
+                    self.propagate(CrashRoundUpdate::Tick { round_id, multiplier });
+                }
+                event = self.crash_receiver.recv() => {
+                    match event.ok_or(CrashError::ChannelClosed)? {
+                        CrashEvent::Bet(bet) => {
+                            reject_bet(
+                                &self.manager_sender,
+                                &bet.uuid,
+                                BetRejectionReason::BettingClosed,
+                                "The round is already started",
+                            );
+                        }
+                        CrashEvent::Cashout(cashout) => {
+                            settlements.extend(
+                                self.cash_out(round_id, crash_point, started, &params, &cashout, &mut players)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // bust
+        for (_, player) in players.drain() {
+            settlements.push(self.settle(round_id, player, None, crash_point));
+        }
+
+        self.propagate(CrashRoundUpdate::Busted {
+            round_id,
+            crash_point,
+            hash,
+        });
+
+        // the players can bet in the next round only when their bets are settled
+        let cooldown = Instant::now() + Duration::from_millis(params.cooldown);
+        for settlement in join_all(settlements).await {
+            if let Err(e) = settlement {
+                error!("Crash bet settlement failed: {:?}", e);
+            }
+        }
+
+        self.idle(cooldown, "The next round is not started yet")
+            .await
+    }
+
+    /// Settles the bet of the player at the current multiplier, `None` if it can't be cashed out
+    fn cash_out(
+        &self,
+        round_id: i64,
+        crash_point: Decimal,
+        started: Instant,
+        params: &CrashParameters,
+        cashout: &CrashCashout,
+        players: &mut HashMap<(i64, i64), CrashPlayer>,
+    ) -> Option<JoinHandle<()>> {
+        let multiplier = multiplier_at(started.elapsed(), params.growth_rate);
+        if multiplier >= crash_point {
+            reject_bet(
+                &self.manager_sender,
+                &cashout.uuid,
+                BetRejectionReason::BettingClosed,
+                "The round is busted",
+            );
+            return None;
+        }
+
+        let player =
+            if let Some(player) = players.remove(&(cashout.user_id.unwrap(), cashout.coin_id)) {
+                player
+            } else {
+                reject_bet(
+                    &self.manager_sender,
+                    &cashout.uuid,
+                    BetRejectionReason::GameNotStarted,
+                    "No bet placed in the round",
+                );
+                return None;
+            };
+
+        Some(self.settle(round_id, player, Some(multiplier), multiplier))
+    }
+
+    pub async fn run(mut self) {
+        info!("Starting crash engine");
+
+        if let Err(e) = self.refund_unfinished_bets().await {
+            error!("Error refunding unfinished crash bets: {:?}", e);
+        }
+
+        loop {
+            match self.play_round().await {
+                Ok(()) => {}
+                Err(CrashError::ChannelClosed) => {
+                    warn!("Crash events channel closed");
+                    return;
+                }
+                Err(e) => {
+                    error!("Error playing crash round: {:?}", e);
+                    if let Err(CrashError::ChannelClosed) = self
+                        .idle(
+                            Instant::now() + Duration::from_secs(5),
+                            "The game is not available",
+                        )
+                        .await
+                    {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
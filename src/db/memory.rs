@@ -0,0 +1,1393 @@
+//! Store kept in the memory of the process, used to run the engines and the filters without
+//! a running database
+
+use super::store::{BetStore, GameStateStore, PartnerStore, SeedStore, UserStore, WalletStore};
+use crate::{
+    config,
+    jackpot::is_jackpot_won,
+    models::{
+        db_models::{
+            Amount, Bet, Coin, ConnectedWallet, Game, GameLimits, GameState, Jackpot, JackpotWin,
+            OauthProvider, Partner, PartnerContact, PartnerSite, RefClicks, ReferalLink,
+            RefreshToken, SeedPair, ServerSeed, SettledBet, SiteSubId, TimeBoundaries, User,
+            UserSeed, UserTotals, Withdrawal,
+        },
+        json_requests::WithdrawRequest,
+        json_responses::{AmountConnectedWallets, BetExpanded},
+    },
+    tools::blake_hash,
+};
+
+use chrono::{DateTime, Duration, Months, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+struct RefClick {
+    timestamp: DateTime<Utc>,
+    sub_id_internal: i64,
+    partner_id: i64,
+}
+
+struct ConnectedUser {
+    id: i64,
+    user_id: i64,
+    timestamp: DateTime<Utc>,
+    sub_id_internal: i64,
+    partner_id: i64,
+}
+
+#[derive(Default)]
+struct Tables {
+    sequences: HashMap<&'static str, i64>,
+
+    users: Vec<User>,
+    refresh_tokens: Vec<RefreshToken>,
+    referal_links: Vec<ReferalLink>,
+    referals: Vec<(i64, i64)>,
+
+    coins: Vec<Coin>,
+    amounts: BTreeMap<(i64, i64), Decimal>,
+
+    user_seeds: Vec<UserSeed>,
+    server_seeds: Vec<ServerSeed>,
+    nonces: HashMap<(i64, i64), i64>,
+
+    games: Vec<Game>,
+    game_limits: Vec<GameLimits>,
+    bets: Vec<Bet>,
+    game_states: Vec<GameState>,
+    jackpots: BTreeMap<i64, Decimal>,
+    jackpot_wins: Vec<JackpotWin>,
+
+    partners: Vec<Partner>,
+    partner_contacts: Vec<PartnerContact>,
+    partner_sites: Vec<PartnerSite>,
+    site_subids: Vec<SiteSubId>,
+    clicks: Vec<RefClick>,
+    connected_users: Vec<ConnectedUser>,
+    withdrawals: Vec<Withdrawal>,
+}
+
+impl Tables {
+    /// Next value of the `BIGSERIAL` of the table, starting from 1
+    fn next_id(&mut self, table: &'static str) -> i64 {
+        let id = self.sequences.entry(table).or_insert(0);
+        *id += 1;
+        *id
+    }
+
+    fn username(&self, user_id: i64) -> Option<String> {
+        self.users
+            .iter()
+            .find(|user| user.id == user_id)
+            .map(|user| user.username.clone())
+    }
+
+    fn expand_bet(&self, bet: &Bet) -> Option<BetExpanded> {
+        Some(BetExpanded {
+            id: bet.id,
+            timestamp: bet.timestamp,
+            amount: bet.amount,
+            profit: bet.profit,
+            num_games: bet.num_games,
+            outcomes: bet.outcomes.clone(),
+            profits: bet.profits.clone(),
+            bet_info: bet.bet_info.clone(),
+            state: bet.state.clone(),
+            uuid: bet.uuid.clone(),
+            game_id: bet.game_id,
+            user_id: bet.user_id,
+            username: self.username(bet.user_id)?,
+            coin_id: bet.coin_id,
+            userseed_id: bet.userseed_id,
+            serverseed_id: bet.serverseed_id,
+            nonce: bet.nonce,
+        })
+    }
+
+    fn coin_price(&self, coin_id: i64) -> Option<Decimal> {
+        self.coins
+            .iter()
+            .find(|coin| coin.id == coin_id)
+            .map(|coin| coin.price)
+    }
+
+    fn current_server_seed(&self, user_id: i64) -> Option<&ServerSeed> {
+        self.server_seeds
+            .iter()
+            .find(|seed| seed.user_id == user_id && !seed.revealed)
+    }
+
+    /// Keeps the current seed pair of the user in the history with the nonce `-1` if it wasn't
+    /// played
+    fn keep_current_pair(&mut self, user_id: i64) {
+        let current_user_seed = self
+            .user_seeds
+            .iter()
+            .filter(|seed| seed.user_id == user_id)
+            .max_by_key(|seed| seed.id);
+        if let (Some(user_seed), Some(server_seed)) =
+            (current_user_seed, self.current_server_seed(user_id))
+        {
+            let pair = (user_seed.id, server_seed.id);
+            self.nonces.entry(pair).or_insert(-1);
+        }
+    }
+
+    fn site_subid(&self, partner_id: i64, site_id: i64, sub_id: i64) -> Option<&SiteSubId> {
+        let site = self
+            .partner_sites
+            .iter()
+            .find(|site| site.partner_id == partner_id && site.id == site_id)?;
+        self.site_subids.iter().find(|subid| {
+            subid.site_id == site.internal_id
+                && subid.partner_id == partner_id
+                && subid.id == sub_id
+        })
+    }
+
+    fn connected_users(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> impl Iterator<Item = &ConnectedUser> {
+        let since = boundary_start(time_boundaries);
+        self.connected_users.iter().filter(move |connected| {
+            connected.partner_id == partner_id && since.is_none_or(|t| connected.timestamp > t)
+        })
+    }
+
+    fn count_betted(&self, connected: &[&ConnectedUser]) -> i64 {
+        connected
+            .iter()
+            .map(|connected| connected.user_id)
+            .filter(|user_id| self.bets.iter().any(|bet| bet.user_id == *user_id))
+            .collect::<BTreeSet<_>>()
+            .len() as i64
+    }
+}
+
+fn boundary_start(time_boundaries: TimeBoundaries) -> Option<DateTime<Utc>> {
+    let now = Utc::now();
+    match time_boundaries {
+        TimeBoundaries::Daily => Some(now - Duration::days(1)),
+        TimeBoundaries::Weekly => Some(now - Duration::weeks(1)),
+        TimeBoundaries::Monthly => now.checked_sub_months(Months::new(1)),
+        TimeBoundaries::All => None,
+    }
+}
+
+/// In-memory implementation of the stores, follows the semantics of the queries of `DB`
+///
+/// Coins, games and their limits are not created through the API, so they are added with
+/// `add_coin`, `add_game` and `add_game_limits` before use
+#[derive(Clone, Default)]
+pub struct MemoryDB {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl MemoryDB {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().unwrap()
+    }
+
+    pub fn add_coin(&self, name: &str, price: Decimal) -> Coin {
+        let mut tables = self.tables();
+        let coin = Coin {
+            id: tables.next_id("coin"),
+            name: name.into(),
+            price,
+        };
+        tables.coins.push(coin.clone());
+        coin
+    }
+
+    pub fn add_game(&self, name: &str, parameters: &str, state_timeout: Option<i64>) -> Game {
+        let mut tables = self.tables();
+        let game = Game {
+            id: tables.next_id("game"),
+            name: name.into(),
+            parameters: parameters.into(),
+            state_timeout,
+        };
+        tables.games.push(game.clone());
+        game
+    }
+
+    pub fn add_game_limits(&self, limits: GameLimits) {
+        self.tables().game_limits.push(limits);
+    }
+
+    pub fn fetch_jackpot(&self, coin_id: i64) -> Option<Jackpot> {
+        self.tables().jackpots.get(&coin_id).map(|amount| Jackpot {
+            coin_id,
+            amount: *amount,
+        })
+    }
+}
+
+impl UserStore for MemoryDB {
+    async fn fetch_user(&self, id: i64) -> Result<Option<User>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .users
+            .iter()
+            .find(|user| user.id == id)
+            .cloned())
+    }
+
+    async fn fetch_user_by_login(&self, login: &str) -> Result<Option<User>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .users
+            .iter()
+            .find(|user| user.login == login)
+            .cloned())
+    }
+
+    async fn login_user(&self, login: &str, password: &str) -> Result<Option<User>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .users
+            .iter()
+            .find(|user| user.login == login && user.password == password)
+            .cloned())
+    }
+
+    async fn register_user(
+        &self,
+        login: &str,
+        username: &str,
+        provider: OauthProvider,
+        password_hash: &str,
+    ) -> Result<User, sqlx::Error> {
+        let mut tables = self.tables();
+        if tables.users.iter().any(|user| user.login == login) {
+            return Err(sqlx::Error::Protocol(format!(
+                "User with login `{}` already exists",
+                login
+            )));
+        }
+
+        let user = User {
+            id: tables.next_id("users"),
+            registration_time: Utc::now(),
+            login: login.into(),
+            username: username.into(),
+            password: password_hash.into(),
+            user_level: 1,
+            provider,
+        };
+        tables.users.push(user.clone());
+        Ok(user)
+    }
+
+    async fn change_username(&self, id: i64, username: &str) -> Result<(), sqlx::Error> {
+        if let Some(user) = self.tables().users.iter_mut().find(|user| user.id == id) {
+            user.username = username.into();
+        }
+        Ok(())
+    }
+
+    async fn change_password(&self, id: i64, password_hash: &str) -> Result<(), sqlx::Error> {
+        if let Some(user) = self.tables().users.iter_mut().find(|user| user.id == id) {
+            user.password = password_hash.into();
+        }
+        Ok(())
+    }
+
+    async fn new_refresh_token(&self, user_id: i64, token: &str) -> Result<(), sqlx::Error> {
+        self.tables().refresh_tokens.push(RefreshToken {
+            token: token.into(),
+            creation_date: Utc::now(),
+            user_id,
+        });
+        Ok(())
+    }
+
+    async fn fetch_refresh_token(
+        &self,
+        token: &str,
+        user_id: i64,
+    ) -> Result<RefreshToken, sqlx::Error> {
+        self.tables()
+            .refresh_tokens
+            .iter()
+            .find(|t| t.token == token && t.user_id == user_id)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn remove_refresh_token(&self, token: &str, user_id: i64) -> Result<bool, sqlx::Error> {
+        let mut tables = self.tables();
+        let before = tables.refresh_tokens.len();
+        tables
+            .refresh_tokens
+            .retain(|t| !(t.token == token && t.user_id == user_id));
+        Ok(tables.refresh_tokens.len() != before)
+    }
+
+    async fn create_referal_link(&self, refer_to: i64, link_name: &str) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        if let Some(link) = tables
+            .referal_links
+            .iter_mut()
+            .find(|link| link.refer_to == refer_to)
+        {
+            link.link_name = link_name.into();
+        } else {
+            let id = tables.next_id("referal");
+            tables.referal_links.push(ReferalLink {
+                id,
+                refer_to,
+                link_name: link_name.into(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn fetch_referal_link(&self, link_name: &str) -> Result<ReferalLink, sqlx::Error> {
+        self.tables()
+            .referal_links
+            .iter()
+            .find(|link| link.link_name == link_name)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn new_referal(&self, refer_to: i64, referal: i64) -> Result<(), sqlx::Error> {
+        self.tables().referals.push((refer_to, referal));
+        Ok(())
+    }
+}
+
+impl WalletStore for MemoryDB {
+    async fn fetch_coins(&self) -> Result<Vec<Coin>, sqlx::Error> {
+        Ok(self.tables().coins.clone())
+    }
+
+    async fn fetch_amounts(&self, id: i64) -> Result<Vec<Amount>, sqlx::Error> {
+        let tables = self.tables();
+        Ok(tables
+            .amounts
+            .iter()
+            .filter(|((user_id, _), _)| *user_id == id)
+            .filter_map(|((_, coin_id), amount)| {
+                let coin = tables.coins.iter().find(|coin| coin.id == *coin_id)?;
+                Some(Amount {
+                    name: coin.name.clone(),
+                    amount: *amount,
+                })
+            })
+            .collect())
+    }
+
+    async fn fetch_amount(
+        &self,
+        user_id: i64,
+        coin_id: i64,
+    ) -> Result<Option<Decimal>, sqlx::Error> {
+        self.tables()
+            .amounts
+            .get(&(user_id, coin_id))
+            .map(|amount| Some(*amount))
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn init_amount(
+        &self,
+        user_id: i64,
+        coin_id: i64,
+        amount: Decimal,
+    ) -> Result<(), sqlx::Error> {
+        self.tables().amounts.insert((user_id, coin_id), amount);
+        Ok(())
+    }
+
+    async fn increase_amounts_by_usdt_amount(
+        &self,
+        user_id: i64,
+        amount: &Decimal,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        let prices: HashMap<i64, Decimal> = tables
+            .coins
+            .iter()
+            .map(|coin| (coin.id, coin.price))
+            .collect();
+        for ((user, coin_id), balance) in tables.amounts.iter_mut() {
+            if *user == user_id {
+                *balance += amount * prices.get(coin_id).copied().unwrap_or_default();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SeedStore for MemoryDB {
+    async fn new_user_seed(&self, user_id: i64, seed: &str) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        tables.keep_current_pair(user_id);
+        let id = tables.next_id("userseed");
+        tables.user_seeds.push(UserSeed {
+            id,
+            user_id,
+            user_seed: seed.into(),
+        });
+        Ok(())
+    }
+
+    async fn fetch_current_user_seed(&self, user_id: i64) -> Result<UserSeed, sqlx::Error> {
+        self.tables()
+            .user_seeds
+            .iter()
+            .filter(|seed| seed.user_id == user_id)
+            .max_by_key(|seed| seed.id)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn fetch_user_seed_by_id(&self, seed_id: i64) -> Result<Option<UserSeed>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .user_seeds
+            .iter()
+            .find(|seed| seed.id == seed_id)
+            .cloned())
+    }
+
+    async fn fetch_server_seed_by_id(
+        &self,
+        seed_id: i64,
+    ) -> Result<Option<ServerSeed>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .server_seeds
+            .iter()
+            .find(|seed| seed.id == seed_id)
+            .cloned())
+    }
+
+    async fn fetch_current_server_seed(&self, user_id: i64) -> Result<ServerSeed, sqlx::Error> {
+        let mut seed = self.fetch_current_server_seed_unhashed(user_id).await?;
+        seed.server_seed = blake_hash(&seed.server_seed);
+        Ok(seed)
+    }
+
+    async fn fetch_current_server_seed_unhashed(
+        &self,
+        user_id: i64,
+    ) -> Result<ServerSeed, sqlx::Error> {
+        self.tables()
+            .current_server_seed(user_id)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn next_nonce(&self, userseed_id: i64, serverseed_id: i64) -> Result<i64, sqlx::Error> {
+        let mut tables = self.tables();
+        let nonce = tables
+            .nonces
+            .entry((userseed_id, serverseed_id))
+            .and_modify(|nonce| *nonce += 1)
+            .or_insert(0);
+        Ok(*nonce)
+    }
+
+    async fn new_server_seed(&self, user_id: i64, seed: &str) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        let id = tables.next_id("serverseed");
+        tables.server_seeds.push(ServerSeed {
+            id,
+            user_id,
+            server_seed: seed.into(),
+            revealed: false,
+        });
+        Ok(())
+    }
+
+    async fn fetch_seed_history(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<SeedPair>, sqlx::Error> {
+        let tables = self.tables();
+
+        let mut pairs: BTreeSet<(i64, i64)> = tables
+            .bets
+            .iter()
+            .filter(|bet| bet.user_id == user_id)
+            .map(|bet| (bet.userseed_id, bet.serverseed_id))
+            .collect();
+        pairs.extend(tables.nonces.keys().filter(|(userseed_id, _)| {
+            tables
+                .user_seeds
+                .iter()
+                .any(|seed| seed.id == *userseed_id && seed.user_id == user_id)
+        }));
+        let current_user_seed = tables
+            .user_seeds
+            .iter()
+            .filter(|seed| seed.user_id == user_id)
+            .max_by_key(|seed| seed.id);
+        if let (Some(user_seed), Some(server_seed)) =
+            (current_user_seed, tables.current_server_seed(user_id))
+        {
+            pairs.insert((user_seed.id, server_seed.id));
+        }
+
+        let mut history: Vec<SeedPair> = pairs
+            .into_iter()
+            .filter_map(|(userseed_id, serverseed_id)| {
+                let user_seed = tables.user_seeds.iter().find(|s| s.id == userseed_id)?;
+                let server_seed = tables.server_seeds.iter().find(|s| s.id == serverseed_id)?;
+                let bet_ids: Vec<i64> = tables
+                    .bets
+                    .iter()
+                    .filter(|bet| {
+                        bet.userseed_id == userseed_id && bet.serverseed_id == serverseed_id
+                    })
+                    .map(|bet| bet.id)
+                    .collect();
+                Some(SeedPair {
+                    userseed_id,
+                    user_seed: user_seed.user_seed.clone(),
+                    serverseed_id,
+                    server_seed: server_seed.server_seed.clone(),
+                    revealed: server_seed.revealed,
+                    bets_amount: bet_ids.len() as i64,
+                    bet_ids,
+                })
+            })
+            .collect();
+        history.sort_by(|a, b| {
+            (b.serverseed_id, b.userseed_id).cmp(&(a.serverseed_id, a.userseed_id))
+        });
+
+        Ok(history
+            .into_iter()
+            .skip((page * page_size).max(0) as usize)
+            .take(page_size.max(0) as usize)
+            .collect())
+    }
+
+    async fn reveal_last_seed(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        tables.keep_current_pair(user_id);
+        for seed in tables.server_seeds.iter_mut() {
+            if seed.user_id == user_id {
+                seed.revealed = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BetStore for MemoryDB {
+    async fn fetch_game(&self, game_id: i64) -> Result<Option<Game>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .games
+            .iter()
+            .find(|game| game.id == game_id)
+            .cloned())
+    }
+
+    async fn fetch_game_limits(
+        &self,
+        game_id: i64,
+        coin_id: i64,
+    ) -> Result<Option<GameLimits>, sqlx::Error> {
+        let tables = self.tables();
+        let price = match tables.coin_price(coin_id) {
+            Some(price) => price,
+            None => return Ok(None),
+        };
+        Ok(tables
+            .game_limits
+            .iter()
+            .find(|limits| limits.game_id == game_id && limits.coin_id == coin_id)
+            .map(|limits| GameLimits {
+                max_payout: limits.max_payout.min(*config::MAX_PAYOUT * price),
+                ..limits.clone()
+            }))
+    }
+
+    async fn settle_bet(
+        &self,
+        debit: Decimal,
+        credit: Decimal,
+        bet: &Bet,
+        remove_state: bool,
+        jackpot_roll: Option<u64>,
+    ) -> Result<Option<SettledBet>, sqlx::Error> {
+        let mut tables = self.tables();
+        let key = (bet.user_id, bet.coin_id);
+
+        if !matches!(tables.amounts.get(&key), Some(balance) if *balance >= debit) {
+            return Ok(None);
+        }
+
+        let state_index = tables.game_states.iter().position(|state| {
+            state.game_id == bet.game_id
+                && state.user_id == bet.user_id
+                && state.coin_id == bet.coin_id
+        });
+        if remove_state && state_index.is_none() {
+            return Ok(None);
+        }
+
+        *tables.amounts.get_mut(&key).unwrap() += credit - debit;
+        if let Some(index) = state_index.filter(|_| remove_state) {
+            tables.game_states.remove(index);
+        }
+
+        let bet_id = tables.next_id("bet");
+        tables.bets.push(Bet {
+            id: bet_id,
+            ..bet.clone()
+        });
+
+        let mut settled = SettledBet {
+            bet_id,
+            jackpot: None,
+            jackpot_win: None,
+        };
+
+        let stake = bet.amount * Decimal::from(bet.num_games);
+        if let Some(roll) = jackpot_roll.filter(|_| !stake.is_zero()) {
+            let pool = tables.jackpots.entry(bet.coin_id).or_default();
+            *pool += stake * *config::JACKPOT_SHARE;
+            let mut jackpot = Jackpot {
+                coin_id: bet.coin_id,
+                amount: *pool,
+            };
+
+            let price = tables
+                .coin_price(bet.coin_id)
+                .ok_or(sqlx::Error::RowNotFound)?;
+
+            if !jackpot.amount.is_zero() && is_jackpot_won(roll, stake, price) {
+                *tables.amounts.get_mut(&key).unwrap() += jackpot.amount;
+                tables.jackpots.insert(bet.coin_id, Decimal::ZERO);
+
+                let win = JackpotWin {
+                    id: tables.next_id("jackpotwin"),
+                    timestamp: Utc::now(),
+                    bet_id,
+                    user_id: bet.user_id,
+                    coin_id: bet.coin_id,
+                    amount: jackpot.amount,
+                };
+                tables.jackpot_wins.push(win.clone());
+                settled.jackpot_win = Some(win);
+                jackpot.amount = Decimal::ZERO;
+            }
+
+            settled.jackpot = Some(jackpot);
+        }
+
+        Ok(Some(settled))
+    }
+
+    async fn fetch_bet_by_id(&self, bet_id: i64) -> Result<Option<Bet>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .bets
+            .iter()
+            .find(|bet| bet.id == bet_id)
+            .cloned())
+    }
+
+    async fn fetch_bet(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        uuid: &str,
+        coin_id: i64,
+    ) -> Result<Option<Bet>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .bets
+            .iter()
+            .find(|bet| {
+                bet.game_id == game_id
+                    && bet.user_id == user_id
+                    && bet.uuid == uuid
+                    && bet.num_games == 0
+                    && bet.coin_id == coin_id
+            })
+            .cloned())
+    }
+
+    async fn fetch_bets_for_gamename(
+        &self,
+        game_name: &str,
+        limit: i64,
+    ) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        let tables = self.tables();
+        let game_ids: Vec<i64> = tables
+            .games
+            .iter()
+            .filter(|game| game.name == game_name)
+            .map(|game| game.id)
+            .collect();
+        Ok(tables
+            .bets
+            .iter()
+            .rev()
+            .filter(|bet| game_ids.contains(&bet.game_id))
+            .filter_map(|bet| tables.expand_bet(bet))
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn fetch_all_latest_bets(&self, limit: i64) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        let tables = self.tables();
+        Ok(tables
+            .bets
+            .iter()
+            .rev()
+            .filter_map(|bet| tables.expand_bet(bet))
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn fetch_bets_for_user(
+        &self,
+        user_id: i64,
+        last_id: Option<i64>,
+        page_size: i64,
+    ) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        let tables = self.tables();
+        Ok(tables
+            .bets
+            .iter()
+            .rev()
+            .filter(|bet| bet.user_id == user_id && last_id.is_none_or(|id| bet.id < id))
+            .filter_map(|bet| tables.expand_bet(bet))
+            .take(page_size.max(0) as usize)
+            .collect())
+    }
+
+    async fn fetch_bets_for_user_inc(
+        &self,
+        user_id: i64,
+        last_id: Option<i64>,
+        page_size: i64,
+    ) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        let tables = self.tables();
+        Ok(tables
+            .bets
+            .iter()
+            .filter(|bet| bet.user_id == user_id && last_id.is_none_or(|id| bet.id > id))
+            .filter_map(|bet| tables.expand_bet(bet))
+            .take(page_size.max(0) as usize)
+            .collect())
+    }
+
+    async fn latest_games(&self, user_id: i64) -> Result<Vec<String>, sqlx::Error> {
+        let tables = self.tables();
+        let mut bets: Vec<&Bet> = tables
+            .bets
+            .iter()
+            .filter(|bet| bet.user_id == user_id)
+            .collect();
+        bets.sort_by_key(|bet| std::cmp::Reverse(bet.timestamp));
+        Ok(bets
+            .into_iter()
+            .take(2)
+            .filter_map(|bet| tables.games.iter().find(|game| game.id == bet.game_id))
+            .map(|game| game.name.clone())
+            .collect())
+    }
+
+    async fn fetch_user_totals(&self, user_id: i64) -> Result<UserTotals, sqlx::Error> {
+        let tables = self.tables();
+        let mut totals = UserTotals::default();
+        let mut highest_win: Option<Decimal> = None;
+        for bet in tables.bets.iter().filter(|bet| bet.user_id == user_id) {
+            let price = match tables.coin_price(bet.coin_id) {
+                Some(price) => price,
+                None => continue,
+            };
+            let wagered = bet.amount * Decimal::from(bet.num_games);
+            totals.bets_amount += 1;
+            if wagered > bet.profit {
+                totals.lost_bets += 1;
+            } else {
+                totals.won_bets += 1;
+            }
+            totals.total_wagered_sum += wagered / price;
+            totals.gross_profit += bet.profit / price;
+            highest_win = highest_win.max(Some(bet.profit / price));
+        }
+        totals.net_profit = totals.gross_profit - totals.total_wagered_sum;
+        totals.highest_win = highest_win.unwrap_or_default();
+        Ok(totals)
+    }
+}
+
+impl GameStateStore for MemoryDB {
+    async fn fetch_game_state(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        coin_id: i64,
+    ) -> Result<Option<GameState>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .game_states
+            .iter()
+            .find(|state| {
+                state.game_id == game_id && state.user_id == user_id && state.coin_id == coin_id
+            })
+            .cloned())
+    }
+
+    async fn start_game_state(
+        &self,
+        debit: Decimal,
+        state: &GameState,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tables = self.tables();
+        let key = (state.user_id, state.coin_id);
+
+        if tables.game_states.iter().any(|s| {
+            s.game_id == state.game_id
+                && s.user_id == state.user_id
+                && s.coin_id == state.coin_id
+                && s.userseed_id == state.userseed_id
+                && s.serverseed_id == state.serverseed_id
+        }) {
+            return Err(sqlx::Error::Protocol(format!(
+                "Game state of the game `{}` already exists",
+                state.game_id
+            )));
+        }
+
+        match tables.amounts.get_mut(&key) {
+            Some(balance) if *balance >= debit => *balance -= debit,
+            _ => return Ok(false),
+        }
+
+        let id = tables.next_id("gamestate");
+        tables.game_states.push(GameState {
+            id,
+            timestamp: Utc::now(),
+            ..state.clone()
+        });
+        Ok(true)
+    }
+
+    async fn change_game_state(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        coin_id: i64,
+        debit: Decimal,
+        new_state: &str,
+        steps: &str,
+        nonce: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tables = self.tables();
+
+        let index = match tables.game_states.iter().position(|state| {
+            state.game_id == game_id && state.user_id == user_id && state.coin_id == coin_id
+        }) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+
+        if !debit.is_zero() {
+            match tables.amounts.get_mut(&(user_id, coin_id)) {
+                Some(balance) if *balance >= debit => *balance -= debit,
+                _ => return Ok(false),
+            }
+        }
+
+        let state = &mut tables.game_states[index];
+        state.state = new_state.into();
+        state.steps = steps.into();
+        state.nonce = nonce;
+        state.amount += debit;
+        state.timestamp = Utc::now();
+        Ok(true)
+    }
+
+    async fn refund_game_state(&self, state: &GameState) -> Result<bool, sqlx::Error> {
+        let mut tables = self.tables();
+
+        let index = match tables.game_states.iter().position(|s| s.id == state.id) {
+            Some(index) => index,
+            None => return Ok(false),
+        };
+        tables.game_states.remove(index);
+
+        if let Some(balance) = tables.amounts.get_mut(&(state.user_id, state.coin_id)) {
+            *balance += state.amount;
+        }
+        Ok(true)
+    }
+
+    async fn fetch_game_states_by_game(&self, game_id: i64) -> Result<Vec<GameState>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .game_states
+            .iter()
+            .filter(|state| state.game_id == game_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_expired_game_states(&self) -> Result<Vec<GameState>, sqlx::Error> {
+        let tables = self.tables();
+        let now = Utc::now();
+        Ok(tables
+            .game_states
+            .iter()
+            .filter(|state| {
+                tables
+                    .games
+                    .iter()
+                    .find(|game| game.id == state.game_id)
+                    .and_then(|game| game.state_timeout)
+                    .is_some_and(|timeout| state.timestamp < now - Duration::seconds(timeout))
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+impl PartnerStore for MemoryDB {
+    async fn create_partner(
+        &self,
+        partner: Partner,
+        contacts: &[(String, String)],
+    ) -> Result<i64, sqlx::Error> {
+        let id = {
+            let mut tables = self.tables();
+            if tables.partners.iter().any(|p| p.login == partner.login) {
+                return Err(sqlx::Error::Protocol(format!(
+                    "Partner with login `{}` already exists",
+                    partner.login
+                )));
+            }
+
+            let id = tables.next_id("partner");
+            tables.partners.push(Partner {
+                id,
+                is_verified: false,
+                registration_time: Utc::now(),
+                ..partner
+            });
+            id
+        };
+
+        self.add_partner_contacts(id, contacts).await?;
+
+        Ok(id)
+    }
+
+    async fn get_partner(&self, id: i64) -> Result<Partner, sqlx::Error> {
+        self.tables()
+            .partners
+            .iter()
+            .find(|partner| partner.id == id)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn get_partner_by_login(&self, login: &str) -> Result<Partner, sqlx::Error> {
+        self.tables()
+            .partners
+            .iter()
+            .find(|partner| partner.login == login)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn create_withdraw_request(
+        &self,
+        partner_id: i64,
+        withdraw_request: &WithdrawRequest,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        let id = tables.next_id("withdrawal");
+        tables.withdrawals.push(Withdrawal {
+            id,
+            start_time: Utc::now(),
+            token: withdraw_request.token.clone(),
+            network: withdraw_request.network.clone(),
+            wallet_address: withdraw_request.wallet_address.clone(),
+            status: "waiting".into(),
+            partner_id,
+            amount: withdraw_request.amount.clone(),
+        });
+        Ok(())
+    }
+
+    async fn get_partner_withdrawal_requests(
+        &self,
+        partner: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<Vec<Withdrawal>, sqlx::Error> {
+        let since = boundary_start(time_boundaries);
+        Ok(self
+            .tables()
+            .withdrawals
+            .iter()
+            .filter(|w| w.partner_id == partner && since.is_none_or(|t| w.start_time > t))
+            .cloned()
+            .collect())
+    }
+
+    async fn login_partner(
+        &self,
+        login: &str,
+        password: &str,
+    ) -> Result<Option<Partner>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .partners
+            .iter()
+            .find(|partner| partner.login == login && partner.password == password)
+            .cloned())
+    }
+
+    async fn partner_change_password(
+        &self,
+        partner_id: i64,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tables = self.tables();
+        match tables
+            .partners
+            .iter_mut()
+            .find(|partner| partner.id == partner_id && partner.password == old_password)
+        {
+            Some(partner) => {
+                partner.password = new_password.into();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn add_partner_contacts(
+        &self,
+        partner_id: i64,
+        contacts: &[(String, String)],
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        for (name, url) in contacts {
+            let id = tables.next_id("partnercontact");
+            tables.partner_contacts.push(PartnerContact {
+                id,
+                name: name.clone(),
+                url: url.clone(),
+                partner_id,
+            });
+        }
+        Ok(())
+    }
+
+    async fn delete_partner_contacts(
+        &self,
+        partner_id: i64,
+        contact_ids: &[i64],
+    ) -> Result<(), sqlx::Error> {
+        self.tables().partner_contacts.retain(|contact| {
+            !(contact.partner_id == partner_id && contact_ids.contains(&contact.id))
+        });
+        Ok(())
+    }
+
+    async fn get_partner_contacts(
+        &self,
+        partner_id: i64,
+    ) -> Result<Vec<PartnerContact>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .partner_contacts
+            .iter()
+            .filter(|contact| contact.partner_id == partner_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn add_partner_site(
+        &self,
+        partner_id: i64,
+        url: &str,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        let id = tables
+            .partner_sites
+            .iter()
+            .filter(|site| site.partner_id == partner_id)
+            .map(|site| site.id + 1)
+            .max()
+            .unwrap_or(0);
+        let internal_id = tables.next_id("partnersite");
+        tables.partner_sites.push(PartnerSite {
+            internal_id,
+            id,
+            name: name.into(),
+            url: url.into(),
+            partner_id,
+        });
+        Ok(())
+    }
+
+    async fn get_partner_sites(&self, partner_id: i64) -> Result<Vec<PartnerSite>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .partner_sites
+            .iter()
+            .filter(|site| site.partner_id == partner_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn add_partner_subid(
+        &self,
+        internal_site_id: i64,
+        patner_id: i64,
+        url: &str,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        let id = tables
+            .site_subids
+            .iter()
+            .filter(|subid| subid.site_id == internal_site_id)
+            .map(|subid| subid.id + 1)
+            .max()
+            .unwrap_or(0);
+        let internal_id = tables.next_id("sitesubid");
+        tables.site_subids.push(SiteSubId {
+            internal_id,
+            id,
+            name: name.into(),
+            url: url.into(),
+            site_id: internal_site_id,
+            partner_id: patner_id,
+        });
+        Ok(())
+    }
+
+    async fn get_site_subids(&self, internal_site_id: i64) -> Result<Vec<SiteSubId>, sqlx::Error> {
+        Ok(self
+            .tables()
+            .site_subids
+            .iter()
+            .filter(|subid| subid.site_id == internal_site_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_subid(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+        sub_id: i64,
+    ) -> Result<SiteSubId, sqlx::Error> {
+        self.tables()
+            .site_subid(partner_id, site_id, sub_id)
+            .cloned()
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn get_subid_clicks(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+        sub_id: i64,
+    ) -> Result<RefClicks, sqlx::Error> {
+        let tables = self.tables();
+        let clicks = match tables.site_subid(partner_id, site_id, sub_id) {
+            Some(subid) => tables
+                .clicks
+                .iter()
+                .filter(|click| click.sub_id_internal == subid.internal_id)
+                .count() as i64,
+            None => 0,
+        };
+        Ok(RefClicks { clicks })
+    }
+
+    async fn get_site_clicks(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+    ) -> Result<RefClicks, sqlx::Error> {
+        let tables = self.tables();
+        let clicks = tables
+            .clicks
+            .iter()
+            .filter(|click| click.partner_id == partner_id)
+            .filter(|click| {
+                tables
+                    .site_subids
+                    .iter()
+                    .find(|subid| subid.internal_id == click.sub_id_internal)
+                    .and_then(|subid| {
+                        tables
+                            .partner_sites
+                            .iter()
+                            .find(|site| site.internal_id == subid.site_id)
+                    })
+                    .is_some_and(|site| site.id == site_id)
+            })
+            .count() as i64;
+        Ok(RefClicks { clicks })
+    }
+
+    async fn get_partner_clicks(&self, partner_id: i64) -> Result<RefClicks, sqlx::Error> {
+        let clicks = self
+            .tables()
+            .clicks
+            .iter()
+            .filter(|click| click.partner_id == partner_id)
+            .count() as i64;
+        Ok(RefClicks { clicks })
+    }
+
+    async fn get_partner_connected_wallets_amount_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        let connected_users = self
+            .tables()
+            .connected_users
+            .iter()
+            .filter(|c| c.partner_id == partner_id && c.timestamp >= start && c.timestamp <= end)
+            .count() as i64;
+        Ok(AmountConnectedWallets { connected_users })
+    }
+
+    async fn get_partner_connected_wallets_with_bets_amount_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        let tables = self.tables();
+        let connected: Vec<&ConnectedUser> = tables
+            .connected_users
+            .iter()
+            .filter(|c| c.partner_id == partner_id && c.timestamp >= start && c.timestamp <= end)
+            .collect();
+        Ok(AmountConnectedWallets {
+            connected_users: tables.count_betted(&connected),
+        })
+    }
+
+    async fn get_partner_clicks_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<RefClicks, sqlx::Error> {
+        let clicks = self
+            .tables()
+            .clicks
+            .iter()
+            .filter(|c| c.partner_id == partner_id && c.timestamp >= start && c.timestamp <= end)
+            .count() as i64;
+        Ok(RefClicks { clicks })
+    }
+
+    async fn get_partner_connected_wallets_info(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<Vec<ConnectedWallet>, sqlx::Error> {
+        let tables = self.tables();
+        Ok(tables
+            .connected_users(partner_id, time_boundaries)
+            .filter_map(|connected| {
+                let subid = tables
+                    .site_subids
+                    .iter()
+                    .find(|subid| subid.internal_id == connected.sub_id_internal)?;
+                let site = tables
+                    .partner_sites
+                    .iter()
+                    .find(|site| site.internal_id == subid.site_id)?;
+                Some(ConnectedWallet {
+                    id: connected.id,
+                    user_id: connected.user_id,
+                    timestamp: connected.timestamp,
+                    site_id: site.id,
+                    sub_id: subid.id,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_partner_connected_wallets_with_deposits_amount(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        let tables = self.tables();
+        let connected: Vec<&ConnectedUser> = tables
+            .connected_users(partner_id, time_boundaries)
+            .collect();
+        Ok(AmountConnectedWallets {
+            connected_users: tables.count_betted(&connected),
+        })
+    }
+
+    async fn get_partner_connected_wallets_amount(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        let connected_users = self
+            .tables()
+            .connected_users(partner_id, time_boundaries)
+            .count() as i64;
+        Ok(AmountConnectedWallets { connected_users })
+    }
+
+    async fn add_click(&self, partner_id: i64, sub_id: i64) -> Result<(), sqlx::Error> {
+        self.tables().clicks.push(RefClick {
+            timestamp: Utc::now(),
+            sub_id_internal: sub_id,
+            partner_id,
+        });
+        Ok(())
+    }
+
+    async fn add_ref_wallet(
+        &self,
+        user_id: i64,
+        timestamp: DateTime<Utc>,
+        sub_id_internal: i64,
+        partner_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        let mut tables = self.tables();
+        let id = tables.next_id("connectedusers");
+        tables.connected_users.push(ConnectedUser {
+            id,
+            user_id,
+            timestamp,
+            sub_id_internal,
+            partner_id,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use super::DB;
+use crate::errors::MigrationError;
+
+use sqlx::{
+    migrate::{Migrate, Migrator},
+    Executor,
+};
+use tracing::info;
+
+/// Versioned schema migrations from the `migrations` directory, applied once each
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Seed data applied after the schema on every start, so the scripts must be re-runnable
+///
+/// The scripts only insert the missing rows, the changes of the existing rows go to a new
+/// versioned migration
+const DATA_MIGRATIONS: [(&str, &str); 3] = [
+    ("coins", include_str!("../../migrations/data/01_coins.sql")),
+    ("games", include_str!("../../migrations/data/02_games.sql")),
+    (
+        "game_limits",
+        include_str!("../../migrations/data/03_game_limits.sql"),
+    ),
+];
+
+impl DB {
+    /// Migrates the schema to the latest version and applies the data migrations
+    ///
+    /// Fails without touching the database if it was migrated by a newer binary
+    pub async fn migrate(&self) -> Result<(), MigrationError> {
+        let latest = MIGRATOR
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .unwrap_or_default();
+
+        let mut conn = self
+            .db_pool
+            .acquire()
+            .await
+            .map_err(MigrationError::DbError)?;
+        conn.ensure_migrations_table()
+            .await
+            .map_err(MigrationError::SchemaMigration)?;
+        let applied = conn
+            .list_applied_migrations()
+            .await
+            .map_err(MigrationError::SchemaMigration)?;
+        if let Some(version) = applied
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .filter(|version| *version > latest)
+        {
+            return Err(MigrationError::SchemaAhead {
+                database: version,
+                binary: latest,
+            });
+        }
+        drop(conn);
+
+        MIGRATOR
+            .run(&self.db_pool)
+            .await
+            .map_err(MigrationError::SchemaMigration)?;
+        info!("Database schema is at version `{}`", latest);
+
+        let mut transaction = self
+            .db_pool
+            .begin()
+            .await
+            .map_err(MigrationError::DbError)?;
+        for (name, script) in DATA_MIGRATIONS {
+            transaction
+                .execute(script)
+                .await
+                .map_err(|e| MigrationError::DataMigration(name, e))?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(MigrationError::DbError)?;
+        info!("Data migrations applied");
+
+        Ok(())
+    }
+}
@@ -1,13 +1,15 @@
 use crate::{
-    config::DatabaseSettings,
+    config::{self, DatabaseSettings},
+    jackpot::is_jackpot_won,
     models::{
         db_models::{
-            Amount, Bet, BillineInvoice, BillineInvoiceStatus, Coin, ConnectedWallet, Game,
-            GameState, Invoice, Leaderboard, OauthProvider, Partner, PartnerContact,
-            PartnerProgram, PartnerSite, RefClicks, ReferalLink, RefreshToken, ServerSeed,
-            SiteSubId, TimeBoundaries, Totals, User, UserSeed, UserTotals, Withdrawal,
+            Amount, AutoBet, Bet, BillineInvoice, BillineInvoiceStatus, Coin, ConnectedWallet,
+            CrashChain, Game, GameLimits, GameState, Invoice, Jackpot, JackpotWin, Leaderboard,
+            OauthProvider, Partner, PartnerContact, PartnerProgram, PartnerSite, RefClicks,
+            ReferalLink, RefreshToken, SeedPair, ServerSeed, SettledBet, SiteSubId, TimeBoundaries,
+            Totals, User, UserSeed, UserTotals, Withdrawal,
         },
-        json_requests::WithdrawRequest,
+        json_requests::{AutoBetStart, WithdrawRequest},
         json_responses::{AmountConnectedWallets, BetExpanded},
     },
     tools::blake_hash,
@@ -15,9 +17,18 @@ use crate::{
 
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{
+    postgres::{PgListener, PgPoolOptions},
+    PgPool,
+};
 use tracing::info;
 
+mod memory;
+pub use memory::*;
+mod migrations;
+mod store;
+pub use store::*;
+
 #[derive(Debug, Clone)]
 pub struct DB {
     db_pool: PgPool,
@@ -35,6 +46,15 @@ impl DB {
         Self { db_pool }
     }
 
+    /// Open, idle and max connections of the pool
+    pub fn pool_usage(&self) -> (u32, usize, u32) {
+        (
+            self.db_pool.size(),
+            self.db_pool.num_idle(),
+            self.db_pool.options().get_max_connections(),
+        )
+    }
+
     pub async fn new_payout_request(
         &self,
         user_id: i64,
@@ -84,100 +104,79 @@ impl DB {
         .await
     }
 
-    pub async fn remove_game_state(
-        &self,
-        game_id: i64,
-        user_id: i64,
-        coin_id: i64,
-    ) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            r#"DELETE 
-            FROM GameState
-            WHERE game_id=$1 AND
-                user_id=$2 AND
-                coin_id=$3
-            "#,
-            game_id,
-            user_id,
-            coin_id
-        )
-        .execute(&self.db_pool)
-        .await?;
-        Ok(())
-    }
-
+    /// Updates the state of a started game and debits the stake added by the step, the timestamp
+    /// of the state is the time of the last step
+    ///
+    /// Returns `false` if the balance is not sufficient or the game is not started
     pub async fn change_game_state(
         &self,
         game_id: i64,
         user_id: i64,
         coin_id: i64,
+        debit: Decimal,
         new_state: &str,
+        steps: &str,
+        nonce: i64,
     ) -> Result<bool, sqlx::Error> {
-        Ok(sqlx::query!(
+        let mut tx = self.db_pool.begin().await?;
+
+        if !debit.is_zero()
+            && sqlx::query!(
+                r#"
+                UPDATE Amount
+                SET amount = amount - $3
+                WHERE user_id = $1 AND coin_id = $2 AND amount >= $3
+                "#,
+                user_id,
+                coin_id,
+                debit
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+                == 0
+        {
+            return Ok(false);
+        }
+
+        let updated = sqlx::query!(
             r#"UPDATE GameState
-            SET state=$4
+            SET state=$4, steps=$5, nonce=$6, amount=amount + $7, timestamp=now()
             WHERE game_id=$1 AND user_id=$2 AND coin_id=$3
             "#,
             game_id,
             user_id,
             coin_id,
-            new_state
+            new_state,
+            steps,
+            nonce,
+            debit
         )
-        .execute(&self.db_pool)
+        .execute(&mut *tx)
         .await?
         .rows_affected()
-            > 0)
+            > 0;
+
+        if !updated {
+            return Ok(false);
+        }
+
+        tx.commit().await?;
+
+        Ok(true)
     }
 
-    pub async fn insert_game_state(
-        &self,
-        game_id: i64,
-        user_id: i64,
-        uuid: &str,
-        coin_id: i64,
-        bet_info: &str,
-        new_state: &str,
-        amount: &Decimal,
-        userseed_id: i64,
-        serverseed_id: i64,
-    ) -> Result<bool, sqlx::Error> {
-        Ok(sqlx::query!(
-            r#"INSERT INTO GameState(
-                bet_info,
-                game_id,
-                user_id,
-                uuid,
-                coin_id,
-                amount,
-                userseed_id,
-                serverseed_id,
-                state
-            ) VALUES (
-                $1,
-                $2,
-                $3,
-                $4,
-                $5,
-                $6,
-                $7,
-                $8,
-                $9
-            )
+    pub async fn fetch_bet_by_id(&self, bet_id: i64) -> Result<Option<Bet>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            Bet,
+            r#"SELECT *
+            FROM Bet
+            WHERE id=$1
             "#,
-            bet_info,
-            game_id,
-            user_id,
-            uuid,
-            coin_id,
-            amount,
-            userseed_id,
-            serverseed_id,
-            new_state
+            bet_id
         )
-        .execute(&self.db_pool)
-        .await?
-        .rows_affected()
-            > 0)
+        .fetch_optional(&self.db_pool)
+        .await
     }
 
     pub async fn fetch_bet(
@@ -228,6 +227,7 @@ impl DB {
                 Bet.coin_id,
                 Bet.userseed_id,
                 Bet.serverseed_id,
+                Bet.nonce,
                 Bet.outcomes,
                 Bet.profits
             FROM Bet
@@ -263,6 +263,7 @@ impl DB {
                 Bet.coin_id,
                 Bet.userseed_id,
                 Bet.serverseed_id,
+                Bet.nonce,
                 Bet.outcomes,
                 Bet.profits
             FROM Bet
@@ -649,6 +650,76 @@ impl DB {
         .map(|v| v.amount)
     }
 
+    /// Limits of the game, the max payout is capped by the global `MAX_PAYOUT` in the coin
+    pub async fn fetch_game_limits(
+        &self,
+        game_id: i64,
+        coin_id: i64,
+    ) -> Result<Option<GameLimits>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            GameLimits,
+            r#"
+            SELECT
+                GameLimits.game_id,
+                GameLimits.coin_id,
+                GameLimits.min_bet,
+                GameLimits.max_bet,
+                GameLimits.max_num_games,
+                LEAST(GameLimits.max_payout, $3 * Coin.price) AS max_payout
+            FROM GameLimits
+            INNER JOIN Coin ON Coin.id = GameLimits.coin_id
+            WHERE game_id=$1 AND coin_id=$2
+            "#,
+            game_id,
+            coin_id,
+            *config::MAX_PAYOUT
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+    }
+
+    pub async fn fetch_all_game_limits(&self) -> Result<Vec<GameLimits>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            GameLimits,
+            r#"
+            SELECT
+                GameLimits.game_id,
+                GameLimits.coin_id,
+                GameLimits.min_bet,
+                GameLimits.max_bet,
+                GameLimits.max_num_games,
+                LEAST(GameLimits.max_payout, $1 * Coin.price) AS max_payout
+            FROM GameLimits
+            INNER JOIN Coin ON Coin.id = GameLimits.coin_id
+            "#,
+            *config::MAX_PAYOUT
+        )
+        .fetch_all(&self.db_pool)
+        .await
+    }
+
+    /// Listens to the notifications sent by the `Game` table on any change
+    pub async fn listen_game_changes(&self) -> Result<PgListener, sqlx::Error> {
+        let mut listener = PgListener::connect_with(&self.db_pool).await?;
+        listener.listen("game_changed").await?;
+        Ok(listener)
+    }
+
+    pub async fn fetch_game_by_name(&self, name: &str) -> Result<Option<Game>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            Game,
+            r#"
+            SELECT *
+            FROM Game
+            WHERE name=$1
+            LIMIT 1
+            "#,
+            name
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+    }
+
     pub async fn fetch_game(&self, game_id: i64) -> Result<Option<Game>, sqlx::Error> {
         sqlx::query_as_unchecked!(
             Game,
@@ -810,9 +881,31 @@ impl DB {
         Ok(())
     }
 
+    /// Replaces the current user seed, the replaced pair is kept in the seed history with the
+    /// nonce `-1` if it wasn't played
     pub async fn new_user_seed(&self, user_id: i64, seed: &str) -> Result<(), sqlx::Error> {
         sqlx::query!(
             r#"
+            WITH ReplacedPair AS (
+                INSERT INTO SeedNonce(
+                    userseed_id,
+                    serverseed_id,
+                    nonce
+                )
+                SELECT CurrentUserSeed.id, CurrentServerSeed.id, -1
+                FROM (
+                    SELECT id FROM UserSeed
+                    WHERE user_id = $1
+                    ORDER BY id DESC
+                    LIMIT 1
+                ) AS CurrentUserSeed
+                CROSS JOIN (
+                    SELECT id FROM ServerSeed
+                    WHERE user_id = $1 AND revealed = FALSE
+                    LIMIT 1
+                ) AS CurrentServerSeed
+                ON CONFLICT(userseed_id, serverseed_id) DO NOTHING
+            )
             INSERT INTO UserSeed(
                 user_id,
                 user_seed
@@ -845,6 +938,39 @@ impl DB {
         .await
     }
 
+    pub async fn fetch_user_seed_by_id(
+        &self,
+        seed_id: i64,
+    ) -> Result<Option<UserSeed>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            UserSeed,
+            r#"
+            SELECT * FROM UserSeed
+            WHERE id = $1
+            "#,
+            seed_id,
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+    }
+
+    /// Fetches the server seed as it is stored, without hashing it
+    pub async fn fetch_server_seed_by_id(
+        &self,
+        seed_id: i64,
+    ) -> Result<Option<ServerSeed>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            ServerSeed,
+            r#"
+            SELECT * FROM ServerSeed
+            WHERE id = $1
+            "#,
+            seed_id,
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+    }
+
     pub async fn fetch_current_server_seed(&self, user_id: i64) -> Result<ServerSeed, sqlx::Error> {
         let mut res = sqlx::query_as_unchecked!(
             ServerSeed,
@@ -863,6 +989,53 @@ impl DB {
         Ok(res)
     }
 
+    /// Fetches the current server seed without hashing it, it must not be shown to the user until revealed
+    pub async fn fetch_current_server_seed_unhashed(
+        &self,
+        user_id: i64,
+    ) -> Result<ServerSeed, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            ServerSeed,
+            r#"
+            SELECT * FROM ServerSeed
+            WHERE user_id = $1 AND revealed = FALSE
+            LIMIT 1
+            "#,
+            user_id,
+        )
+        .fetch_one(&self.db_pool)
+        .await
+    }
+
+    /// Returns the next nonce for the pair of seeds, starting from 0
+    pub async fn next_nonce(
+        &self,
+        userseed_id: i64,
+        serverseed_id: i64,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            INSERT INTO SeedNonce(
+                userseed_id,
+                serverseed_id,
+                nonce
+            ) VALUES (
+                $1,
+                $2,
+                0
+            )
+            ON CONFLICT(userseed_id, serverseed_id) DO UPDATE
+            SET nonce = SeedNonce.nonce + 1
+            RETURNING nonce
+            "#,
+            userseed_id,
+            serverseed_id
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .map(|r| r.nonce)
+    }
+
     pub async fn new_server_seed(&self, user_id: i64, seed: &str) -> Result<(), sqlx::Error> {
         sqlx::query!(
             r#"
@@ -885,63 +1058,100 @@ impl DB {
         Ok(())
     }
 
-    pub async fn reveal_last_seed(&self, user_id: i64) -> Result<(), sqlx::Error> {
-        sqlx::query!(
-            r#"
-            UPDATE ServerSeed
-            SET revealed = True
-            WHERE user_id = $1 AND revealed = False
-            "#,
-            user_id,
-        )
-        .execute(&self.db_pool)
-        .await?;
-
-        Ok(())
-    }
-
-    pub async fn decrease_balance(
+    /// Every seed pair the user had, the newest server seeds first
+    pub async fn fetch_seed_history(
         &self,
         user_id: i64,
-        coin_id: i64,
-        amount: Decimal,
-    ) -> Result<bool, sqlx::Error> {
-        let res = sqlx::query!(
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<SeedPair>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            SeedPair,
             r#"
-            UPDATE Amount
-            SET amount = amount - $3
-            WHERE user_id = $1 AND coin_id = $2 and amount >= amount
+            WITH Pairs AS (
+                SELECT userseed_id, serverseed_id
+                FROM Bet
+                WHERE user_id = $1
+                UNION
+                SELECT SeedNonce.userseed_id, SeedNonce.serverseed_id
+                FROM SeedNonce
+                INNER JOIN UserSeed ON UserSeed.id = SeedNonce.userseed_id
+                WHERE UserSeed.user_id = $1
+                UNION
+                SELECT CurrentUserSeed.id, CurrentServerSeed.id
+                FROM (
+                    SELECT id FROM UserSeed
+                    WHERE user_id = $1
+                    ORDER BY id DESC
+                    LIMIT 1
+                ) AS CurrentUserSeed
+                CROSS JOIN (
+                    SELECT id FROM ServerSeed
+                    WHERE user_id = $1 AND revealed = FALSE
+                    LIMIT 1
+                ) AS CurrentServerSeed
+            )
+            SELECT
+                UserSeed.id AS userseed_id,
+                UserSeed.user_seed,
+                ServerSeed.id AS serverseed_id,
+                ServerSeed.server_seed,
+                ServerSeed.revealed,
+                COUNT(Bet.id) AS bets_amount,
+                COALESCE(
+                    ARRAY_AGG(Bet.id ORDER BY Bet.id) FILTER (WHERE Bet.id IS NOT NULL),
+                    '{}'
+                ) AS bet_ids
+            FROM Pairs
+            INNER JOIN UserSeed ON UserSeed.id = Pairs.userseed_id
+            INNER JOIN ServerSeed ON ServerSeed.id = Pairs.serverseed_id
+            LEFT JOIN Bet ON Bet.userseed_id = Pairs.userseed_id AND
+                Bet.serverseed_id = Pairs.serverseed_id
+            GROUP BY UserSeed.id, ServerSeed.id
+            ORDER BY ServerSeed.id DESC, UserSeed.id DESC
+            LIMIT $2
+            OFFSET $3
             "#,
             user_id,
-            coin_id,
-            amount
+            page_size,
+            page * page_size
         )
-        .execute(&self.db_pool)
-        .await?;
-
-        Ok(res.rows_affected() > 0)
+        .fetch_all(&self.db_pool)
+        .await
     }
 
-    pub async fn increase_balance(
-        &self,
-        user_id: i64,
-        coin_id: i64,
-        amount: &Decimal,
-    ) -> Result<bool, sqlx::Error> {
-        let res = sqlx::query!(
+    /// Reveals the current server seed, its pair is kept in the seed history with the nonce
+    /// `-1` if it wasn't played
+    pub async fn reveal_last_seed(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query!(
             r#"
-            UPDATE Amount
-            SET amount = amount + $3
-            WHERE user_id = $1 AND coin_id = $2 
+            WITH Revealed AS (
+                UPDATE ServerSeed
+                SET revealed = True
+                WHERE user_id = $1 AND revealed = False
+                RETURNING id
+            )
+            INSERT INTO SeedNonce(
+                userseed_id,
+                serverseed_id,
+                nonce
+            )
+            SELECT CurrentUserSeed.id, Revealed.id, -1
+            FROM Revealed
+            CROSS JOIN (
+                SELECT id FROM UserSeed
+                WHERE user_id = $1
+                ORDER BY id DESC
+                LIMIT 1
+            ) AS CurrentUserSeed
+            ON CONFLICT(userseed_id, serverseed_id) DO NOTHING
             "#,
             user_id,
-            coin_id,
-            amount
         )
         .execute(&self.db_pool)
         .await?;
 
-        Ok(res.rows_affected() > 0)
+        Ok(())
     }
 
     pub async fn fetch_totals(&self) -> Result<Totals, sqlx::Error> {
@@ -985,6 +1195,7 @@ impl DB {
                 Bet.coin_id,
                 Bet.userseed_id,
                 Bet.serverseed_id,
+                Bet.nonce,
                 Bet.outcomes,
                 Bet.profits
             FROM Bet
@@ -1018,6 +1229,7 @@ impl DB {
                 Bet.coin_id,
                 Bet.userseed_id,
                 Bet.serverseed_id,
+                Bet.nonce,
                 Bet.outcomes,
                 Bet.profits
             FROM Bet
@@ -1059,6 +1271,7 @@ impl DB {
                 Bet.coin_id,
                 Bet.userseed_id,
                 Bet.serverseed_id,
+                Bet.nonce,
                 Bet.outcomes,
                 Bet.profits
             FROM Bet
@@ -1092,6 +1305,7 @@ impl DB {
                 Bet.coin_id,
                 Bet.userseed_id,
                 Bet.serverseed_id,
+                Bet.nonce,
                 Bet.outcomes,
                 Bet.profits
             FROM Bet
@@ -1158,38 +1372,93 @@ impl DB {
         .await
     }
 
-    pub async fn place_bet(
+    /// Debits the stake, credits the payout and inserts the bet in a single transaction
+    ///
+    /// If `remove_state` is set, the game state of the bet is removed in the same transaction.
+    /// With the `jackpot_roll` a share of the stake goes to the jackpot pool of the coin and the
+    /// pool is paid out to the player if the roll wins.
+    /// Returns `None` if the balance is not sufficient or the game state was already removed
+    pub async fn settle_bet(
         &self,
-        amount: Decimal,
-        profit: Decimal,
-        num_games: i32,
-        outcomes: &str,
-        profits: &str,
-        bet_info: &str,
-        state: Option<&str>,
-        uuid: &str,
-        game_id: i64,
-        user_id: i64,
-        coin_id: i64,
-        userseed_id: i64,
-        serverseed_id: i64,
-    ) -> Result<i64, sqlx::Error> {
+        debit: Decimal,
+        credit: Decimal,
+        bet: &Bet,
+        remove_state: bool,
+        jackpot_roll: Option<u64>,
+    ) -> Result<Option<SettledBet>, sqlx::Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let balance = sqlx::query!(
+            r#"
+            SELECT amount
+            FROM Amount
+            WHERE user_id = $1 AND coin_id = $2
+            FOR UPDATE
+            "#,
+            bet.user_id,
+            bet.coin_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .and_then(|r| r.amount);
+
+        if !matches!(balance, Some(balance) if balance >= debit) {
+            return Ok(None);
+        }
+
         sqlx::query!(
             r#"
-            INSERT INTO Bet(
-                amount,
-                profit,
-                num_games,
-                outcomes,
-                profits,
-                bet_info, 
-                uuid,
+            UPDATE Amount
+            SET amount = amount - $3 + $4
+            WHERE user_id = $1 AND coin_id = $2
+            "#,
+            bet.user_id,
+            bet.coin_id,
+            debit,
+            credit
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if remove_state
+            && sqlx::query!(
+                r#"DELETE 
+                FROM GameState
+                WHERE game_id=$1 AND
+                    user_id=$2 AND
+                    coin_id=$3
+                "#,
+                bet.game_id,
+                bet.user_id,
+                bet.coin_id
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+                == 0
+        {
+            return Ok(None);
+        }
+
+        let bet_id = sqlx::query!(
+            r#"
+            INSERT INTO Bet(
+                amount,
+                profit,
+                num_games,
+                outcomes,
+                profits,
+                bet_info, 
+                uuid,
                 game_id,
                 user_id,
                 coin_id,
                 userseed_id,
                 serverseed_id,
-                state
+                state,
+                steps,
+                timestamp,
+                nonce
             ) VALUES (
                 $1,
                 $2,
@@ -1203,26 +1472,581 @@ impl DB {
                 $10,
                 $11,
                 $12,
-                $13
+                $13,
+                $14,
+                $15,
+                $16
             ) RETURNING id
             "#,
-            amount,
-            profit,
-            num_games,
-            outcomes,
-            profits,
-            bet_info,
-            uuid,
-            game_id,
+            bet.amount,
+            bet.profit,
+            bet.num_games,
+            bet.outcomes,
+            bet.profits,
+            bet.bet_info,
+            bet.uuid,
+            bet.game_id,
+            bet.user_id,
+            bet.coin_id,
+            bet.userseed_id,
+            bet.serverseed_id,
+            bet.state,
+            bet.steps,
+            bet.timestamp.naive_utc(),
+            bet.nonce
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .id;
+
+        let mut settled = SettledBet {
+            bet_id,
+            jackpot: None,
+            jackpot_win: None,
+        };
+
+        let stake = bet.amount * Decimal::from(bet.num_games);
+        if let Some(roll) = jackpot_roll.filter(|_| !stake.is_zero()) {
+            let mut jackpot = sqlx::query_as_unchecked!(
+                Jackpot,
+                r#"
+                INSERT INTO Jackpot(
+                    coin_id,
+                    amount
+                ) VALUES (
+                    $1,
+                    $2
+                )
+                ON CONFLICT (coin_id) DO UPDATE
+                SET amount = Jackpot.amount + EXCLUDED.amount
+                RETURNING coin_id, amount
+                "#,
+                bet.coin_id,
+                stake * *config::JACKPOT_SHARE
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+
+            let price = sqlx::query!(
+                r#"
+                SELECT price
+                FROM Coin
+                WHERE id = $1
+                "#,
+                bet.coin_id
+            )
+            .fetch_one(&mut *tx)
+            .await?
+            .price;
+
+            if !jackpot.amount.is_zero() && is_jackpot_won(roll, stake, price) {
+                sqlx::query!(
+                    r#"
+                    UPDATE Amount
+                    SET amount = amount + $3
+                    WHERE user_id = $1 AND coin_id = $2
+                    "#,
+                    bet.user_id,
+                    bet.coin_id,
+                    jackpot.amount
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query!(
+                    r#"
+                    UPDATE Jackpot
+                    SET amount = 0
+                    WHERE coin_id = $1
+                    "#,
+                    bet.coin_id
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                settled.jackpot_win = Some(
+                    sqlx::query_as_unchecked!(
+                        JackpotWin,
+                        r#"
+                        INSERT INTO JackpotWin(
+                            bet_id,
+                            user_id,
+                            coin_id,
+                            amount
+                        ) VALUES (
+                            $1,
+                            $2,
+                            $3,
+                            $4
+                        )
+                        RETURNING *
+                        "#,
+                        bet_id,
+                        bet.user_id,
+                        bet.coin_id,
+                        jackpot.amount
+                    )
+                    .fetch_one(&mut *tx)
+                    .await?,
+                );
+                jackpot.amount = Decimal::ZERO;
+            }
+
+            settled.jackpot = Some(jackpot);
+        }
+
+        tx.commit().await?;
+
+        Ok(Some(settled))
+    }
+
+    pub async fn fetch_jackpots(&self) -> Result<Vec<Jackpot>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            Jackpot,
+            r#"
+            SELECT coin_id, amount
+            FROM Jackpot
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+    }
+
+    /// Debits the stake and inserts the state of a started game in a single transaction
+    ///
+    /// Returns `false` if the balance is not sufficient
+    pub async fn start_game_state(
+        &self,
+        debit: Decimal,
+        state: &GameState,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let balance = sqlx::query!(
+            r#"
+            SELECT amount
+            FROM Amount
+            WHERE user_id = $1 AND coin_id = $2
+            FOR UPDATE
+            "#,
+            state.user_id,
+            state.coin_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .and_then(|r| r.amount);
+
+        if !matches!(balance, Some(balance) if balance >= debit) {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE Amount
+            SET amount = amount - $3
+            WHERE user_id = $1 AND coin_id = $2
+            "#,
+            state.user_id,
+            state.coin_id,
+            debit
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"INSERT INTO GameState(
+                bet_info,
+                game_id,
+                user_id,
+                uuid,
+                coin_id,
+                amount,
+                userseed_id,
+                serverseed_id,
+                state,
+                steps,
+                nonce
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6,
+                $7,
+                $8,
+                $9,
+                $10,
+                $11
+            )
+            "#,
+            state.bet_info,
+            state.game_id,
+            state.user_id,
+            state.uuid,
+            state.coin_id,
+            state.amount,
+            state.userseed_id,
+            state.serverseed_id,
+            state.state,
+            state.steps,
+            state.nonce
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Removes the game state and returns its amount to the balance of the user
+    ///
+    /// Returns `false` if the game state was already removed
+    pub async fn refund_game_state(&self, state: &GameState) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let removed = sqlx::query!(
+            r#"
+            DELETE FROM GameState
+            WHERE id = $1
+            "#,
+            state.id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !removed {
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE Amount
+            SET amount = amount + $3
+            WHERE user_id = $1 AND coin_id = $2
+            "#,
+            state.user_id,
+            state.coin_id,
+            state.amount
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    pub async fn fetch_game_states_by_game(
+        &self,
+        game_id: i64,
+    ) -> Result<Vec<GameState>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            GameState,
+            r#"SELECT *
+            FROM GameState
+            WHERE game_id=$1
+            "#,
+            game_id
+        )
+        .fetch_all(&self.db_pool)
+        .await
+    }
+
+    /// Fetches the states of the games that were not finished within the timeout of the game
+    pub async fn fetch_expired_game_states(&self) -> Result<Vec<GameState>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            GameState,
+            r#"SELECT GameState.*
+            FROM GameState
+            INNER JOIN Game ON Game.id = GameState.game_id
+            WHERE Game.state_timeout IS NOT NULL AND
+                GameState.timestamp < now() - make_interval(secs => Game.state_timeout)
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+    }
+
+    /// Fetches the latest hash chain of the crash rounds that is not used up yet
+    pub async fn fetch_active_crash_chain(&self) -> Result<Option<CrashChain>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            CrashChain,
+            r#"
+            SELECT id, seed, commitment, length, position, salt
+            FROM CrashChain
+            WHERE position < length
+            ORDER BY id DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+    }
+
+    pub async fn new_crash_chain(
+        &self,
+        seed: &str,
+        commitment: &str,
+        length: i64,
+    ) -> Result<CrashChain, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            CrashChain,
+            r#"
+            INSERT INTO CrashChain(
+                seed,
+                commitment,
+                length
+            ) VALUES (
+                $1,
+                $2,
+                $3
+            )
+            RETURNING id, seed, commitment, length, position, salt
+            "#,
+            seed,
+            commitment,
+            length
+        )
+        .fetch_one(&self.db_pool)
+        .await
+    }
+
+    /// Sets the salt of the crash chain, returns `false` if the salt is used by another chain
+    pub async fn set_crash_chain_salt(
+        &self,
+        chain_id: i64,
+        salt: &str,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(sqlx::query!(
+            r#"
+            UPDATE CrashChain
+            SET salt = $2
+            WHERE id = $1 AND salt IS NULL AND NOT EXISTS (
+                SELECT 1
+                FROM CrashChain
+                WHERE salt = $2
+            )
+            "#,
+            chain_id,
+            salt
+        )
+        .execute(&self.db_pool)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+
+    /// Records a new crash round and moves the position of its hash chain in one transaction
+    pub async fn new_crash_round(
+        &self,
+        chain_id: i64,
+        position: i64,
+        hash: &str,
+        crash_point: Decimal,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let round_id = sqlx::query!(
+            r#"
+            INSERT INTO CrashRound(
+                chain_id,
+                position,
+                hash,
+                crash_point
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4
+            )
+            RETURNING id
+            "#,
+            chain_id,
+            position,
+            hash,
+            crash_point
+        )
+        .fetch_one(&mut *tx)
+        .await?
+        .id;
+
+        sqlx::query!(
+            r#"
+            UPDATE CrashChain
+            SET position = $2
+            WHERE id = $1
+            "#,
+            chain_id,
+            position + 1
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(round_id)
+    }
+
+    /// Starts the auto bet session, `None` if the player already auto plays the game with the coin
+    pub async fn new_auto_bet(
+        &self,
+        session: &AutoBetStart,
+        strategy: &str,
+    ) -> Result<Option<AutoBet>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            AutoBet,
+            r#"
+            INSERT INTO AutoBet(
+                game_id,
+                user_id,
+                coin_id,
+                uuid,
+                data,
+                strategy,
+                base_amount,
+                amount,
+                max_bets,
+                stop_win,
+                stop_loss
+            ) VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6,
+                $7,
+                $7,
+                $8,
+                $9,
+                $10
+            )
+            ON CONFLICT (game_id, user_id, coin_id) WHERE active DO NOTHING
+            RETURNING *
+            "#,
+            session.game_id,
+            session.user_id.unwrap(),
+            session.coin_id,
+            session.uuid.as_ref().unwrap(),
+            session.data,
+            strategy,
+            session.amount,
+            session.max_bets,
+            session.stop_win,
+            session.stop_loss
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+    }
+
+    pub async fn fetch_active_auto_bets(&self) -> Result<Vec<AutoBet>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            AutoBet,
+            r#"
+            SELECT *
+            FROM AutoBet
+            WHERE active
+            "#
+        )
+        .fetch_all(&self.db_pool)
+        .await
+    }
+
+    pub async fn fetch_user_auto_bets(
+        &self,
+        user_id: i64,
+        limit: i64,
+    ) -> Result<Vec<AutoBet>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            AutoBet,
+            r#"
+            SELECT *
+            FROM AutoBet
+            WHERE user_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
             user_id,
-            coin_id,
-            userseed_id,
-            serverseed_id,
-            state
+            limit
+        )
+        .fetch_all(&self.db_pool)
+        .await
+    }
+
+    /// Records the result of a bet of the session and the stake of the next one, returns the
+    /// feed the session is bound to
+    pub async fn update_auto_bet(
+        &self,
+        id: i64,
+        amount: Decimal,
+        bets: i64,
+        profit: Decimal,
+    ) -> Result<String, sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE AutoBet
+            SET amount = $2,
+                bets = $3,
+                profit = $4
+            WHERE id = $1
+            RETURNING uuid
+            "#,
+            id,
+            amount,
+            bets,
+            profit
         )
         .fetch_one(&self.db_pool)
         .await
-        .map(|v| v.id)
+        .map(|r| r.uuid)
+    }
+
+    /// Binds the active sessions of the user to the feed `uuid`
+    pub async fn rebind_auto_bets(&self, user_id: i64, uuid: &str) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"
+            UPDATE AutoBet
+            SET uuid = $2
+            WHERE user_id = $1 AND
+                active
+            "#,
+            user_id,
+            uuid
+        )
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Stops the active session of the user, `None` if there is no such session
+    pub async fn stop_auto_bet(
+        &self,
+        id: i64,
+        user_id: i64,
+        reason: &str,
+    ) -> Result<Option<AutoBet>, sqlx::Error> {
+        sqlx::query_as_unchecked!(
+            AutoBet,
+            r#"
+            UPDATE AutoBet
+            SET active = FALSE,
+                stop_reason = $3
+            WHERE id = $1 AND
+                user_id = $2 AND
+                active
+            RETURNING *
+            "#,
+            id,
+            user_id,
+            reason
+        )
+        .fetch_optional(&self.db_pool)
+        .await
     }
 
     pub async fn fetch_leaderboard_volume(
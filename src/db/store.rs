@@ -0,0 +1,945 @@
+//! Operations of the stores grouped by the domain
+//!
+//! Handlers and engines are generic over these traits, so they can run on top of the
+//! Postgres `DB` as well as on top of the `MemoryDB`
+
+use super::DB;
+use crate::models::{
+    db_models::{
+        Amount, Bet, Coin, ConnectedWallet, Game, GameLimits, GameState, OauthProvider, Partner,
+        PartnerContact, PartnerSite, RefClicks, ReferalLink, RefreshToken, SeedPair, ServerSeed,
+        SettledBet, SiteSubId, TimeBoundaries, User, UserSeed, UserTotals, Withdrawal,
+    },
+    json_requests::WithdrawRequest,
+    json_responses::{AmountConnectedWallets, BetExpanded},
+};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::future::Future;
+
+pub trait UserStore: Clone + Send + Sync + 'static {
+    fn fetch_user(&self, id: i64)
+        -> impl Future<Output = Result<Option<User>, sqlx::Error>> + Send;
+
+    fn fetch_user_by_login(
+        &self,
+        login: &str,
+    ) -> impl Future<Output = Result<Option<User>, sqlx::Error>> + Send;
+
+    fn login_user(
+        &self,
+        login: &str,
+        password: &str,
+    ) -> impl Future<Output = Result<Option<User>, sqlx::Error>> + Send;
+
+    fn register_user(
+        &self,
+        login: &str,
+        username: &str,
+        provider: OauthProvider,
+        password_hash: &str,
+    ) -> impl Future<Output = Result<User, sqlx::Error>> + Send;
+
+    fn change_username(
+        &self,
+        id: i64,
+        username: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn change_password(
+        &self,
+        id: i64,
+        password_hash: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn new_refresh_token(
+        &self,
+        user_id: i64,
+        token: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn fetch_refresh_token(
+        &self,
+        token: &str,
+        user_id: i64,
+    ) -> impl Future<Output = Result<RefreshToken, sqlx::Error>> + Send;
+
+    fn remove_refresh_token(
+        &self,
+        token: &str,
+        user_id: i64,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+
+    fn create_referal_link(
+        &self,
+        refer_to: i64,
+        link_name: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn fetch_referal_link(
+        &self,
+        link_name: &str,
+    ) -> impl Future<Output = Result<ReferalLink, sqlx::Error>> + Send;
+
+    fn new_referal(
+        &self,
+        refer_to: i64,
+        referal: i64,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+}
+
+pub trait WalletStore: Clone + Send + Sync + 'static {
+    fn fetch_coins(&self) -> impl Future<Output = Result<Vec<Coin>, sqlx::Error>> + Send;
+
+    fn fetch_amounts(
+        &self,
+        id: i64,
+    ) -> impl Future<Output = Result<Vec<Amount>, sqlx::Error>> + Send;
+
+    fn fetch_amount(
+        &self,
+        user_id: i64,
+        coin_id: i64,
+    ) -> impl Future<Output = Result<Option<Decimal>, sqlx::Error>> + Send;
+
+    fn init_amount(
+        &self,
+        user_id: i64,
+        coin_id: i64,
+        amount: Decimal,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn increase_amounts_by_usdt_amount(
+        &self,
+        user_id: i64,
+        amount: &Decimal,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+}
+
+pub trait SeedStore: Clone + Send + Sync + 'static {
+    /// Replaces the current user seed, the replaced pair is kept in the seed history
+    fn new_user_seed(
+        &self,
+        user_id: i64,
+        seed: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn fetch_current_user_seed(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = Result<UserSeed, sqlx::Error>> + Send;
+
+    fn fetch_user_seed_by_id(
+        &self,
+        seed_id: i64,
+    ) -> impl Future<Output = Result<Option<UserSeed>, sqlx::Error>> + Send;
+
+    /// Fetches the server seed as it is stored, without hashing it
+    fn fetch_server_seed_by_id(
+        &self,
+        seed_id: i64,
+    ) -> impl Future<Output = Result<Option<ServerSeed>, sqlx::Error>> + Send;
+
+    fn fetch_current_server_seed(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = Result<ServerSeed, sqlx::Error>> + Send;
+
+    /// Fetches the current server seed without hashing it, it must not be shown to the user until revealed
+    fn fetch_current_server_seed_unhashed(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = Result<ServerSeed, sqlx::Error>> + Send;
+
+    /// Returns the next nonce for the pair of seeds, starting from 0
+    fn next_nonce(
+        &self,
+        userseed_id: i64,
+        serverseed_id: i64,
+    ) -> impl Future<Output = Result<i64, sqlx::Error>> + Send;
+
+    fn new_server_seed(
+        &self,
+        user_id: i64,
+        seed: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    /// Every seed pair the user had, the newest server seeds first
+    fn fetch_seed_history(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> impl Future<Output = Result<Vec<SeedPair>, sqlx::Error>> + Send;
+
+    /// Reveals the current server seed, its pair is kept in the seed history
+    fn reveal_last_seed(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+}
+
+pub trait BetStore: Clone + Send + Sync + 'static {
+    fn fetch_game(
+        &self,
+        game_id: i64,
+    ) -> impl Future<Output = Result<Option<Game>, sqlx::Error>> + Send;
+
+    /// Limits of the game, the max payout is capped by the global `MAX_PAYOUT` in the coin
+    fn fetch_game_limits(
+        &self,
+        game_id: i64,
+        coin_id: i64,
+    ) -> impl Future<Output = Result<Option<GameLimits>, sqlx::Error>> + Send;
+
+    /// Debits the stake, credits the payout and inserts the bet in a single transaction
+    ///
+    /// If `remove_state` is set, the game state of the bet is removed in the same transaction.
+    /// With the `jackpot_roll` a share of the stake goes to the jackpot pool of the coin and the
+    /// pool is paid out to the player if the roll wins.
+    /// Returns `None` if the balance is not sufficient or the game state was already removed
+    fn settle_bet(
+        &self,
+        debit: Decimal,
+        credit: Decimal,
+        bet: &Bet,
+        remove_state: bool,
+        jackpot_roll: Option<u64>,
+    ) -> impl Future<Output = Result<Option<SettledBet>, sqlx::Error>> + Send;
+
+    fn fetch_bet_by_id(
+        &self,
+        bet_id: i64,
+    ) -> impl Future<Output = Result<Option<Bet>, sqlx::Error>> + Send;
+
+    fn fetch_bet(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        uuid: &str,
+        coin_id: i64,
+    ) -> impl Future<Output = Result<Option<Bet>, sqlx::Error>> + Send;
+
+    fn fetch_bets_for_gamename(
+        &self,
+        game_name: &str,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<BetExpanded>, sqlx::Error>> + Send;
+
+    fn fetch_all_latest_bets(
+        &self,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<BetExpanded>, sqlx::Error>> + Send;
+
+    fn fetch_bets_for_user(
+        &self,
+        user_id: i64,
+        last_id: Option<i64>,
+        page_size: i64,
+    ) -> impl Future<Output = Result<Vec<BetExpanded>, sqlx::Error>> + Send;
+
+    fn fetch_bets_for_user_inc(
+        &self,
+        user_id: i64,
+        last_id: Option<i64>,
+        page_size: i64,
+    ) -> impl Future<Output = Result<Vec<BetExpanded>, sqlx::Error>> + Send;
+
+    fn latest_games(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = Result<Vec<String>, sqlx::Error>> + Send;
+
+    fn fetch_user_totals(
+        &self,
+        user_id: i64,
+    ) -> impl Future<Output = Result<UserTotals, sqlx::Error>> + Send;
+}
+
+pub trait GameStateStore: Clone + Send + Sync + 'static {
+    fn fetch_game_state(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        coin_id: i64,
+    ) -> impl Future<Output = Result<Option<GameState>, sqlx::Error>> + Send;
+
+    /// Debits the stake and inserts the state of a started game in a single transaction
+    ///
+    /// Returns `false` if the balance is not sufficient
+    fn start_game_state(
+        &self,
+        debit: Decimal,
+        state: &GameState,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+
+    /// Updates the state of a started game and debits the stake added by the step, the timestamp
+    /// of the state is the time of the last step
+    ///
+    /// Returns `false` if the balance is not sufficient or the game is not started
+    #[allow(clippy::too_many_arguments)]
+    fn change_game_state(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        coin_id: i64,
+        debit: Decimal,
+        new_state: &str,
+        steps: &str,
+        nonce: i64,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+
+    /// Removes the game state and returns its amount to the balance of the user
+    ///
+    /// Returns `false` if the game state was already removed
+    fn refund_game_state(
+        &self,
+        state: &GameState,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+
+    fn fetch_game_states_by_game(
+        &self,
+        game_id: i64,
+    ) -> impl Future<Output = Result<Vec<GameState>, sqlx::Error>> + Send;
+
+    /// Fetches the states of the games that were not finished within the timeout of the game
+    fn fetch_expired_game_states(
+        &self,
+    ) -> impl Future<Output = Result<Vec<GameState>, sqlx::Error>> + Send;
+}
+
+pub trait PartnerStore: Clone + Send + Sync + 'static {
+    fn create_partner(
+        &self,
+        partner: Partner,
+        contacts: &[(String, String)],
+    ) -> impl Future<Output = Result<i64, sqlx::Error>> + Send;
+
+    fn get_partner(&self, id: i64) -> impl Future<Output = Result<Partner, sqlx::Error>> + Send;
+
+    fn get_partner_by_login(
+        &self,
+        login: &str,
+    ) -> impl Future<Output = Result<Partner, sqlx::Error>> + Send;
+
+    fn create_withdraw_request(
+        &self,
+        partner_id: i64,
+        withdraw_request: &WithdrawRequest,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn get_partner_withdrawal_requests(
+        &self,
+        partner: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> impl Future<Output = Result<Vec<Withdrawal>, sqlx::Error>> + Send;
+
+    fn login_partner(
+        &self,
+        login: &str,
+        password: &str,
+    ) -> impl Future<Output = Result<Option<Partner>, sqlx::Error>> + Send;
+
+    fn partner_change_password(
+        &self,
+        partner_id: i64,
+        old_password: &str,
+        new_password: &str,
+    ) -> impl Future<Output = Result<bool, sqlx::Error>> + Send;
+
+    fn add_partner_contacts(
+        &self,
+        partner_id: i64,
+        contacts: &[(String, String)],
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn delete_partner_contacts(
+        &self,
+        partner_id: i64,
+        contact_ids: &[i64],
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn get_partner_contacts(
+        &self,
+        partner_id: i64,
+    ) -> impl Future<Output = Result<Vec<PartnerContact>, sqlx::Error>> + Send;
+
+    fn add_partner_site(
+        &self,
+        partner_id: i64,
+        url: &str,
+        name: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn get_partner_sites(
+        &self,
+        partner_id: i64,
+    ) -> impl Future<Output = Result<Vec<PartnerSite>, sqlx::Error>> + Send;
+
+    fn add_partner_subid(
+        &self,
+        internal_site_id: i64,
+        patner_id: i64,
+        url: &str,
+        name: &str,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn get_site_subids(
+        &self,
+        internal_site_id: i64,
+    ) -> impl Future<Output = Result<Vec<SiteSubId>, sqlx::Error>> + Send;
+
+    fn get_subid(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+        sub_id: i64,
+    ) -> impl Future<Output = Result<SiteSubId, sqlx::Error>> + Send;
+
+    fn get_subid_clicks(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+        sub_id: i64,
+    ) -> impl Future<Output = Result<RefClicks, sqlx::Error>> + Send;
+
+    fn get_site_clicks(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+    ) -> impl Future<Output = Result<RefClicks, sqlx::Error>> + Send;
+
+    fn get_partner_clicks(
+        &self,
+        partner_id: i64,
+    ) -> impl Future<Output = Result<RefClicks, sqlx::Error>> + Send;
+
+    fn get_partner_connected_wallets_amount_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Future<Output = Result<AmountConnectedWallets, sqlx::Error>> + Send;
+
+    fn get_partner_connected_wallets_with_bets_amount_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Future<Output = Result<AmountConnectedWallets, sqlx::Error>> + Send;
+
+    fn get_partner_clicks_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> impl Future<Output = Result<RefClicks, sqlx::Error>> + Send;
+
+    fn get_partner_connected_wallets_info(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> impl Future<Output = Result<Vec<ConnectedWallet>, sqlx::Error>> + Send;
+
+    fn get_partner_connected_wallets_with_deposits_amount(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> impl Future<Output = Result<AmountConnectedWallets, sqlx::Error>> + Send;
+
+    fn get_partner_connected_wallets_amount(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> impl Future<Output = Result<AmountConnectedWallets, sqlx::Error>> + Send;
+
+    fn add_click(
+        &self,
+        partner_id: i64,
+        sub_id: i64,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+
+    fn add_ref_wallet(
+        &self,
+        user_id: i64,
+        timestamp: DateTime<Utc>,
+        sub_id_internal: i64,
+        partner_id: i64,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+}
+
+/// Everything the game engines need to play and settle the bets
+pub trait EngineStore: UserStore + WalletStore + SeedStore + BetStore + GameStateStore {}
+
+impl<T> EngineStore for T where T: UserStore + WalletStore + SeedStore + BetStore + GameStateStore {}
+
+impl UserStore for DB {
+    async fn fetch_user(&self, id: i64) -> Result<Option<User>, sqlx::Error> {
+        DB::fetch_user(self, id).await
+    }
+
+    async fn fetch_user_by_login(&self, login: &str) -> Result<Option<User>, sqlx::Error> {
+        DB::fetch_user_by_login(self, login).await
+    }
+
+    async fn login_user(&self, login: &str, password: &str) -> Result<Option<User>, sqlx::Error> {
+        DB::login_user(self, login, password).await
+    }
+
+    async fn register_user(
+        &self,
+        login: &str,
+        username: &str,
+        provider: OauthProvider,
+        password_hash: &str,
+    ) -> Result<User, sqlx::Error> {
+        DB::register_user(self, login, username, provider, password_hash).await
+    }
+
+    async fn change_username(&self, id: i64, username: &str) -> Result<(), sqlx::Error> {
+        DB::change_username(self, id, username).await
+    }
+
+    async fn change_password(&self, id: i64, password_hash: &str) -> Result<(), sqlx::Error> {
+        DB::change_password(self, id, password_hash).await
+    }
+
+    async fn new_refresh_token(&self, user_id: i64, token: &str) -> Result<(), sqlx::Error> {
+        DB::new_refresh_token(self, user_id, token).await
+    }
+
+    async fn fetch_refresh_token(
+        &self,
+        token: &str,
+        user_id: i64,
+    ) -> Result<RefreshToken, sqlx::Error> {
+        DB::fetch_refresh_token(self, token, user_id).await
+    }
+
+    async fn remove_refresh_token(&self, token: &str, user_id: i64) -> Result<bool, sqlx::Error> {
+        DB::remove_refresh_token(self, token, user_id).await
+    }
+
+    async fn create_referal_link(&self, refer_to: i64, link_name: &str) -> Result<(), sqlx::Error> {
+        DB::create_referal_link(self, refer_to, link_name).await
+    }
+
+    async fn fetch_referal_link(&self, link_name: &str) -> Result<ReferalLink, sqlx::Error> {
+        DB::fetch_referal_link(self, link_name).await
+    }
+
+    async fn new_referal(&self, refer_to: i64, referal: i64) -> Result<(), sqlx::Error> {
+        DB::new_referal(self, refer_to, referal).await
+    }
+}
+
+impl WalletStore for DB {
+    async fn fetch_coins(&self) -> Result<Vec<Coin>, sqlx::Error> {
+        DB::fetch_coins(self).await
+    }
+
+    async fn fetch_amounts(&self, id: i64) -> Result<Vec<Amount>, sqlx::Error> {
+        DB::fetch_amounts(self, id).await
+    }
+
+    async fn fetch_amount(
+        &self,
+        user_id: i64,
+        coin_id: i64,
+    ) -> Result<Option<Decimal>, sqlx::Error> {
+        DB::fetch_amount(self, user_id, coin_id).await
+    }
+
+    async fn init_amount(
+        &self,
+        user_id: i64,
+        coin_id: i64,
+        amount: Decimal,
+    ) -> Result<(), sqlx::Error> {
+        DB::init_amount(self, user_id, coin_id, amount).await
+    }
+
+    async fn increase_amounts_by_usdt_amount(
+        &self,
+        user_id: i64,
+        amount: &Decimal,
+    ) -> Result<(), sqlx::Error> {
+        DB::increase_amounts_by_usdt_amount(self, user_id, amount).await
+    }
+}
+
+impl SeedStore for DB {
+    async fn new_user_seed(&self, user_id: i64, seed: &str) -> Result<(), sqlx::Error> {
+        DB::new_user_seed(self, user_id, seed).await
+    }
+
+    async fn fetch_current_user_seed(&self, user_id: i64) -> Result<UserSeed, sqlx::Error> {
+        DB::fetch_current_user_seed(self, user_id).await
+    }
+
+    async fn fetch_user_seed_by_id(&self, seed_id: i64) -> Result<Option<UserSeed>, sqlx::Error> {
+        DB::fetch_user_seed_by_id(self, seed_id).await
+    }
+
+    async fn fetch_server_seed_by_id(
+        &self,
+        seed_id: i64,
+    ) -> Result<Option<ServerSeed>, sqlx::Error> {
+        DB::fetch_server_seed_by_id(self, seed_id).await
+    }
+
+    async fn fetch_current_server_seed(&self, user_id: i64) -> Result<ServerSeed, sqlx::Error> {
+        DB::fetch_current_server_seed(self, user_id).await
+    }
+
+    async fn fetch_current_server_seed_unhashed(
+        &self,
+        user_id: i64,
+    ) -> Result<ServerSeed, sqlx::Error> {
+        DB::fetch_current_server_seed_unhashed(self, user_id).await
+    }
+
+    async fn next_nonce(&self, userseed_id: i64, serverseed_id: i64) -> Result<i64, sqlx::Error> {
+        DB::next_nonce(self, userseed_id, serverseed_id).await
+    }
+
+    async fn new_server_seed(&self, user_id: i64, seed: &str) -> Result<(), sqlx::Error> {
+        DB::new_server_seed(self, user_id, seed).await
+    }
+
+    async fn fetch_seed_history(
+        &self,
+        user_id: i64,
+        page: i64,
+        page_size: i64,
+    ) -> Result<Vec<SeedPair>, sqlx::Error> {
+        DB::fetch_seed_history(self, user_id, page, page_size).await
+    }
+
+    async fn reveal_last_seed(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        DB::reveal_last_seed(self, user_id).await
+    }
+}
+
+impl BetStore for DB {
+    async fn fetch_game(&self, game_id: i64) -> Result<Option<Game>, sqlx::Error> {
+        DB::fetch_game(self, game_id).await
+    }
+
+    async fn fetch_game_limits(
+        &self,
+        game_id: i64,
+        coin_id: i64,
+    ) -> Result<Option<GameLimits>, sqlx::Error> {
+        DB::fetch_game_limits(self, game_id, coin_id).await
+    }
+
+    async fn settle_bet(
+        &self,
+        debit: Decimal,
+        credit: Decimal,
+        bet: &Bet,
+        remove_state: bool,
+        jackpot_roll: Option<u64>,
+    ) -> Result<Option<SettledBet>, sqlx::Error> {
+        DB::settle_bet(self, debit, credit, bet, remove_state, jackpot_roll).await
+    }
+
+    async fn fetch_bet_by_id(&self, bet_id: i64) -> Result<Option<Bet>, sqlx::Error> {
+        DB::fetch_bet_by_id(self, bet_id).await
+    }
+
+    async fn fetch_bet(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        uuid: &str,
+        coin_id: i64,
+    ) -> Result<Option<Bet>, sqlx::Error> {
+        DB::fetch_bet(self, game_id, user_id, uuid, coin_id).await
+    }
+
+    async fn fetch_bets_for_gamename(
+        &self,
+        game_name: &str,
+        limit: i64,
+    ) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        DB::fetch_bets_for_gamename(self, game_name, limit).await
+    }
+
+    async fn fetch_all_latest_bets(&self, limit: i64) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        DB::fetch_all_latest_bets(self, limit).await
+    }
+
+    async fn fetch_bets_for_user(
+        &self,
+        user_id: i64,
+        last_id: Option<i64>,
+        page_size: i64,
+    ) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        DB::fetch_bets_for_user(self, user_id, last_id, page_size).await
+    }
+
+    async fn fetch_bets_for_user_inc(
+        &self,
+        user_id: i64,
+        last_id: Option<i64>,
+        page_size: i64,
+    ) -> Result<Vec<BetExpanded>, sqlx::Error> {
+        DB::fetch_bets_for_user_inc(self, user_id, last_id, page_size).await
+    }
+
+    async fn latest_games(&self, user_id: i64) -> Result<Vec<String>, sqlx::Error> {
+        DB::latest_games(self, user_id).await
+    }
+
+    async fn fetch_user_totals(&self, user_id: i64) -> Result<UserTotals, sqlx::Error> {
+        DB::fetch_user_totals(self, user_id).await
+    }
+}
+
+impl GameStateStore for DB {
+    async fn fetch_game_state(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        coin_id: i64,
+    ) -> Result<Option<GameState>, sqlx::Error> {
+        DB::fetch_game_state(self, game_id, user_id, coin_id).await
+    }
+
+    async fn start_game_state(
+        &self,
+        debit: Decimal,
+        state: &GameState,
+    ) -> Result<bool, sqlx::Error> {
+        DB::start_game_state(self, debit, state).await
+    }
+
+    async fn change_game_state(
+        &self,
+        game_id: i64,
+        user_id: i64,
+        coin_id: i64,
+        debit: Decimal,
+        new_state: &str,
+        steps: &str,
+        nonce: i64,
+    ) -> Result<bool, sqlx::Error> {
+        DB::change_game_state(
+            self, game_id, user_id, coin_id, debit, new_state, steps, nonce,
+        )
+        .await
+    }
+
+    async fn refund_game_state(&self, state: &GameState) -> Result<bool, sqlx::Error> {
+        DB::refund_game_state(self, state).await
+    }
+
+    async fn fetch_game_states_by_game(&self, game_id: i64) -> Result<Vec<GameState>, sqlx::Error> {
+        DB::fetch_game_states_by_game(self, game_id).await
+    }
+
+    async fn fetch_expired_game_states(&self) -> Result<Vec<GameState>, sqlx::Error> {
+        DB::fetch_expired_game_states(self).await
+    }
+}
+
+impl PartnerStore for DB {
+    async fn create_partner(
+        &self,
+        partner: Partner,
+        contacts: &[(String, String)],
+    ) -> Result<i64, sqlx::Error> {
+        DB::create_partner(self, partner, contacts).await
+    }
+
+    async fn get_partner(&self, id: i64) -> Result<Partner, sqlx::Error> {
+        DB::get_partner(self, id).await
+    }
+
+    async fn get_partner_by_login(&self, login: &str) -> Result<Partner, sqlx::Error> {
+        DB::get_partner_by_login(self, login).await
+    }
+
+    async fn create_withdraw_request(
+        &self,
+        partner_id: i64,
+        withdraw_request: &WithdrawRequest,
+    ) -> Result<(), sqlx::Error> {
+        DB::create_withdraw_request(self, partner_id, withdraw_request).await
+    }
+
+    async fn get_partner_withdrawal_requests(
+        &self,
+        partner: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<Vec<Withdrawal>, sqlx::Error> {
+        DB::get_partner_withdrawal_requests(self, partner, time_boundaries).await
+    }
+
+    async fn login_partner(
+        &self,
+        login: &str,
+        password: &str,
+    ) -> Result<Option<Partner>, sqlx::Error> {
+        DB::login_partner(self, login, password).await
+    }
+
+    async fn partner_change_password(
+        &self,
+        partner_id: i64,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<bool, sqlx::Error> {
+        DB::partner_change_password(self, partner_id, old_password, new_password).await
+    }
+
+    async fn add_partner_contacts(
+        &self,
+        partner_id: i64,
+        contacts: &[(String, String)],
+    ) -> Result<(), sqlx::Error> {
+        DB::add_partner_contacts(self, partner_id, contacts).await
+    }
+
+    async fn delete_partner_contacts(
+        &self,
+        partner_id: i64,
+        contact_ids: &[i64],
+    ) -> Result<(), sqlx::Error> {
+        DB::delete_partner_contacts(self, partner_id, contact_ids).await
+    }
+
+    async fn get_partner_contacts(
+        &self,
+        partner_id: i64,
+    ) -> Result<Vec<PartnerContact>, sqlx::Error> {
+        DB::get_partner_contacts(self, partner_id).await
+    }
+
+    async fn add_partner_site(
+        &self,
+        partner_id: i64,
+        url: &str,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        DB::add_partner_site(self, partner_id, url, name).await
+    }
+
+    async fn get_partner_sites(&self, partner_id: i64) -> Result<Vec<PartnerSite>, sqlx::Error> {
+        DB::get_partner_sites(self, partner_id).await
+    }
+
+    async fn add_partner_subid(
+        &self,
+        internal_site_id: i64,
+        patner_id: i64,
+        url: &str,
+        name: &str,
+    ) -> Result<(), sqlx::Error> {
+        DB::add_partner_subid(self, internal_site_id, patner_id, url, name).await
+    }
+
+    async fn get_site_subids(&self, internal_site_id: i64) -> Result<Vec<SiteSubId>, sqlx::Error> {
+        DB::get_site_subids(self, internal_site_id).await
+    }
+
+    async fn get_subid(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+        sub_id: i64,
+    ) -> Result<SiteSubId, sqlx::Error> {
+        DB::get_subid(self, partner_id, site_id, sub_id).await
+    }
+
+    async fn get_subid_clicks(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+        sub_id: i64,
+    ) -> Result<RefClicks, sqlx::Error> {
+        DB::get_subid_clicks(self, partner_id, site_id, sub_id).await
+    }
+
+    async fn get_site_clicks(
+        &self,
+        partner_id: i64,
+        site_id: i64,
+    ) -> Result<RefClicks, sqlx::Error> {
+        DB::get_site_clicks(self, partner_id, site_id).await
+    }
+
+    async fn get_partner_clicks(&self, partner_id: i64) -> Result<RefClicks, sqlx::Error> {
+        DB::get_partner_clicks(self, partner_id).await
+    }
+
+    async fn get_partner_connected_wallets_amount_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        DB::get_partner_connected_wallets_amount_exact_date(self, partner_id, start, end).await
+    }
+
+    async fn get_partner_connected_wallets_with_bets_amount_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        DB::get_partner_connected_wallets_with_bets_amount_exact_date(self, partner_id, start, end)
+            .await
+    }
+
+    async fn get_partner_clicks_exact_date(
+        &self,
+        partner_id: i64,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<RefClicks, sqlx::Error> {
+        DB::get_partner_clicks_exact_date(self, partner_id, start, end).await
+    }
+
+    async fn get_partner_connected_wallets_info(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<Vec<ConnectedWallet>, sqlx::Error> {
+        DB::get_partner_connected_wallets_info(self, partner_id, time_boundaries).await
+    }
+
+    async fn get_partner_connected_wallets_with_deposits_amount(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        DB::get_partner_connected_wallets_with_deposits_amount(self, partner_id, time_boundaries)
+            .await
+    }
+
+    async fn get_partner_connected_wallets_amount(
+        &self,
+        partner_id: i64,
+        time_boundaries: TimeBoundaries,
+    ) -> Result<AmountConnectedWallets, sqlx::Error> {
+        DB::get_partner_connected_wallets_amount(self, partner_id, time_boundaries).await
+    }
+
+    async fn add_click(&self, partner_id: i64, sub_id: i64) -> Result<(), sqlx::Error> {
+        DB::add_click(self, partner_id, sub_id).await
+    }
+
+    async fn add_ref_wallet(
+        &self,
+        user_id: i64,
+        timestamp: DateTime<Utc>,
+        sub_id_internal: i64,
+        partner_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        DB::add_ref_wallet(self, user_id, timestamp, sub_id_internal, partner_id).await
+    }
+}
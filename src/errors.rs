@@ -1,5 +1,6 @@
 use crate::communication::ChannelType;
 use reqwest::Error as ReqwestError;
+use sqlx::migrate::MigrateError;
 use thedex::errors::Error as TheDexError;
 use thiserror::Error;
 use warp::reject;
@@ -13,6 +14,42 @@ pub enum ManagerError {
     FeedDoesntExist(String),
 }
 
+#[derive(Error, Debug)]
+pub enum CrashError {
+    #[error("Db Error: {0}")]
+    DbError(sqlx::Error),
+
+    #[error("The game `Crash` doesn't exist")]
+    GameDoesntExist,
+
+    #[error("Bad parameters of the game `Crash`: {0}")]
+    BadParameters(serde_json::Error),
+
+    #[error("The crash hash chain is empty")]
+    EmptyChain,
+
+    #[error("No new salt is set for the crash hash chain with the commitment `{0}`")]
+    SaltNotPublished(String),
+
+    #[error("The crash events channel is closed")]
+    ChannelClosed,
+}
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("Db Error: {0}")]
+    DbError(sqlx::Error),
+
+    #[error("Schema migration Error: {0}")]
+    SchemaMigration(MigrateError),
+
+    #[error("The database schema version `{database}` is ahead of the binary version `{binary}`")]
+    SchemaAhead { database: i64, binary: i64 },
+
+    #[error("Data migration `{0}` Error: {1}")]
+    DataMigration(&'static str, sqlx::Error),
+}
+
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("Db Error: {0}")]
@@ -27,6 +64,15 @@ pub enum ApiError {
     #[error("The game with ID: `{0}` doesn't exist")]
     GameWithIDDoesntExist(i64),
 
+    #[error("The bet with ID: `{0}` doesn't exist")]
+    BetDoesntExist(i64),
+
+    #[error("The bet with ID: `{0}` can't be replayed")]
+    BetReplayError(i64),
+
+    #[error("The server seed with ID: `{0}` is not revealed yet")]
+    ServerSeedNotRevealed(i64),
+
     #[error("Bad signature provided address: `{0}` message: `{1}` signature: `{2}`")]
     BadSignature(String, String, String),
 
@@ -1,6 +1,6 @@
 use crate::config;
 use crate::config::PASSWORD_SALT;
-use crate::db::DB;
+use crate::db::{BetStore, PartnerStore, SeedStore, UserStore, WalletStore, DB};
 use crate::errors::ApiError;
 use crate::handlers;
 use crate::jwt;
@@ -10,7 +10,10 @@ use crate::models::json_requests;
 use crate::models::LeaderboardType;
 use crate::oauth_providers;
 use crate::tools;
+use crate::AutoBetEventSender;
+use crate::CrashEventSender;
 use crate::EngineBetSender;
+use crate::StatefulEngineBetRouter;
 
 use crate::WsManagerEventSender;
 use base64::{engine::general_purpose, Engine as _};
@@ -36,7 +39,9 @@ pub fn json_body_new_payout(
 ) -> impl Filter<Extract = (json_requests::PayoutRequest,), Error = warp::Rejection> + Clone {
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
-fn with_db(db: DB) -> impl Filter<Extract = (DB,), Error = std::convert::Infallible> + Clone {
+fn with_db<D: Clone + Send>(
+    db: D,
+) -> impl Filter<Extract = (D,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || db.clone())
 }
 
@@ -77,12 +82,30 @@ fn with_manager_channel(
     warp::any().map(move || ch.clone())
 }
 
+fn with_crash_channel(
+    ch: CrashEventSender,
+) -> impl Filter<Extract = (CrashEventSender,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || ch.clone())
+}
+
+fn with_autobet_channel(
+    ch: AutoBetEventSender,
+) -> impl Filter<Extract = (AutoBetEventSender,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || ch.clone())
+}
+
 fn with_engine_channel(
     ch: EngineBetSender,
 ) -> impl Filter<Extract = (EngineBetSender,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || ch.clone())
 }
 
+fn with_stateful_engine_channel(
+    ch: StatefulEngineBetRouter,
+) -> impl Filter<Extract = (StatefulEngineBetRouter,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || ch.clone())
+}
+
 fn extract_token(headers: &HeaderMap<HeaderValue>) -> Result<(String, Payload), ApiError> {
     let header = match headers.get(AUTHORIZATION) {
         Some(h) => h,
@@ -128,7 +151,10 @@ fn extract_token(headers: &HeaderMap<HeaderValue>) -> Result<(String, Payload),
     ))
 }
 
-async fn auth_verified(headers: HeaderMap<HeaderValue>, db: DB) -> Result<i64, warp::Rejection> {
+async fn auth_verified<D: UserStore>(
+    headers: HeaderMap<HeaderValue>,
+    db: D,
+) -> Result<i64, warp::Rejection> {
     match extract_token(&headers) {
         Ok((token, decoded)) => {
             debug!("Token {:?}", decoded);
@@ -153,16 +179,18 @@ async fn auth_verified(headers: HeaderMap<HeaderValue>, db: DB) -> Result<i64, w
     }
 }
 
-fn with_auth(db: DB) -> impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone {
+fn with_auth<D: UserStore>(
+    db: D,
+) -> impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone {
     headers_cloned()
         .map(|header| header)
         .and(with_db(db))
         .and_then(auth_verified)
 }
 
-async fn auth_verified_partner(
+async fn auth_verified_partner<D: PartnerStore>(
     headers: HeaderMap<HeaderValue>,
-    db: DB,
+    db: D,
 ) -> Result<i64, warp::Rejection> {
     match extract_token(&headers) {
         Ok((token, decoded)) => {
@@ -184,7 +212,9 @@ async fn auth_verified_partner(
         Err(e) => Err(reject::custom(e)),
     }
 }
-fn with_auth_partner(db: DB) -> impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone {
+fn with_auth_partner<D: PartnerStore>(
+    db: D,
+) -> impl Filter<Extract = (i64,), Error = warp::Rejection> + Clone {
     headers_cloned()
         .map(|header| header)
         .and(with_db(db))
@@ -271,8 +301,8 @@ pub fn coin(db: DB) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::
 }
 
 // BETS
-pub fn get_user_bets(
-    db: DB,
+pub fn get_user_bets<D: BetStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("user" / i64 / ..)
         .and(
@@ -285,8 +315,8 @@ pub fn get_user_bets(
         .and_then(handlers::get_user_bets)
 }
 
-pub fn get_user_bets_inc(
-    db: DB,
+pub fn get_user_bets_inc<D: BetStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("user" / "inc" / i64 / ..)
         .and(
@@ -299,34 +329,45 @@ pub fn get_user_bets_inc(
         .and_then(handlers::get_user_bets_inc)
 }
 
-pub fn get_all_last_bets(
-    db: DB,
+pub fn get_all_last_bets<D: BetStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("list")
         .and(with_db(db))
         .and_then(handlers::get_all_last_bets)
 }
 
-pub fn get_bets_for_game(
-    db: DB,
+pub fn get_bets_for_game<D: BetStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("game" / String)
         .and(with_db(db))
         .and_then(handlers::get_bets_for_game)
 }
 
-pub fn bets(db: DB) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+pub fn verify_bet<D: SeedStore + BetStore>(
+    db: D,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("verify" / i64)
+        .and(with_db(db))
+        .and_then(handlers::verify_bet)
+}
+
+pub fn bets<D: SeedStore + BetStore>(
+    db: D,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path("bets").and(
         get_all_last_bets(db.clone())
             .or(get_bets_for_game(db.clone()))
             .or(get_user_bets(db.clone()))
-            .or(get_user_bets_inc(db)),
+            .or(get_user_bets_inc(db.clone()))
+            .or(verify_bet(db)),
     )
 }
 
 // USER
-pub fn register_user(
-    db: DB,
+pub fn register_user<D: UserStore + WalletStore>(
+    db: D,
     hcap: hcaptcha::HCaptcha,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("register")
@@ -338,8 +379,8 @@ pub fn register_user(
         .and_then(handlers::register_user)
 }
 
-pub fn login_user(
-    db: DB,
+pub fn login_user<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("login")
         .and(warp::post())
@@ -348,8 +389,8 @@ pub fn login_user(
         .and_then(handlers::login_user)
 }
 
-pub fn login_user_google(
-    db: DB,
+pub fn login_user_google<D: UserStore + WalletStore>(
+    db: D,
     google: oauth_providers::google::GoogleOauth,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("login" / "google")
@@ -360,8 +401,8 @@ pub fn login_user_google(
         .and_then(handlers::login_google)
 }
 
-pub fn refresh_token(
-    db: DB,
+pub fn refresh_token<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("refresh" / String)
         .and(warp::post())
@@ -369,8 +410,8 @@ pub fn refresh_token(
         .and_then(handlers::refresh_token)
 }
 
-pub fn get_amounts(
-    db: DB,
+pub fn get_amounts<D: WalletStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("amounts" / i64)
         .and(warp::get())
@@ -378,8 +419,8 @@ pub fn get_amounts(
         .and_then(handlers::get_amounts)
 }
 
-pub fn get_latest_games(
-    db: DB,
+pub fn get_latest_games<D: BetStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("latest_games" / i64)
         .and(warp::get())
@@ -387,8 +428,8 @@ pub fn get_latest_games(
         .and_then(handlers::get_latest_games)
 }
 
-pub fn get_user_totals(
-    db: DB,
+pub fn get_user_totals<D: BetStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("totals" / i64)
         .and(warp::get())
@@ -396,8 +437,8 @@ pub fn get_user_totals(
         .and_then(handlers::get_users_totals)
 }
 
-pub fn change_username(
-    db: DB,
+pub fn change_username<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("username")
         .and(warp::patch())
@@ -407,8 +448,8 @@ pub fn change_username(
         .and_then(handlers::change_username)
 }
 
-pub fn change_password(
-    db: DB,
+pub fn change_password<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("password")
         .and(warp::patch())
@@ -418,8 +459,8 @@ pub fn change_password(
         .and_then(handlers::change_password)
 }
 
-pub fn register_referal_link(
-    db: DB,
+pub fn register_referal_link<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("register_ref" / String)
         .and(warp::post())
@@ -428,8 +469,8 @@ pub fn register_referal_link(
         .and_then(handlers::register_referal_link)
 }
 
-pub fn register_referal(
-    db: DB,
+pub fn register_referal<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("referal" / String)
         .and(warp::get())
@@ -438,8 +479,8 @@ pub fn register_referal(
         .and_then(handlers::register_referal)
 }
 
-pub fn get_user(
-    db: DB,
+pub fn get_user<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!(i64)
         .and(warp::get())
@@ -447,8 +488,8 @@ pub fn get_user(
         .and_then(handlers::get_user)
 }
 
-pub fn get_logined_user(
-    db: DB,
+pub fn get_logined_user<D: UserStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!()
         .and(warp::get())
@@ -457,8 +498,8 @@ pub fn get_logined_user(
         .and_then(handlers::get_user)
 }
 
-pub fn get_client_seed(
-    db: DB,
+pub fn get_client_seed<D: UserStore + SeedStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("client")
         .and(warp::get())
@@ -467,8 +508,8 @@ pub fn get_client_seed(
         .and_then(handlers::get_client_seed)
 }
 
-pub fn get_server_seed(
-    db: DB,
+pub fn get_server_seed<D: UserStore + SeedStore>(
+    db: D,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
     warp::path!("server")
         .and(warp::get())
@@ -477,12 +518,34 @@ pub fn get_server_seed(
         .and_then(handlers::get_server_seed)
 }
 
-pub fn seed(db: DB) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path("seed").and(get_client_seed(db.clone()).or(get_server_seed(db)))
+pub fn get_seed_history<D: UserStore + SeedStore>(
+    db: D,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("history" / ..)
+        .and(warp::get())
+        .and(with_auth(db.clone()))
+        .and(
+            warp::path::param::<i64>()
+                .map(Some)
+                .or_else(|_| async { Ok::<(Option<i64>,), std::convert::Infallible>((None,)) }),
+        )
+        .and(warp::path::end())
+        .and(with_db(db))
+        .and_then(handlers::get_seed_history)
 }
 
-pub fn user(
-    db: DB,
+pub fn seed<D: UserStore + SeedStore>(
+    db: D,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path("seed").and(
+        get_client_seed(db.clone())
+            .or(get_server_seed(db.clone()))
+            .or(get_seed_history(db)),
+    )
+}
+
+pub fn user<D: UserStore + WalletStore + SeedStore + BetStore>(
+    db: D,
     hcap: hcaptcha::HCaptcha,
     google: oauth_providers::google::GoogleOauth,
 ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
@@ -687,12 +750,26 @@ pub fn general(
     )
 }
 
+pub fn metrics(
+    db: DB,
+    engine_sender: EngineBetSender,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    warp::path!("metrics")
+        .and(warp::get())
+        .and(with_db(db))
+        .and(with_engine_channel(engine_sender))
+        .and_then(handlers::get_metrics)
+}
+
 pub fn init_filters(
     db: DB,
     dex: TheDex,
     p2way: P2Way,
     manager_channel: WsManagerEventSender,
     engine_sender: EngineBetSender,
+    stateful_engine_sender: StatefulEngineBetRouter,
+    crash_sender: CrashEventSender,
+    autobet_sender: AutoBetEventSender,
     hcap: hcaptcha::HCaptcha,
     google: oauth_providers::google::GoogleOauth,
     dexs: dexscreener::DexScreener,
@@ -706,17 +783,24 @@ pub fn init_filters(
         .or(p2way_filter(db.clone(), p2way))
         .or(partners::partners(db.clone()))
         .or(create_payout_request(db.clone()))
+        .or(metrics(db.clone(), engine_sender.clone()))
         .or(warp::path!("updates")
             .and(warp::ws())
             .and(with_db(db))
             .and(with_manager_channel(manager_channel.clone()))
             .and(with_engine_channel(engine_sender.clone()))
+            .and(with_stateful_engine_channel(stateful_engine_sender))
+            .and(with_crash_channel(crash_sender.clone()))
+            .and(with_autobet_channel(autobet_sender.clone()))
             .and(warp::header::header::<SocketAddr>("X-Forwarded-For"))
             .map(
                 |ws: warp::ws::Ws,
                  db,
                  channel: WsManagerEventSender,
                  engine_channel: EngineBetSender,
+                 stateful_engine_channel: StatefulEngineBetRouter,
+                 crash_channel: CrashEventSender,
+                 autobet_channel: AutoBetEventSender,
                  addr| {
                     ws.on_upgrade(move |socket| {
                         handlers::websockets_handler(
@@ -725,6 +809,9 @@ pub fn init_filters(
                             db,
                             channel.clone(),
                             engine_channel.clone(),
+                            stateful_engine_channel.clone(),
+                            crash_channel.clone(),
+                            autobet_channel.clone(),
                         )
                     })
                 },
@@ -795,8 +882,8 @@ pub mod partners {
         warp::body::content_length_limit(1024 * 16).and(warp::body::json())
     }
     // PARTNERS REFERALS
-    pub fn submit_question(
-        db: DB,
+    pub fn submit_question<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("question")
             .and(warp::post())
@@ -811,8 +898,8 @@ pub mod partners {
         warp::body::content_length_limit(1024 * 16).and(warp::body::json())
     }
 
-    pub fn register_partner(
-        db: DB,
+    pub fn register_partner<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("register")
             .and(warp::post())
@@ -827,8 +914,8 @@ pub mod partners {
         warp::body::content_length_limit(1024 * 16).and(warp::body::json())
     }
 
-    pub fn login_partner(
-        db: DB,
+    pub fn login_partner<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("login")
             .and(warp::post())
@@ -837,8 +924,8 @@ pub mod partners {
             .and_then(handlers::login_partner)
     }
 
-    pub fn get_partner(
-        db: DB,
+    pub fn get_partner<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("get")
             .and(warp::get())
@@ -853,8 +940,8 @@ pub mod partners {
             .and_then(handlers::get_partner)
     }
 
-    pub fn add_partner_contacts(
-        db: DB,
+    pub fn add_partner_contacts<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("add")
             .and(warp::post())
@@ -865,8 +952,8 @@ pub mod partners {
             .and_then(handlers::add_contacts)
     }
 
-    pub fn add_partner_site(
-        db: DB,
+    pub fn add_partner_site<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("add")
             .and(warp::post())
@@ -876,8 +963,8 @@ pub mod partners {
             .and_then(handlers::add_partner_site)
     }
 
-    pub fn get_partner_sites(
-        db: DB,
+    pub fn get_partner_sites<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("get")
             .and(warp::get())
@@ -887,8 +974,8 @@ pub mod partners {
             .and_then(handlers::get_partner_sites)
     }
 
-    pub fn add_partner_subid(
-        db: DB,
+    pub fn add_partner_subid<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("add")
             .and(warp::post())
@@ -898,8 +985,8 @@ pub mod partners {
             .and_then(handlers::add_partner_subid)
     }
 
-    pub fn click_partner_subid(
-        db: DB,
+    pub fn click_partner_subid<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("click" / i64 / i64 / i64)
             .and(warp::post())
@@ -907,8 +994,8 @@ pub mod partners {
             .and_then(handlers::click_partner_subid)
     }
 
-    pub fn subid_get_clicks(
-        db: DB,
+    pub fn subid_get_clicks<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("clicks")
             .and(warp::get())
@@ -921,8 +1008,8 @@ pub mod partners {
             .and_then(handlers::get_clicks)
     }
 
-    pub fn site_get_clicks(
-        db: DB,
+    pub fn site_get_clicks<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("clicks")
             .and(warp::get())
@@ -934,8 +1021,8 @@ pub mod partners {
             .and_then(handlers::get_site_clicks)
     }
 
-    pub fn partner_get_clicks(
-        db: DB,
+    pub fn partner_get_clicks<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("clicks")
             .and(warp::get())
@@ -945,8 +1032,8 @@ pub mod partners {
             .and_then(handlers::get_partner_clicks)
     }
 
-    pub fn get_partner_clicks_exact_date(
-        db: DB,
+    pub fn get_partner_clicks_exact_date<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("clicks")
             .and(warp::get())
@@ -960,8 +1047,8 @@ pub mod partners {
             .and_then(handlers::get_partner_clicks_exact_date)
     }
 
-    pub fn connect_wallet_subid(
-        db: DB,
+    pub fn connect_wallet_subid<D: UserStore + PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("connect")
             .and(warp::post())
@@ -971,8 +1058,8 @@ pub mod partners {
             .and_then(handlers::connect_wallet)
     }
 
-    pub fn get_partner_contacts(
-        db: DB,
+    pub fn get_partner_contacts<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("get")
             .and(warp::get())
@@ -982,8 +1069,8 @@ pub mod partners {
             .and_then(handlers::get_partner_contacts)
     }
 
-    pub fn delete_partner_contacts(
-        db: DB,
+    pub fn delete_partner_contacts<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("delete")
             .and(warp::post())
@@ -994,8 +1081,8 @@ pub mod partners {
             .and_then(handlers::delete_partner_contacts)
     }
 
-    pub fn get_partner_connected_wallets_with_deposits_amount(
-        db: DB,
+    pub fn get_partner_connected_wallets_with_deposits_amount<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("connected_betted")
             .and(warp::get())
@@ -1007,8 +1094,8 @@ pub mod partners {
             .and_then(handlers::get_partner_connected_wallets_with_deposits_amount)
     }
 
-    pub fn get_partner_connected_wallets(
-        db: DB,
+    pub fn get_partner_connected_wallets<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("connected")
             .and(warp::get())
@@ -1020,8 +1107,8 @@ pub mod partners {
             .and_then(handlers::get_partner_connected_wallets)
     }
 
-    pub fn get_partner_connected_wallets_exact_date(
-        db: DB,
+    pub fn get_partner_connected_wallets_exact_date<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("connected")
             .and(warp::get())
@@ -1035,8 +1122,8 @@ pub mod partners {
             .and_then(handlers::get_partner_connected_wallets_exact_date)
     }
 
-    pub fn get_partner_connected_wallets_betted_exact_date(
-        db: DB,
+    pub fn get_partner_connected_wallets_betted_exact_date<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("connected_betted")
             .and(warp::get())
@@ -1050,8 +1137,8 @@ pub mod partners {
             .and_then(handlers::get_partner_connected_wallets_betted_exact_date)
     }
 
-    pub fn get_conected_totals(
-        db: DB,
+    pub fn get_conected_totals<D: PartnerStore + BetStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("connected" / "totals")
             .and(warp::get())
@@ -1061,8 +1148,8 @@ pub mod partners {
             .and_then(handlers::get_connected_totals)
     }
 
-    pub fn get_partner_connected_wallets_info(
-        db: DB,
+    pub fn get_partner_connected_wallets_info<D: PartnerStore + BetStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("wallets")
             .and(warp::get())
@@ -1074,8 +1161,8 @@ pub mod partners {
             .and_then(handlers::get_partner_connected_wallets_info)
     }
 
-    pub fn submit_partner_withdraw_request(
-        db: DB,
+    pub fn submit_partner_withdraw_request<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("withdraw")
             .and(warp::post())
@@ -1085,8 +1172,8 @@ pub mod partners {
             .and_then(handlers::submit_withdrawal)
     }
 
-    pub fn partner_change_password(
-        db: DB,
+    pub fn partner_change_password<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path!("password")
             .and(warp::put())
@@ -1096,8 +1183,8 @@ pub mod partners {
             .and_then(handlers::partner_change_password)
     }
 
-    pub fn partner_get_withdrawals(
-        db: DB,
+    pub fn partner_get_withdrawals<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("withdrawals")
             .and(warp::get())
@@ -1108,14 +1195,14 @@ pub mod partners {
             .and_then(handlers::get_withdrawal_requests)
     }
 
-    pub fn partner_change(
-        db: DB,
+    pub fn partner_change<D: PartnerStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("change").and(partner_change_password(db.clone()))
     }
 
-    pub fn partners(
-        db: DB,
+    pub fn partners<D: UserStore + PartnerStore + BetStore>(
+        db: D,
     ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
         warp::path("partner").and(
             register_partner(db.clone())
@@ -1,106 +1,42 @@
-use crate::games::{
-    Apples, BigSlots, CoinFlip, Dice, Mines, Plinko, Poker, Race, Rocket, Roulette, Slots,
-    StatefulGameEng, StatefullTest, Wheel, RPS,
+use crate::db::{BetStore, EngineStore, GameStateStore, SeedStore};
+use crate::errors::ApiError;
+use crate::games::registry::{find_game, GameParser};
+use crate::games::StatefulGameEng;
+use crate::jackpot::{jackpot_roll, propagate_jackpot};
+use crate::metrics;
+use crate::models::db_models::{
+    Bet, Game, GameLimits, GameResult, GameState, GameStep, ServerSeed, UserSeed,
 };
-use crate::models::db_models::GameState;
-use crate::models::json_responses::BetExpanded;
-use crate::tools::blake_hash_256_u64;
+use crate::models::json_requests::{ContinueGame, PropagatedBet};
+use crate::models::json_responses::{
+    BetExpanded, BetRejected, BetRejectionReason, BetVerification,
+};
+use crate::tools::{blake_hash, blake_hash_256_u64};
 use crate::DB;
 use crate::{communication::*, games::GameEng};
+use prometheus::IntGauge;
 use rust_decimal::Decimal;
 use serde_json::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::error::SendError;
+use tokio::sync::watch;
+use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
 pub fn parse_stateless_game(
     game_name: &str,
     params: &str,
 ) -> Result<Option<Box<dyn GameEng>>, Error> {
-    match game_name {
-        "CoinFlip" => match serde_json::from_str::<CoinFlip>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing CoinFlip game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Dice" => match serde_json::from_str::<Dice>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Dice game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "RPS" => match serde_json::from_str::<RPS>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing RPS game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Race" => match serde_json::from_str::<Race>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Race game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Wheel" => match serde_json::from_str::<Wheel>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Wheel game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Rocket" => match serde_json::from_str::<Rocket>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
+    match find_game(game_name).map(|game| &game.parser) {
+        Some(GameParser::Stateless(parse)) => match parse(params) {
+            Ok(gm) => Ok(Some(gm)),
             Err(e) => {
-                error!("Error deserializing Rocket game: `{:?}`", e);
+                error!("Error deserializing {} game: `{:?}`", game_name, e);
                 Err(e)
             }
         },
-        "Thimbles" => match serde_json::from_str::<Race>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Thimbles game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Crash" => match serde_json::from_str::<Rocket>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Crash game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Plinko" => match serde_json::from_str::<Plinko>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Plinko game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "CarRace" => match serde_json::from_str::<Race>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing CarRace game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Slots" => match serde_json::from_str::<Slots>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Slots game: `{:?}`", e);
-                Err(e)
-            }
-        },
-        "Roulette" => match serde_json::from_str::<Roulette>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Roulette game: `{:?}`", e);
-                Err(e)
-            }
-        },
-
         _ => Ok(None),
     }
 }
@@ -109,97 +45,691 @@ pub fn parse_statefull_game(
     game_name: &str,
     params: &str,
 ) -> Result<Option<Box<dyn StatefulGameEng>>, Error> {
-    match game_name {
-        "Mines" => match serde_json::from_str::<Mines>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
+    match find_game(game_name).map(|game| &game.parser) {
+        Some(GameParser::Stateful(parse)) => match parse(params) {
+            Ok(gm) => Ok(Some(gm)),
             Err(e) => {
-                error!("Error deserializing Mines game: `{:?}`", e);
+                error!("Error deserializing {} game: `{:?}`", game_name, e);
                 Err(e)
             }
         },
-        "Poker" => match serde_json::from_str::<Poker>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
+        _ => Ok(None),
+    }
+}
+
+pub type StatelessGameEngines = Arc<HashMap<u64, Box<dyn GameEng>>>;
+pub type StatefulGameEngines = Arc<HashMap<u64, Box<dyn StatefulGameEng>>>;
+
+pub type StatelessGameEnginesReceiver = watch::Receiver<StatelessGameEngines>;
+pub type StatelessGameEnginesSender = watch::Sender<StatelessGameEngines>;
+
+pub type StatefulGameEnginesReceiver = watch::Receiver<StatefulGameEngines>;
+pub type StatefulGameEnginesSender = watch::Sender<StatefulGameEngines>;
+
+pub fn parse_stateless_games(games: &[Game]) -> Result<HashMap<u64, Box<dyn GameEng>>, Error> {
+    let mut game_engines = HashMap::with_capacity(games.len());
+    for game in games {
+        if let Some(game_eng) = parse_stateless_game(&game.name, &game.parameters)? {
+            game_engines.insert(game.id as u64, game_eng);
+        }
+    }
+    Ok(game_engines)
+}
+
+pub fn parse_statefull_games(
+    games: &[Game],
+) -> Result<HashMap<u64, Box<dyn StatefulGameEng>>, Error> {
+    let mut game_engines = HashMap::with_capacity(games.len());
+    for game in games {
+        if let Some(game_eng) = parse_statefull_game(&game.name, &game.parameters)? {
+            game_engines.insert(game.id as u64, game_eng);
+        }
+    }
+    Ok(game_engines)
+}
+
+/// Reloads parameters of the games when the `Game` table changes
+///
+/// The new games are swapped in all the engines at once, if any game fails to parse the old
+/// games are kept
+pub struct GamesReloader {
+    db: DB,
+    stateless_sender: StatelessGameEnginesSender,
+    stateful_sender: StatefulGameEnginesSender,
+}
+
+impl GamesReloader {
+    pub fn new(
+        db: DB,
+        stateless_sender: StatelessGameEnginesSender,
+        stateful_sender: StatefulGameEnginesSender,
+    ) -> Self {
+        Self {
+            db,
+            stateless_sender,
+            stateful_sender,
+        }
+    }
+
+    async fn reload(&self) {
+        let games = match self.db.fetch_all_games().await {
+            Ok(games) => games,
             Err(e) => {
-                error!("Error deserializing Poker game: `{:?}`", e);
-                Err(e)
+                error!("Error fetching games from db: {:?}", e);
+                return;
             }
-        },
-        "Apples" => match serde_json::from_str::<Apples>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing Apples game: `{:?}`", e);
-                Err(e)
+        };
+
+        let (stateless_games, stateful_games) =
+            match (parse_stateless_games(&games), parse_statefull_games(&games)) {
+                (Ok(stateless_games), Ok(stateful_games)) => (stateless_games, stateful_games),
+                _ => {
+                    error!("Error parsing games, keeping the old parameters");
+                    return;
+                }
+            };
+
+        self.stateless_sender
+            .send_replace(Arc::new(stateless_games));
+        self.stateful_sender.send_replace(Arc::new(stateful_games));
+        info!("Games reloaded");
+    }
+
+    pub async fn run(self) {
+        info!("Starting games reloader");
+        loop {
+            let mut listener = match self.db.listen_game_changes().await {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Error listening to the game changes: {:?}", e);
+                    sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+
+            // changes could have been missed while the listener was down
+            self.reload().await;
+
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => {
+                        debug!("Recieved game change: {:?}", notification);
+                        self.reload().await;
+                    }
+                    Err(e) => {
+                        error!("Error recieving game change: {:?}", e);
+                        break;
+                    }
+                }
             }
-        },
-        "BigSlots" => match serde_json::from_str::<BigSlots>(params) {
-            Ok(gm) => Ok(Some(Box::new(gm))),
-            Err(e) => {
-                error!("Error deserializing BigSlots game: `{:?}`", e);
-                Err(e)
+        }
+    }
+}
+
+/// Interval between the searches of the expired game states
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Steps after which the resolution of an expired game is given up
+const MAX_RESOLVE_STEPS: usize = 1000;
+
+/// Resolves the games that were not finished within the `Game.state_timeout`
+///
+/// The expired states are resolved by the stateful engines, so the steps of the sweeper don't
+/// interleave with the steps of the player
+pub struct GameStateSweeper<D = DB> {
+    db: D,
+    stateful_bet_sender: StatefulEngineBetRouter,
+}
+
+impl<D: GameStateStore> GameStateSweeper<D> {
+    pub fn new(db: D, stateful_bet_sender: StatefulEngineBetRouter) -> Self {
+        Self {
+            db,
+            stateful_bet_sender,
+        }
+    }
+
+    pub async fn run(self) {
+        info!("Starting game state sweeper");
+        loop {
+            sleep(SWEEP_INTERVAL).await;
+
+            let states = match self.db.fetch_expired_game_states().await {
+                Ok(states) => states,
+                Err(e) => {
+                    error!("Error fetching expired game states: {:?}", e);
+                    continue;
+                }
+            };
+
+            for state in states {
+                debug!("Resolving expired state: {:?}", state);
+                if let Err(e) = self
+                    .stateful_bet_sender
+                    .send(EnginePropagatedBet::ResolveGame(state))
+                {
+                    error!("Error propagating state to the stateful engine: {:?}", e);
+                    return;
+                }
             }
-        },
+        }
+    }
+}
 
-        _ => Ok(None),
+/// Checks the stake of every game in the bet and the amount of games against the limits
+pub fn check_limits(
+    limits: &GameLimits,
+    amount: Decimal,
+    num_games: u64,
+) -> Result<(), (BetRejectionReason, String)> {
+    if num_games > limits.max_num_games as u64 {
+        return Err((
+            BetRejectionReason::TooManyGames,
+            format!(
+                "Maximum amount of games per bet is {}",
+                limits.max_num_games
+            ),
+        ));
+    }
+
+    if amount < limits.min_bet {
+        return Err((
+            BetRejectionReason::StakeTooLow,
+            format!("Minimum bet is {}", limits.min_bet.normalize()),
+        ));
+    }
+
+    if amount > limits.max_bet {
+        return Err((
+            BetRejectionReason::StakeTooHigh,
+            format!("Maximum bet is {}", limits.max_bet.normalize()),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks the highest payout of the bet against the limits, `None` if the bet data is not valid
+pub fn check_payout(
+    limits: &GameLimits,
+    max_payout: Option<Decimal>,
+) -> Result<(), (BetRejectionReason, String)> {
+    let max_payout = max_payout.ok_or((BetRejectionReason::InvalidBet, "Bad bet data".into()))?;
+
+    if max_payout > limits.max_payout {
+        return Err((
+            BetRejectionReason::PayoutTooHigh,
+            format!("Maximum payout is {}", limits.max_payout.normalize()),
+        ));
     }
+
+    Ok(())
+}
+
+pub fn reject_bet(
+    manager_sender: &WsManagerEventSender,
+    uuid: &Option<String>,
+    reason_code: BetRejectionReason,
+    message: &str,
+) {
+    if let Some(uuid) = uuid {
+        if let Err(e) = manager_sender.send(WsManagerEvent::RejectBet(BetRejected {
+            uuid: uuid.clone(),
+            reason_code,
+            message: message.into(),
+        })) {
+            error!("Error propagating bet rejection: {:?}", e);
+        }
+    }
+}
+
+/// Rejects the bet of the game played by the engines and counts the rejection
+fn reject_game_bet(
+    manager_sender: &WsManagerEventSender,
+    game_id: i64,
+    uuid: &Option<String>,
+    reason_code: BetRejectionReason,
+    message: &str,
+) {
+    metrics::bet_rejected(game_id, reason_code);
+    reject_bet(manager_sender, uuid, reason_code, message);
 }
 
 pub fn generate_random_numbers(
     client_seed: &str,
     server_seed: &str,
-    timestamp: u64,
+    nonce: u64,
     amount: u64,
 ) -> Vec<u64> {
-    let postfix = format!("{timestamp}{client_seed}{server_seed}");
+    let postfix = format!("{nonce}:{client_seed}:{server_seed}");
 
     (0..amount)
-        .map(|index| blake_hash_256_u64(&format!("{index}{postfix}")))
+        .map(|index| blake_hash_256_u64(&format!("{index}:{postfix}")))
         .collect()
 }
 
-pub struct Engine {
-    db: DB,
+async fn fetch_revealed_seeds<D: SeedStore>(
+    db: &D,
+    userseed_id: i64,
+    serverseed_id: i64,
+) -> Result<(UserSeed, ServerSeed), ApiError> {
+    let user_seed = db
+        .fetch_user_seed_by_id(userseed_id)
+        .await
+        .map_err(ApiError::DbError)?
+        .ok_or(ApiError::ArbitraryError(format!(
+            "User seed `{}` not found",
+            userseed_id
+        )))?;
+
+    let server_seed = db
+        .fetch_server_seed_by_id(serverseed_id)
+        .await
+        .map_err(ApiError::DbError)?
+        .ok_or(ApiError::ArbitraryError(format!(
+            "Server seed `{}` not found",
+            serverseed_id
+        )))?;
+
+    if !server_seed.revealed {
+        return Err(ApiError::ServerSeedNotRevealed(serverseed_id));
+    }
+
+    Ok((user_seed, server_seed))
+}
+
+fn parse_profits(profits: &str) -> Vec<Decimal> {
+    profits
+        .trim_matches(|c| c == '[' || c == ']')
+        .split(',')
+        .filter_map(|profit| Decimal::from_str(profit.trim()).ok())
+        .map(|profit| profit.normalize())
+        .collect()
+}
+
+fn replay_stateful_steps(
+    game_eng: &dyn StatefulGameEng,
+    bet: &Bet,
+    steps: Vec<(GameStep, Vec<u64>)>,
+) -> Option<GameResult> {
+    // the stake of the bet includes the stakes added by the following steps
+    let added_stake: Decimal = steps.iter().skip(1).map(|(step, _)| step.stake).sum();
+
+    let mut state: Option<GameState> = None;
+    for (step, random_numbers) in steps {
+        let game_result = if let Some(state) = &state {
+            game_eng.continue_playing(
+                state,
+                &ContinueGame {
+                    game_id: bet.game_id,
+                    coin_id: bet.coin_id,
+                    user_id: Some(bet.user_id),
+                    uuid: Some(bet.uuid.clone()),
+                    data: step.data.clone(),
+                },
+                &random_numbers,
+            )?
+        } else {
+            game_eng.start_playing(
+                &PropagatedBet {
+                    game_id: bet.game_id,
+                    amount: bet.amount - added_stake,
+                    coin_id: bet.coin_id,
+                    user_id: Some(bet.user_id),
+                    uuid: Some(bet.uuid.clone()),
+                    data: step.data.clone(),
+                    stop_loss: Decimal::ZERO,
+                    stop_win: Decimal::ZERO,
+                    num_games: 1,
+                },
+                &random_numbers,
+            )?
+        };
+
+        if game_result.finished {
+            return Some(game_result);
+        }
+
+        if let Some(state) = &mut state {
+            state.state = game_result.data;
+            state.amount += step.stake;
+        } else {
+            state = Some(GameState {
+                amount: bet.amount - added_stake,
+                bet_info: step.data,
+                state: game_result.data,
+                uuid: bet.uuid.clone(),
+                game_id: bet.game_id,
+                user_id: bet.user_id,
+                coin_id: bet.coin_id,
+                userseed_id: step.userseed_id,
+                serverseed_id: step.serverseed_id,
+                ..Default::default()
+            });
+        }
+    }
+
+    None
+}
+
+/// Replays the bet from the seeds it was played with and compares the result with the stored one
+///
+/// Refuses to replay the bet if any of its server seeds is not revealed yet
+pub async fn replay_bet<D: SeedStore + BetStore>(
+    db: &D,
+    bet: &Bet,
+) -> Result<BetVerification, ApiError> {
+    let game = db
+        .fetch_game(bet.game_id)
+        .await
+        .map_err(ApiError::DbError)?
+        .ok_or(ApiError::GameWithIDDoesntExist(bet.game_id))?;
+
+    let (user_seed, server_seed) =
+        fetch_revealed_seeds(db, bet.userseed_id, bet.serverseed_id).await?;
+
+    let game_result = if let Some(steps) = &bet.steps {
+        let steps: Vec<GameStep> =
+            serde_json::from_str(steps).map_err(|_| ApiError::BetReplayError(bet.id))?;
+
+        let mut seeded_steps = Vec::with_capacity(steps.len());
+        for step in steps {
+            let (step_user_seed, step_server_seed) =
+                fetch_revealed_seeds(db, step.userseed_id, step.serverseed_id).await?;
+            seeded_steps.push((step, step_user_seed.user_seed, step_server_seed.server_seed));
+        }
+
+        let game_eng = parse_statefull_game(&game.name, &game.parameters)
+            .map_err(|_| ApiError::BetReplayError(bet.id))?
+            .ok_or(ApiError::BetReplayError(bet.id))?;
+
+        let steps = seeded_steps
+            .into_iter()
+            .map(|(step, user_seed, server_seed)| {
+                let random_numbers = generate_random_numbers(
+                    &user_seed,
+                    &server_seed,
+                    step.nonce,
+                    game_eng.numbers_per_bet(),
+                );
+                (step, random_numbers)
+            })
+            .collect();
+
+        replay_stateful_steps(game_eng.as_ref(), bet, steps)
+            .ok_or(ApiError::BetReplayError(bet.id))?
+    } else {
+        let game_eng = parse_stateless_game(&game.name, &game.parameters)
+            .map_err(|_| ApiError::BetReplayError(bet.id))?
+            .ok_or(ApiError::BetReplayError(bet.id))?;
+
+        // stop conditions are not stored, so only the games that were actually played are replayed
+        let random_numbers = generate_random_numbers(
+            &user_seed.user_seed,
+            &server_seed.server_seed,
+            bet.nonce as u64,
+            game_eng.numbers_per_bet() * bet.num_games as u64,
+        );
+
+        game_eng
+            .play(
+                &PropagatedBet {
+                    game_id: bet.game_id,
+                    amount: bet.amount,
+                    coin_id: bet.coin_id,
+                    user_id: Some(bet.user_id),
+                    uuid: Some(bet.uuid.clone()),
+                    data: bet.bet_info.clone(),
+                    stop_loss: Decimal::ZERO,
+                    stop_win: Decimal::ZERO,
+                    num_games: bet.num_games as u64,
+                },
+                &random_numbers,
+            )
+            .ok_or(ApiError::BetReplayError(bet.id))?
+    };
+
+    let replayed_outcomes = format!("{:?}", game_result.outcomes);
+    let replayed_profits: Vec<Decimal> = game_result
+        .profits
+        .iter()
+        .map(|profit| profit.normalize())
+        .collect();
+    let verified =
+        replayed_outcomes.eq(&bet.outcomes) && replayed_profits.eq(&parse_profits(&bet.profits));
+
+    Ok(BetVerification {
+        bet_id: bet.id,
+        game_id: bet.game_id,
+        user_seed: user_seed.user_seed,
+        server_seed_hash: blake_hash(&server_seed.server_seed),
+        server_seed: server_seed.server_seed,
+        nonce: bet.nonce,
+        outcomes: bet.outcomes.clone(),
+        profits: bet.profits.clone(),
+        replayed_outcomes,
+        replayed_profits: format!("{:?}", replayed_profits),
+        verified,
+    })
+}
+
+pub struct Engine<D = DB> {
+    db: D,
     manager_sender: WsManagerEventSender,
     bet_reciever: EngineBetReciever,
-    game_engines: HashMap<u64, Box<dyn GameEng>>,
-    stateful_bet_sender: StatefulEngineBetSender,
+    game_engines: StatelessGameEnginesReceiver,
+    stateful_bet_sender: StatefulEngineBetRouter,
 }
 
-impl Engine {
-    pub async fn new(
-        db: DB,
+impl<D: EngineStore> Engine<D> {
+    pub fn new(
+        db: D,
         manager_sender: WsManagerEventSender,
         bet_reciever: EngineBetReciever,
-        stateful_bet_sender: StatefulEngineBetSender,
+        stateful_bet_sender: StatefulEngineBetRouter,
+        game_engines: StatelessGameEnginesReceiver,
     ) -> Self {
-        let games: HashMap<u64, Box<dyn GameEng>> = db
-            .fetch_all_games()
-            .await
-            .expect("Error fetching games from db")
-            .into_iter()
-            .map(|game| {
-                (
-                    game.id as u64,
-                    parse_stateless_game(&game.name, &game.parameters).unwrap(),
-                )
-            })
-            .filter_map(|(game_id, game_data)| {
-                if game_data.is_some() {
-                    Some((game_id, game_data.unwrap()))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
         Self {
             db,
             manager_sender,
             bet_reciever,
-            game_engines: games,
+            game_engines,
             stateful_bet_sender,
         }
     }
 
+    /// Plays the stateless bet and settles it, the rejection is returned to the caller
+    async fn place_bet(
+        &self,
+        game_eng: &dyn GameEng,
+        bet: &PropagatedBet,
+    ) -> Result<BetExpanded, (BetRejectionReason, String)> {
+        let limits =
+            if let Ok(Some(limits)) = self.db.fetch_game_limits(bet.game_id, bet.coin_id).await {
+                limits
+            } else {
+                return Err((
+                    BetRejectionReason::LimitsNotFound,
+                    "The game is not available for the coin".into(),
+                ));
+            };
+
+        check_limits(&limits, bet.amount, bet.num_games)?;
+        check_payout(&limits, game_eng.max_payout(bet))?;
+
+        let amount = if let Ok(Some(amount)) = self
+            .db
+            .fetch_amount(bet.user_id.unwrap(), bet.coin_id)
+            .await
+        {
+            amount
+        } else {
+            return Err((
+                BetRejectionReason::InsufficientBalance,
+                "Insufficient balance".into(),
+            ));
+        };
+
+        if !bet.amount.is_zero() && bet.amount * Decimal::from(bet.num_games) > amount {
+            return Err((
+                BetRejectionReason::InsufficientBalance,
+                "Insufficient balance".into(),
+            ));
+        }
+
+        let user_seed = self
+            .db
+            .fetch_current_user_seed(bet.user_id.unwrap())
+            .await
+            .map_err(|e| {
+                error!(
+                    "Error getting user seed for user `{}`: {:?}",
+                    bet.user_id.unwrap(),
+                    e
+                );
+                (
+                    BetRejectionReason::MissingSeed,
+                    "User seed not found".to_string(),
+                )
+            })?;
+
+        let server_seed = self
+            .db
+            .fetch_current_server_seed_unhashed(bet.user_id.unwrap())
+            .await
+            .map_err(|e| {
+                error!(
+                    "Error getting server seed for user `{}`: {:?}",
+                    bet.user_id.unwrap(),
+                    e
+                );
+                (
+                    BetRejectionReason::MissingSeed,
+                    "Server seed not found".to_string(),
+                )
+            })?;
+
+        let nonce = self
+            .db
+            .next_nonce(user_seed.id, server_seed.id)
+            .await
+            .map_err(|e| {
+                error!("Error getting nonce for the bet {:?}: {:?}", bet, e);
+                (
+                    BetRejectionReason::InternalError,
+                    "Error getting nonce".to_string(),
+                )
+            })?;
+
+        let timestamp = chrono::offset::Utc::now();
+
+        let random_numbers = generate_random_numbers(
+            &user_seed.user_seed,
+            &server_seed.server_seed,
+            nonce as u64,
+            game_eng.numbers_per_bet() * bet.num_games,
+        );
+
+        let game_result = if let Some(res) = game_eng.play(bet, &random_numbers) {
+            res
+        } else {
+            warn!("Couldn't proccess bet");
+            return Err((BetRejectionReason::InvalidBet, "Bad bet data".into()));
+        };
+
+        let outcomes = format!("{:?}", game_result.outcomes);
+        let profits = format!("{:?}", game_result.profits);
+
+        // Apply taking money/sending profit
+        let timer = metrics::SETTLEMENT_DURATION
+            .with_label_values(&["stateless"])
+            .start_timer();
+        let settled = self
+            .db
+            .settle_bet(
+                bet.amount * Decimal::from(game_result.num_games),
+                game_result.total_profit,
+                &Bet {
+                    id: 0,
+                    timestamp,
+                    amount: bet.amount,
+                    profit: game_result.total_profit,
+                    num_games: game_result.num_games as i32,
+                    outcomes: outcomes.clone(),
+                    profits: profits.clone(),
+                    bet_info: bet.data.clone(),
+                    state: None,
+                    steps: None,
+                    uuid: bet.uuid.clone().unwrap(),
+                    nonce,
+                    game_id: bet.game_id,
+                    user_id: bet.user_id.unwrap(),
+                    coin_id: bet.coin_id,
+                    userseed_id: user_seed.id,
+                    serverseed_id: server_seed.id,
+                },
+                false,
+                Some(jackpot_roll(
+                    &user_seed.user_seed,
+                    &server_seed.server_seed,
+                    nonce as u64,
+                )),
+            )
+            .await;
+        timer.observe_duration();
+        let bet_id = match settled {
+            Ok(Some(settled)) => {
+                let bet_id = settled.bet_id;
+                propagate_jackpot(&self.manager_sender, settled);
+                metrics::bet_processed(bet.game_id);
+                bet_id
+            }
+            Ok(None) => {
+                warn!("Insufficient balance to settle the bet: {:?}", bet);
+                return Err((
+                    BetRejectionReason::InsufficientBalance,
+                    "Insufficient balance".into(),
+                ));
+            }
+            Err(e) => {
+                error!("Error settling the bet {:?}: {:?}", bet, e);
+                return Err((
+                    BetRejectionReason::InternalError,
+                    "Error settling the bet".into(),
+                ));
+            }
+        };
+
+        let user = if let Ok(Some(user)) = self.db.fetch_user(bet.user_id.unwrap()).await {
+            user
+        } else {
+            error!("Unable to find user: {:?}", bet.user_id);
+            return Err((
+                BetRejectionReason::InternalError,
+                "Unable to find user".into(),
+            ));
+        };
+
+        Ok(BetExpanded {
+            id: bet_id,
+            timestamp,
+            amount: bet.amount,
+            profit: game_result.total_profit,
+            bet_info: game_result.data,
+            state: None,
+            game_id: bet.game_id,
+            user_id: bet.user_id.unwrap(),
+            username: user.username,
+            coin_id: bet.coin_id,
+            userseed_id: user_seed.id,
+            serverseed_id: server_seed.id,
+            nonce,
+            outcomes,
+            num_games: game_result.num_games as i32,
+            uuid: bet.uuid.clone().unwrap(),
+            profits,
+        })
+    }
+
     pub async fn run(self) {
         info!("Starting engine");
         loop {
@@ -210,209 +740,71 @@ impl Engine {
                     break;
                 }
             };
-            match &orig_bet {
+            // bets keep the parameters of the games they were recieved with
+            let game_engines = self.game_engines.borrow().clone();
+            match orig_bet {
                 EnginePropagatedBet::NewBet(bet) => {
-                    if bet.num_games > 100 {
-                        continue;
-                    }
-
-                    let game_eng =
-                        if let Some(game_eng) = self.game_engines.get(&(bet.game_id as u64)) {
-                            game_eng
-                        } else {
-                            warn!("Stateless game `{:?}` not found", bet.game_id);
-                            if let Err(e) = self.stateful_bet_sender.send(orig_bet) {
-                                error!("Error propagating bet to the stateful engine: {:?}", e);
-                                break;
-                            }
-                            continue;
-                        };
-
-                    let coin = if let Ok(Some(coin)) = self.db.fetch_coin_by_id(bet.coin_id).await {
-                        coin
-                    } else {
-                        continue;
-                    };
-
-                    if (bet.amount * Decimal::from(bet.num_games)) / coin.price > Decimal::from(50)
-                    {
-                        continue;
-                    }
-
-                    let amount = if let Ok(Some(amount)) = self
-                        .db
-                        .fetch_amount(bet.user_id.unwrap(), bet.coin_id)
-                        .await
-                    {
-                        amount
+                    let game_eng = if let Some(game_eng) = game_engines.get(&(bet.game_id as u64)) {
+                        game_eng
                     } else {
-                        continue;
-                    };
-
-                    if !bet.amount.is_zero() && bet.amount * Decimal::from(bet.num_games) > amount {
-                        continue;
-                    }
-
-                    let user_seed =
-                        match self.db.fetch_current_user_seed(bet.user_id.unwrap()).await {
-                            Ok(seed) => seed,
-                            Err(e) => {
-                                error!(
-                                    "Error getting user seed for user `{}`: {:?}",
-                                    bet.user_id.unwrap(),
-                                    e
-                                );
-                                continue;
-                            }
-                        };
-
-                    let server_seed = match self
-                        .db
-                        .fetch_current_server_seed(bet.user_id.unwrap())
-                        .await
-                    {
-                        Ok(seed) => seed,
-                        Err(e) => {
-                            error!(
-                                "Error getting server seed for user `{}`: {:?}",
-                                bet.user_id.unwrap(),
-                                e
-                            );
-                            continue;
+                        warn!("Stateless game `{:?}` not found", bet.game_id);
+                        if let Err(e) = self
+                            .stateful_bet_sender
+                            .send(EnginePropagatedBet::NewBet(bet))
+                        {
+                            error!("Error propagating bet to the stateful engine: {:?}", e);
+                            break;
                         }
-                    };
-
-                    let timestamp = chrono::offset::Utc::now();
-
-                    let random_numbers = generate_random_numbers(
-                        &user_seed.user_seed,
-                        &server_seed.server_seed,
-                        timestamp.timestamp_millis() as u64,
-                        game_eng.numbers_per_bet() * bet.num_games,
-                    );
-
-                    let game_result = if let Some(res) = game_eng.play(&bet, &random_numbers) {
-                        res
-                    } else {
-                        warn!("Couldn't proccess bet");
                         continue;
                     };
 
-                    // Apply taking money/sending profit
-
-                    match self
-                        .db
-                        .decrease_balance(
-                            bet.user_id.unwrap(),
-                            bet.coin_id,
-                            bet.amount * Decimal::from(game_result.num_games),
-                        )
-                        .await
-                    {
-                        Ok(success) => {
-                            if !success {
-                                continue;
-                            }
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error decreasing balance for user `{}`: {:?}",
-                                bet.user_id.unwrap(),
-                                e
-                            );
-                            continue;
-                        }
-                    }
-
-                    match self
-                        .db
-                        .increase_balance(
-                            bet.user_id.unwrap(),
-                            bet.coin_id,
-                            &game_result.total_profit,
-                        )
-                        .await
-                    {
-                        Ok(success) => {
-                            if !success {
-                                warn!(
-                            "Increasing balance wasn't successful bet data: `{:?}` amount: `{}`",
-                            bet, &game_result.total_profit
-                        );
-                                continue;
+                    match self.place_bet(game_eng.as_ref(), &bet).await {
+                        Ok(constructed_bet) => {
+                            if let Err(e) = self
+                                .manager_sender
+                                .send(WsManagerEvent::PropagateBet(constructed_bet))
+                            {
+                                error!("Error propagating bet: {:?}", e);
+                                break;
                             }
                         }
-                        Err(e) => {
-                            error!(
-                                "Error increasing balance for user `{}`: {:?}",
-                                bet.user_id.unwrap(),
-                                e
+                        Err((reason_code, message)) => {
+                            reject_game_bet(
+                                &self.manager_sender,
+                                bet.game_id,
+                                &bet.uuid,
+                                reason_code,
+                                &message,
                             );
-                            continue;
                         }
                     }
-
-                    let outcomes = format!("{:?}", game_result.outcomes);
-                    let profits = format!("{:?}", game_result.profits);
-
-                    if let Err(e) = self
-                        .db
-                        .place_bet(
-                            bet.amount,
-                            game_result.total_profit,
-                            game_result.num_games as i32,
-                            &outcomes,
-                            &profits,
-                            &bet.data,
-                            None,
-                            bet.uuid.as_ref().unwrap(),
-                            bet.game_id,
-                            bet.user_id.unwrap(),
-                            bet.coin_id,
-                            user_seed.id,
-                            server_seed.id,
-                        )
-                        .await
-                    {
-                        error!("Error adding bet to the db: {:?}", e);
-                    };
-
-                    let user =
-                        if let Ok(Some(user)) = self.db.fetch_user(bet.user_id.unwrap()).await {
-                            user
-                        } else {
-                            error!("Unable to find user: {:?}", bet.user_id);
-                            continue;
-                        };
-
-                    let constructed_bet = BetExpanded {
-                        id: 0,
-                        timestamp,
-                        amount: bet.amount,
-                        profit: game_result.total_profit,
-                        bet_info: game_result.data,
-                        state: None,
-                        game_id: bet.game_id,
-                        user_id: bet.user_id.unwrap(),
-                        username: user.username,
-                        coin_id: bet.coin_id,
-                        userseed_id: user_seed.id,
-                        serverseed_id: server_seed.id,
-                        outcomes,
-                        num_games: game_result.num_games as i32,
-                        uuid: bet.uuid.clone().unwrap(),
-                        profits,
+                }
+                EnginePropagatedBet::AutoBet(bet, result_sender) => {
+                    let result = match game_engines.get(&(bet.game_id as u64)) {
+                        Some(game_eng) => self.place_bet(game_eng.as_ref(), &bet).await,
+                        None => Err((
+                            BetRejectionReason::GameNotFound,
+                            "Only stateless games can be auto played".into(),
+                        )),
                     };
+                    if let Err((reason_code, _)) = &result {
+                        metrics::bet_rejected(bet.game_id, *reason_code);
+                    }
 
-                    if let Err(e) = self
-                        .manager_sender
-                        .send(WsManagerEvent::PropagateBet(constructed_bet))
-                    {
-                        error!("Error propagating bet: {:?}", e);
-                        break;
+                    if let Ok(constructed_bet) = &result {
+                        if let Err(e) = self
+                            .manager_sender
+                            .send(WsManagerEvent::PropagateBet(constructed_bet.clone()))
+                        {
+                            error!("Error propagating bet: {:?}", e);
+                            break;
+                        }
                     }
+                    // the session could have been stopped in the meantime
+                    let _ = result_sender.send(result);
                 }
-                EnginePropagatedBet::ContinueGame(_) => {
+                // sent straight to the stateful engines, forwarded if they are sent here anyway
+                EnginePropagatedBet::ContinueGame(_) | EnginePropagatedBet::ResolveGame(_) => {
                     if let Err(e) = self.stateful_bet_sender.send(orig_bet) {
                         error!("Error propagating bet to the stateful engine: {:?}", e);
                         break;
@@ -423,45 +815,30 @@ impl Engine {
     }
 }
 
-pub struct StatefulGameEngine {
+pub struct StatefulGameEngine<D = DB> {
     bet_reciever: StatefulEngineBetReciever,
+    queue_depth: IntGauge,
 
-    game_engines: HashMap<u64, Box<dyn StatefulGameEng>>,
-    db: DB,
+    game_engines: StatefulGameEnginesReceiver,
+    db: D,
     manager_sender: WsManagerEventSender,
 }
 
-impl StatefulGameEngine {
-    pub async fn new(
-        db: DB,
+impl<D: EngineStore> StatefulGameEngine<D> {
+    pub fn new(
+        db: D,
         manager_sender: WsManagerEventSender,
         bet_reciever: StatefulEngineBetReciever,
+        game_engines: StatefulGameEnginesReceiver,
+        shard: usize,
     ) -> Self {
-        let games: HashMap<u64, Box<dyn StatefulGameEng>> = db
-            .fetch_all_games()
-            .await
-            .expect("Error fetching games from db")
-            .into_iter()
-            .map(|game| {
-                (
-                    game.id as u64,
-                    parse_statefull_game(&game.name, &game.parameters).unwrap(),
-                )
-            })
-            .filter_map(|(game_id, game_data)| {
-                if game_data.is_some() {
-                    Some((game_id, game_data.unwrap()))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
         Self {
             bet_reciever,
+            queue_depth: metrics::STATEFUL_ENGINE_QUEUE_DEPTH
+                .with_label_values(&[&shard.to_string()]),
             db,
             manager_sender,
-            game_engines: games,
+            game_engines,
         }
     }
 
@@ -475,25 +852,52 @@ impl StatefulGameEngine {
                     break;
                 }
             };
+            self.queue_depth.dec();
+            // bets keep the parameters of the games they were recieved with
+            let game_engines = self.game_engines.borrow().clone();
 
             match bet {
                 EnginePropagatedBet::NewBet(bet) => {
                     debug!("recieved `NewBet` event: {:?}", bet);
-                    let game_eng =
-                        if let Some(game_eng) = self.game_engines.get(&(bet.game_id as u64)) {
-                            game_eng
-                        } else {
-                            warn!("Statefull game `{:?}` not found", bet.game_id);
-                            continue;
-                        };
+                    let game_eng = if let Some(game_eng) = game_engines.get(&(bet.game_id as u64)) {
+                        game_eng
+                    } else {
+                        warn!("Statefull game `{:?}` not found", bet.game_id);
+                        reject_game_bet(
+                            &self.manager_sender,
+                            bet.game_id,
+                            &bet.uuid,
+                            BetRejectionReason::GameNotFound,
+                            "Game not found",
+                        );
+                        continue;
+                    };
 
-                    let coin = if let Ok(Some(coin)) = self.db.fetch_coin_by_id(bet.coin_id).await {
-                        coin
+                    let limits = if let Ok(Some(limits)) =
+                        self.db.fetch_game_limits(bet.game_id, bet.coin_id).await
+                    {
+                        limits
                     } else {
+                        reject_game_bet(
+                            &self.manager_sender,
+                            bet.game_id,
+                            &bet.uuid,
+                            BetRejectionReason::LimitsNotFound,
+                            "The game is not available for the coin",
+                        );
                         continue;
                     };
 
-                    if bet.amount / coin.price > Decimal::from(50) {
+                    if let Err((reason_code, message)) = check_limits(&limits, bet.amount, 1)
+                        .and_then(|_| check_payout(&limits, game_eng.max_payout(&bet)))
+                    {
+                        reject_game_bet(
+                            &self.manager_sender,
+                            bet.game_id,
+                            &bet.uuid,
+                            reason_code,
+                            &message,
+                        );
                         continue;
                     }
 
@@ -503,6 +907,13 @@ impl StatefulGameEngine {
                         .await
                     {
                         warn!("State already exists for the bet `{:?}`: {:?}", bet, state);
+                        reject_game_bet(
+                            &self.manager_sender,
+                            bet.game_id,
+                            &bet.uuid,
+                            BetRejectionReason::GameAlreadyStarted,
+                            "The game is already started",
+                        );
                         continue;
                     }
 
@@ -513,10 +924,24 @@ impl StatefulGameEngine {
                     {
                         amount
                     } else {
+                        reject_game_bet(
+                            &self.manager_sender,
+                            bet.game_id,
+                            &bet.uuid,
+                            BetRejectionReason::InsufficientBalance,
+                            "Insufficient balance",
+                        );
                         continue;
                     };
 
                     if !bet.amount.is_zero() && bet.amount > amount {
+                        reject_game_bet(
+                            &self.manager_sender,
+                            bet.game_id,
+                            &bet.uuid,
+                            BetRejectionReason::InsufficientBalance,
+                            "Insufficient balance",
+                        );
                         continue;
                     }
 
@@ -529,13 +954,20 @@ impl StatefulGameEngine {
                                     bet.user_id.unwrap(),
                                     e
                                 );
+                                reject_game_bet(
+                                    &self.manager_sender,
+                                    bet.game_id,
+                                    &bet.uuid,
+                                    BetRejectionReason::MissingSeed,
+                                    "User seed not found",
+                                );
                                 continue;
                             }
                         };
 
                     let server_seed = match self
                         .db
-                        .fetch_current_server_seed(bet.user_id.unwrap())
+                        .fetch_current_server_seed_unhashed(bet.user_id.unwrap())
                         .await
                     {
                         Ok(seed) => seed,
@@ -545,6 +977,28 @@ impl StatefulGameEngine {
                                 bet.user_id.unwrap(),
                                 e
                             );
+                            reject_game_bet(
+                                &self.manager_sender,
+                                bet.game_id,
+                                &bet.uuid,
+                                BetRejectionReason::MissingSeed,
+                                "Server seed not found",
+                            );
+                            continue;
+                        }
+                    };
+
+                    let nonce = match self.db.next_nonce(user_seed.id, server_seed.id).await {
+                        Ok(nonce) => nonce,
+                        Err(e) => {
+                            error!("Error getting nonce for the bet {:?}: {:?}", bet, e);
+                            reject_game_bet(
+                                &self.manager_sender,
+                                bet.game_id,
+                                &bet.uuid,
+                                BetRejectionReason::InternalError,
+                                "Error getting nonce",
+                            );
                             continue;
                         }
                     };
@@ -554,78 +1008,105 @@ impl StatefulGameEngine {
                     let random_numbers = generate_random_numbers(
                         &user_seed.user_seed,
                         &server_seed.server_seed,
-                        timestamp.timestamp_millis() as u64,
+                        nonce as u64,
                         game_eng.numbers_per_bet(),
                     );
 
+                    let steps = serde_json::to_string(&[GameStep {
+                        data: bet.data.clone(),
+                        nonce: nonce as u64,
+                        userseed_id: user_seed.id,
+                        serverseed_id: server_seed.id,
+                        stake: bet.amount,
+                    }])
+                    .unwrap();
+
                     let game_result =
                         if let Some(res) = game_eng.start_playing(&bet, &random_numbers) {
                             res
                         } else {
                             warn!("Couldn't proccess bet {:?}", bet);
+                            reject_game_bet(
+                                &self.manager_sender,
+                                bet.game_id,
+                                &bet.uuid,
+                                BetRejectionReason::InvalidBet,
+                                "Bad bet data",
+                            );
                             continue;
                         };
 
-                    if let Err(e) = self
-                        .db
-                        .decrease_balance(bet.user_id.unwrap(), bet.coin_id, bet.amount)
-                        .await
-                    {
-                        error!("Error decreasing amount for the bet {:?}: {:?}", bet, e);
-                        continue;
-                    }
-
                     if game_result.finished {
                         // game finished
 
-                        if !game_result.total_profit.is_zero() {
-                            if let Err(e) = self
-                                .db
-                                .increase_balance(
-                                    bet.user_id.unwrap(),
-                                    bet.coin_id,
-                                    &game_result.total_profit,
-                                )
-                                .await
-                            {
-                                error!("Error decreasing amount for the bet {:?}: {:?}", bet, e);
-                                continue;
-                            }
-                        }
-
                         let outcomes = format!("{:?}", game_result.outcomes);
                         let profits = format!("{:?}", game_result.profits);
-                        if let Err(e) = self
+                        let timer = metrics::SETTLEMENT_DURATION
+                            .with_label_values(&["stateful"])
+                            .start_timer();
+                        let settled = self
                             .db
-                            .place_bet(
+                            .settle_bet(
                                 bet.amount,
                                 game_result.total_profit,
-                                game_result.num_games as i32,
-                                &outcomes,
-                                &profits,
-                                &bet.data,
-                                Some(&game_result.data),
-                                bet.uuid.as_ref().unwrap(),
-                                bet.game_id,
-                                bet.user_id.unwrap(),
-                                bet.coin_id,
-                                user_seed.id,
-                                server_seed.id,
+                                &Bet {
+                                    id: 0,
+                                    timestamp,
+                                    amount: bet.amount,
+                                    profit: game_result.total_profit,
+                                    num_games: game_result.num_games as i32,
+                                    outcomes: outcomes.clone(),
+                                    profits: profits.clone(),
+                                    bet_info: bet.data.clone(),
+                                    state: Some(game_result.data.clone()),
+                                    steps: Some(steps),
+                                    uuid: bet.uuid.clone().unwrap(),
+                                    nonce,
+                                    game_id: bet.game_id,
+                                    user_id: bet.user_id.unwrap(),
+                                    coin_id: bet.coin_id,
+                                    userseed_id: user_seed.id,
+                                    serverseed_id: server_seed.id,
+                                },
+                                false,
+                                Some(jackpot_roll(
+                                    &user_seed.user_seed,
+                                    &server_seed.server_seed,
+                                    nonce as u64,
+                                )),
                             )
-                            .await
-                        {
-                            error!("Error adding bet to the db: {:?}", e);
-                        };
-
-                        if game_result.num_games > 1 {
-                            if let Err(e) = self
-                                .db
-                                .remove_game_state(bet.game_id, bet.user_id.unwrap(), bet.coin_id)
-                                .await
-                            {
-                                error!("Error removing state from the db {:?}: {:?}", bet, e);
+                            .await;
+                        timer.observe_duration();
+                        let bet_id = match settled {
+                            Ok(Some(settled)) => {
+                                let bet_id = settled.bet_id;
+                                propagate_jackpot(&self.manager_sender, settled);
+                                metrics::bet_processed(bet.game_id);
+                                bet_id
                             }
-                        }
+                            Ok(None) => {
+                                warn!("Insufficient balance to settle the bet: {:?}", bet);
+                                reject_game_bet(
+                                    &self.manager_sender,
+                                    bet.game_id,
+                                    &bet.uuid,
+                                    BetRejectionReason::InsufficientBalance,
+                                    "Insufficient balance",
+                                );
+                                continue;
+                            }
+                            Err(e) => {
+                                error!("Error settling the bet {:?}: {:?}", bet, e);
+                                reject_game_bet(
+                                    &self.manager_sender,
+                                    bet.game_id,
+                                    &bet.uuid,
+                                    BetRejectionReason::InternalError,
+                                    "Error settling the bet",
+                                );
+                                continue;
+                            }
+                        };
 
                         let user = if let Ok(Some(user)) =
                             self.db.fetch_user(bet.user_id.unwrap()).await
@@ -637,7 +1118,7 @@ impl StatefulGameEngine {
                         };
 
                         let constructed_bet = BetExpanded {
-                            id: 0,
+                            id: bet_id,
                             timestamp,
                             amount: bet.amount,
                             profit: game_result.total_profit,
@@ -649,6 +1130,7 @@ impl StatefulGameEngine {
                             coin_id: bet.coin_id,
                             userseed_id: user_seed.id,
                             serverseed_id: server_seed.id,
+                            nonce,
                             outcomes,
                             num_games: game_result.num_games as i32,
                             uuid: bet.uuid.clone().unwrap(),
@@ -664,235 +1146,525 @@ impl StatefulGameEngine {
                         }
                     } else {
                         // game state changed
-                        if let Err(e) = self
-                            .db
-                            .insert_game_state(
-                                bet.game_id,
-                                bet.user_id.unwrap(),
-                                bet.uuid.as_ref().unwrap(),
-                                bet.coin_id,
-                                &bet.data,
-                                &game_result.data,
-                                &bet.amount,
-                                user_seed.id,
-                                server_seed.id,
-                            )
-                            .await
-                        {
-                            error!("Error inserting state to the db: {:?}", e);
-                            continue;
+                        let state = GameState {
+                            id: 0,
+                            timestamp,
+                            amount: bet.amount,
+                            bet_info: bet.data,
+                            state: game_result.data,
+                            steps,
+                            uuid: bet.uuid.unwrap(),
+                            nonce,
+                            game_id: bet.game_id,
+                            user_id: bet.user_id.unwrap(),
+                            coin_id: bet.coin_id,
+                            userseed_id: user_seed.id,
+                            serverseed_id: server_seed.id,
+                        };
+
+                        match self.db.start_game_state(state.amount, &state).await {
+                            Ok(true) => {}
+                            Ok(false) => {
+                                warn!("Insufficient balance to start the game: {:?}", state);
+                                reject_game_bet(
+                                    &self.manager_sender,
+                                    state.game_id,
+                                    &Some(state.uuid.clone()),
+                                    BetRejectionReason::InsufficientBalance,
+                                    "Insufficient balance",
+                                );
+                                continue;
+                            }
+                            Err(e) => {
+                                error!("Error inserting state to the db: {:?}", e);
+                                reject_game_bet(
+                                    &self.manager_sender,
+                                    state.game_id,
+                                    &Some(state.uuid.clone()),
+                                    BetRejectionReason::InternalError,
+                                    "Error starting the game",
+                                );
+                                continue;
+                            }
                         }
 
-                        if let Err(e) =
-                            self.manager_sender
-                                .send(WsManagerEvent::PropagateState(GameState {
-                                    id: 0,
-                                    timestamp,
-                                    amount: bet.amount,
-                                    bet_info: bet.data,
-                                    state: game_result.data,
-                                    uuid: bet.uuid.unwrap(),
-                                    game_id: bet.game_id,
-                                    user_id: bet.user_id.unwrap(),
-                                    coin_id: bet.coin_id,
-                                    userseed_id: user_seed.id,
-                                    serverseed_id: server_seed.id,
-                                }))
+                        if let Err(e) = self
+                            .manager_sender
+                            .send(WsManagerEvent::PropagateState(state))
                         {
                             error!("Error propagating game state: {:?}", e);
                             break;
                         }
                     }
                 }
+                EnginePropagatedBet::AutoBet(bet, result_sender) => {
+                    metrics::bet_rejected(bet.game_id, BetRejectionReason::GameNotFound);
+                    let _ = result_sender.send(Err((
+                        BetRejectionReason::GameNotFound,
+                        "Only stateless games can be auto played".into(),
+                    )));
+                }
                 EnginePropagatedBet::ContinueGame(continue_game) => {
                     debug!("Recieved `ContinueGame` event: {:?}", continue_game);
 
-                    let game_eng = if let Some(game_eng) =
-                        self.game_engines.get(&(continue_game.game_id as u64))
-                    {
-                        game_eng
-                    } else {
-                        warn!("Statefull game `{:?}` not found", continue_game.game_id);
-                        continue;
-                    };
+                    let game_eng =
+                        if let Some(game_eng) = game_engines.get(&(continue_game.game_id as u64)) {
+                            game_eng
+                        } else {
+                            warn!("Statefull game `{:?}` not found", continue_game.game_id);
+                            reject_game_bet(
+                                &self.manager_sender,
+                                continue_game.game_id,
+                                &continue_game.uuid,
+                                BetRejectionReason::GameNotFound,
+                                "Game not found",
+                            );
+                            continue;
+                        };
 
-                    let mut state = if let Ok(Some(state)) = self
-                        .db
-                        .fetch_game_state(
-                            continue_game.game_id,
-                            continue_game.user_id.unwrap(),
-                            continue_game.coin_id,
-                        )
+                    if self
+                        .continue_game(game_eng.as_ref(), continue_game)
                         .await
+                        .is_err()
                     {
-                        state
-                    } else {
-                        warn!("State not found for the bet: {:?}", continue_game);
-                        continue;
-                    };
+                        break;
+                    }
+                }
+                EnginePropagatedBet::ResolveGame(expired) => {
+                    debug!("Recieved `ResolveGame` event: {:?}", expired);
 
-                    let user_seed = match self
-                        .db
-                        .fetch_current_user_seed(continue_game.user_id.unwrap())
-                        .await
-                    {
-                        Ok(seed) => seed,
-                        Err(e) => {
-                            error!(
-                                "Error getting user seed for user `{}`: {:?}",
-                                continue_game.user_id.unwrap(),
-                                e
+                    let game_eng =
+                        if let Some(game_eng) = game_engines.get(&(expired.game_id as u64)) {
+                            game_eng
+                        } else {
+                            warn!(
+                                "Statefull game `{:?}` of the expired state not found",
+                                expired.game_id
                             );
+                            self.refund_expired(&expired).await;
                             continue;
-                        }
-                    };
+                        };
 
-                    let server_seed = match self
-                        .db
-                        .fetch_current_server_seed(continue_game.user_id.unwrap())
+                    if self
+                        .resolve_game(game_eng.as_ref(), &expired)
                         .await
+                        .is_err()
                     {
-                        Ok(seed) => seed,
-                        Err(e) => {
-                            error!(
-                                "Error getting server seed for user `{}`: {:?}",
-                                continue_game.user_id.unwrap(),
-                                e
-                            );
-                            continue;
-                        }
-                    };
+                        break;
+                    }
+                }
+            }
+        }
+    }
 
-                    let timestamp = chrono::offset::Utc::now();
+    /// Plays the expired game to the end on behalf of the player
+    async fn resolve_game(
+        &self,
+        game_eng: &dyn StatefulGameEng,
+        expired: &GameState,
+    ) -> Result<(), SendError<WsManagerEvent>> {
+        for _ in 0..MAX_RESOLVE_STEPS {
+            // the player could have finished the game since it was swept
+            let state = match self
+                .db
+                .fetch_game_state(expired.game_id, expired.user_id, expired.coin_id)
+                .await
+            {
+                Ok(Some(state)) if state.id == expired.id => state,
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    error!("Error fetching the expired state {:?}: {:?}", expired, e);
+                    return Ok(());
+                }
+            };
 
-                    let random_numbers = generate_random_numbers(
-                        &user_seed.user_seed,
-                        &server_seed.server_seed,
-                        timestamp.timestamp_millis() as u64,
-                        game_eng.numbers_per_bet(),
-                    );
+            let data = if let Some(data) = game_eng.resolve_data(&state) {
+                data
+            } else {
+                self.refund_expired(&state).await;
+                return Ok(());
+            };
 
-                    let game_result = if let Some(game_result) =
-                        game_eng.continue_playing(&state, &continue_game, &random_numbers)
-                    {
-                        game_result
-                    } else {
-                        warn!("Couldn't proccess bet {:?}", continue_game);
-                        continue;
-                    };
+            let in_progress = self
+                .continue_game(
+                    game_eng,
+                    ContinueGame {
+                        game_id: state.game_id,
+                        coin_id: state.coin_id,
+                        user_id: Some(state.user_id),
+                        uuid: Some(state.uuid.clone()),
+                        data,
+                    },
+                )
+                .await?;
+            if !in_progress {
+                return Ok(());
+            }
+        }
 
-                    if game_result.finished {
-                        // game finished
+        warn!(
+            "Expired state was not resolved in {} steps: {:?}",
+            MAX_RESOLVE_STEPS, expired
+        );
+        self.refund_expired(expired).await;
+        Ok(())
+    }
 
-                        if let Err(e) = self
-                            .db
-                            .remove_game_state(
-                                continue_game.game_id,
-                                continue_game.user_id.unwrap(),
-                                continue_game.coin_id,
-                            )
-                            .await
-                        {
-                            error!("Error removing state: {:?}", e);
-                        };
+    /// Returns the stakes of the expired game that can't be resolved, so it's not swept again
+    async fn refund_expired(&self, expired: &GameState) {
+        // the stakes of the steps played since the state was swept are returned too
+        let state = match self
+            .db
+            .fetch_game_state(expired.game_id, expired.user_id, expired.coin_id)
+            .await
+        {
+            Ok(Some(state)) if state.id == expired.id => state,
+            Ok(_) => return,
+            Err(e) => {
+                error!("Error fetching the expired state {:?}: {:?}", expired, e);
+                return;
+            }
+        };
 
-                        if let Err(e) = self
-                            .db
-                            .increase_balance(
-                                continue_game.user_id.unwrap(),
-                                continue_game.coin_id,
-                                &game_result.total_profit,
-                            )
-                            .await
-                        {
-                            error!("Error adding bet to the db: {:?}", e);
-                        };
+        match self.db.refund_game_state(&state).await {
+            Ok(true) => info!("Refunded the expired state {:?}", state),
+            Ok(false) => {}
+            Err(e) => error!("Error refunding the expired state {:?}: {:?}", state, e),
+        }
+    }
 
-                        let outcomes = format!("{:?}", game_result.outcomes);
-                        let profits = format!("{:?}", game_result.profits);
-                        if let Err(e) = self
-                            .db
-                            .place_bet(
-                                state.amount,
-                                game_result.total_profit,
-                                game_result.num_games as i32,
-                                &outcomes,
-                                &profits,
-                                &game_result.data,
-                                Some(&game_result.data),
-                                continue_game.uuid.as_ref().unwrap(),
-                                continue_game.game_id,
-                                continue_game.user_id.unwrap(),
-                                continue_game.coin_id,
-                                user_seed.id,
-                                server_seed.id,
-                            )
-                            .await
-                        {
-                            error!("Error adding bet to the db: {:?}", e);
-                        };
+    /// Plays the next step of the started game, returns whether the game is still in progress
+    async fn continue_game(
+        &self,
+        game_eng: &dyn StatefulGameEng,
+        continue_game: ContinueGame,
+    ) -> Result<bool, SendError<WsManagerEvent>> {
+        let mut state = if let Ok(Some(state)) = self
+            .db
+            .fetch_game_state(
+                continue_game.game_id,
+                continue_game.user_id.unwrap(),
+                continue_game.coin_id,
+            )
+            .await
+        {
+            state
+        } else {
+            warn!("State not found for the bet: {:?}", continue_game);
+            reject_game_bet(
+                &self.manager_sender,
+                continue_game.game_id,
+                &continue_game.uuid,
+                BetRejectionReason::GameNotStarted,
+                "The game is not started",
+            );
+            return Ok(false);
+        };
+
+        let user_seed = match self
+            .db
+            .fetch_current_user_seed(continue_game.user_id.unwrap())
+            .await
+        {
+            Ok(seed) => seed,
+            Err(e) => {
+                error!(
+                    "Error getting user seed for user `{}`: {:?}",
+                    continue_game.user_id.unwrap(),
+                    e
+                );
+                reject_game_bet(
+                    &self.manager_sender,
+                    continue_game.game_id,
+                    &continue_game.uuid,
+                    BetRejectionReason::MissingSeed,
+                    "User seed not found",
+                );
+                return Ok(false);
+            }
+        };
 
-                        let user = if let Ok(Some(user)) =
-                            self.db.fetch_user(continue_game.user_id.unwrap()).await
-                        {
-                            user
-                        } else {
-                            error!("Unable to find user: {:?}", continue_game.user_id);
-                            continue;
-                        };
+        let server_seed = match self
+            .db
+            .fetch_current_server_seed_unhashed(continue_game.user_id.unwrap())
+            .await
+        {
+            Ok(seed) => seed,
+            Err(e) => {
+                error!(
+                    "Error getting server seed for user `{}`: {:?}",
+                    continue_game.user_id.unwrap(),
+                    e
+                );
+                reject_game_bet(
+                    &self.manager_sender,
+                    continue_game.game_id,
+                    &continue_game.uuid,
+                    BetRejectionReason::MissingSeed,
+                    "Server seed not found",
+                );
+                return Ok(false);
+            }
+        };
 
-                        let constructed_bet = BetExpanded {
-                            id: 0,
-                            timestamp,
-                            amount: state.amount,
-                            profit: game_result.total_profit,
-                            bet_info: continue_game.data,
-                            state: Some(game_result.data),
-                            game_id: continue_game.game_id,
-                            user_id: continue_game.user_id.unwrap(),
-                            username: user.username,
-                            coin_id: continue_game.coin_id,
-                            userseed_id: user_seed.id,
-                            serverseed_id: server_seed.id,
-                            outcomes,
-                            num_games: game_result.num_games as i32,
-                            uuid: continue_game.uuid.clone().unwrap(),
-                            profits,
-                        };
+        let nonce = match self.db.next_nonce(user_seed.id, server_seed.id).await {
+            Ok(nonce) => nonce,
+            Err(e) => {
+                error!(
+                    "Error getting nonce for the bet {:?}: {:?}",
+                    continue_game, e
+                );
+                reject_game_bet(
+                    &self.manager_sender,
+                    continue_game.game_id,
+                    &continue_game.uuid,
+                    BetRejectionReason::InternalError,
+                    "Error getting nonce",
+                );
+                return Ok(false);
+            }
+        };
 
-                        if let Err(e) = self
-                            .manager_sender
-                            .send(WsManagerEvent::PropagateBet(constructed_bet))
-                        {
-                            error!("Error propagating bet: {:?}", e);
-                            break;
-                        }
-                    } else {
-                        if let Err(e) = self
-                            .db
-                            .change_game_state(
-                                continue_game.game_id,
-                                continue_game.user_id.unwrap(),
-                                continue_game.coin_id,
-                                &game_result.data,
-                            )
-                            .await
-                        {
-                            error!("Error updating state: {:?}", e);
-                        };
+        let timestamp = chrono::offset::Utc::now();
 
-                        state.state = game_result.data;
+        let random_numbers = generate_random_numbers(
+            &user_seed.user_seed,
+            &server_seed.server_seed,
+            nonce as u64,
+            game_eng.numbers_per_bet(),
+        );
 
-                        if let Err(e) = self
-                            .manager_sender
-                            .send(WsManagerEvent::PropagateState(state))
-                        {
-                            error!("Error propagating state: {:?}", e);
-                            break;
-                        }
-                    }
+        let mut steps = match serde_json::from_str::<Vec<GameStep>>(&state.steps) {
+            Ok(steps) => steps,
+            Err(e) => {
+                error!(
+                    "Error deserializing steps of the state {:?}: {:?}",
+                    state, e
+                );
+                reject_game_bet(
+                    &self.manager_sender,
+                    continue_game.game_id,
+                    &continue_game.uuid,
+                    BetRejectionReason::InternalError,
+                    "Error restoring the game",
+                );
+                return Ok(false);
+            }
+        };
+        let stake = if let Some(stake) = game_eng.continue_stake(&state, &continue_game) {
+            stake
+        } else {
+            warn!("Couldn't proccess bet {:?}", continue_game);
+            reject_game_bet(
+                &self.manager_sender,
+                continue_game.game_id,
+                &continue_game.uuid,
+                BetRejectionReason::InvalidBet,
+                "Bad bet data",
+            );
+            return Ok(false);
+        };
+
+        steps.push(GameStep {
+            data: continue_game.data.clone(),
+            nonce: nonce as u64,
+            userseed_id: user_seed.id,
+            serverseed_id: server_seed.id,
+            stake,
+        });
+        let steps = serde_json::to_string(&steps).unwrap();
+
+        let game_result = if let Some(game_result) =
+            game_eng.continue_playing(&state, &continue_game, &random_numbers)
+        {
+            game_result
+        } else {
+            warn!("Couldn't proccess bet {:?}", continue_game);
+            reject_game_bet(
+                &self.manager_sender,
+                continue_game.game_id,
+                &continue_game.uuid,
+                BetRejectionReason::InvalidBet,
+                "Bad bet data",
+            );
+            return Ok(false);
+        };
+        state.amount += stake;
+
+        if game_result.finished {
+            // game finished
+            let outcomes = format!("{:?}", game_result.outcomes);
+            let profits = format!("{:?}", game_result.profits);
+            let timer = metrics::SETTLEMENT_DURATION
+                .with_label_values(&["stateful"])
+                .start_timer();
+            let settled = self
+                .db
+                .settle_bet(
+                    stake,
+                    game_result.total_profit,
+                    &Bet {
+                        id: 0,
+                        timestamp,
+                        amount: state.amount,
+                        profit: game_result.total_profit,
+                        num_games: game_result.num_games as i32,
+                        outcomes: outcomes.clone(),
+                        profits: profits.clone(),
+                        bet_info: continue_game.data.clone(),
+                        state: Some(game_result.data.clone()),
+                        steps: Some(steps),
+                        uuid: continue_game.uuid.clone().unwrap(),
+                        nonce,
+                        game_id: continue_game.game_id,
+                        user_id: continue_game.user_id.unwrap(),
+                        coin_id: continue_game.coin_id,
+                        userseed_id: user_seed.id,
+                        serverseed_id: server_seed.id,
+                    },
+                    true,
+                    Some(jackpot_roll(
+                        &user_seed.user_seed,
+                        &server_seed.server_seed,
+                        nonce as u64,
+                    )),
+                )
+                .await;
+            timer.observe_duration();
+            let bet_id = match settled {
+                Ok(Some(settled)) => {
+                    let bet_id = settled.bet_id;
+                    propagate_jackpot(&self.manager_sender, settled);
+                    metrics::bet_processed(continue_game.game_id);
+                    bet_id
+                }
+                Ok(None) if !stake.is_zero() => {
+                    warn!(
+                        "Insufficient balance to settle the bet: {:?}",
+                        continue_game
+                    );
+                    reject_game_bet(
+                        &self.manager_sender,
+                        continue_game.game_id,
+                        &continue_game.uuid,
+                        BetRejectionReason::InsufficientBalance,
+                        "Insufficient balance",
+                    );
+                    return Ok(false);
+                }
+                Ok(None) => {
+                    warn!("State was already settled: {:?}", state);
+                    reject_game_bet(
+                        &self.manager_sender,
+                        continue_game.game_id,
+                        &continue_game.uuid,
+                        BetRejectionReason::GameNotStarted,
+                        "The game is not started",
+                    );
+                    return Ok(false);
+                }
+                Err(e) => {
+                    error!("Error settling the bet {:?}: {:?}", continue_game, e);
+                    reject_game_bet(
+                        &self.manager_sender,
+                        continue_game.game_id,
+                        &continue_game.uuid,
+                        BetRejectionReason::InternalError,
+                        "Error settling the bet",
+                    );
+                    return Ok(false);
+                }
+            };
+
+            let user =
+                if let Ok(Some(user)) = self.db.fetch_user(continue_game.user_id.unwrap()).await {
+                    user
+                } else {
+                    error!("Unable to find user: {:?}", continue_game.user_id);
+                    return Ok(false);
+                };
+
+            let constructed_bet = BetExpanded {
+                id: bet_id,
+                timestamp,
+                amount: state.amount,
+                profit: game_result.total_profit,
+                bet_info: continue_game.data,
+                state: Some(game_result.data),
+                game_id: continue_game.game_id,
+                user_id: continue_game.user_id.unwrap(),
+                username: user.username,
+                coin_id: continue_game.coin_id,
+                userseed_id: user_seed.id,
+                serverseed_id: server_seed.id,
+                nonce,
+                outcomes,
+                num_games: game_result.num_games as i32,
+                uuid: continue_game.uuid.clone().unwrap(),
+                profits,
+            };
+
+            if let Err(e) = self
+                .manager_sender
+                .send(WsManagerEvent::PropagateBet(constructed_bet))
+            {
+                error!("Error propagating bet: {:?}", e);
+                return Err(e);
+            }
+
+            Ok(false)
+        } else {
+            match self
+                .db
+                .change_game_state(
+                    continue_game.game_id,
+                    continue_game.user_id.unwrap(),
+                    continue_game.coin_id,
+                    stake,
+                    &game_result.data,
+                    &steps,
+                    nonce,
+                )
+                .await
+            {
+                Ok(true) => {}
+                Ok(false) => {
+                    warn!("Insufficient balance to continue the game: {:?}", state);
+                    reject_game_bet(
+                        &self.manager_sender,
+                        continue_game.game_id,
+                        &continue_game.uuid,
+                        BetRejectionReason::InsufficientBalance,
+                        "Insufficient balance",
+                    );
+                    return Ok(false);
+                }
+                Err(e) => {
+                    error!("Error updating state {:?}: {:?}", state, e);
+                    reject_game_bet(
+                        &self.manager_sender,
+                        continue_game.game_id,
+                        &continue_game.uuid,
+                        BetRejectionReason::InternalError,
+                        "Error updating the game",
+                    );
+                    return Ok(false);
                 }
+            };
+
+            state.state = game_result.data;
+            state.steps = steps;
+            state.nonce = nonce;
+
+            if let Err(e) = self
+                .manager_sender
+                .send(WsManagerEvent::PropagateState(state))
+            {
+                error!("Error propagating state: {:?}", e);
+                return Err(e);
             }
+
+            Ok(true)
         }
     }
 }
@@ -23,6 +23,10 @@ pub struct ApplesState {
     pub state: Vec<Vec<bool>>,
     pub picked_tiles: Vec<u8>,
     pub current_multiplier: Decimal,
+    /// Rows of the board with `true` on the bombs, generated at the start of the game and shown
+    /// to the player only when the game is finished
+    #[serde(default)]
+    pub hidden: Vec<Vec<bool>>,
 }
 
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
@@ -31,14 +35,35 @@ pub struct ApplesDifficulty {
     pub total_spaces: u8,
 }
 
+const ROWS: usize = 9;
+
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct Apples {
     pub difficulties: Vec<ApplesDifficulty>,
-    pub multipliers: Vec<[Decimal; 9]>,
+    pub multipliers: Vec<[Decimal; ROWS]>,
+}
+
+impl ApplesDifficulty {
+    fn row(&self, rng: u64) -> Vec<bool> {
+        let mut row = vec![false; self.total_spaces as usize];
+
+        if self.mines == 1 {
+            let mine_index = rng % self.total_spaces as u64;
+            row[mine_index as usize] = true;
+        } else {
+            for r in row.iter_mut() {
+                *r = true;
+            }
+            let empty_index = rng % self.total_spaces as u64;
+            row[empty_index as usize] = false;
+        }
+
+        row
+    }
 }
 
 impl StatefulGameEng for Apples {
-    fn start_playing(&self, bet: &PropagatedBet, _: &[u64]) -> Option<GameResult> {
+    fn start_playing(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
         let data: ApplesData = serde_json::from_str(&bet.data)
             .map_err(|e| {
                 error!("Error parsing Apples data `{:?}`: {:?}", bet.data, e);
@@ -46,9 +71,12 @@ impl StatefulGameEng for Apples {
             })
             .ok()?;
 
-        if data.difficulty as usize >= self.difficulties.len() {
-            return None;
-        }
+        let difficulty = self.difficulties.get(data.difficulty as usize)?;
+        let board = random_numbers
+            .iter()
+            .take(ROWS)
+            .map(|rng| difficulty.row(*rng))
+            .collect();
 
         Some(GameResult {
             total_profit: Decimal::ZERO,
@@ -59,6 +87,7 @@ impl StatefulGameEng for Apples {
                 state: Vec::with_capacity(0),
                 current_multiplier: Decimal::ZERO,
                 picked_tiles: Vec::with_capacity(0),
+                hidden: board,
             })
             .unwrap(),
             finished: false,
@@ -119,22 +148,13 @@ impl StatefulGameEng for Apples {
             return None;
         }
 
-        let mut row = vec![false; difficulty.total_spaces as usize];
+        // games started before the board was generated upfront draw the row with the step
+        let row = match parsed_state.hidden.get(parsed_state.state.len()) {
+            Some(row) => row.clone(),
+            None => difficulty.row(*random_numbers.first()?),
+        };
 
-        let rng = random_numbers[0];
-
-        if difficulty.mines == 1 {
-            let mine_index = rng % difficulty.total_spaces as u64;
-            row[mine_index as usize] = true;
-        } else {
-            for r in row.iter_mut() {
-                *r = true;
-            }
-            let empty_index = rng % difficulty.total_spaces as u64;
-            row[empty_index as usize] = false;
-        }
-
-        let won = !row[picked_tile as usize];
+        let won = !*row.get(picked_tile as usize)?;
 
         parsed_state.state.push(row);
         parsed_state.picked_tiles.push(picked_tile);
@@ -151,7 +171,7 @@ impl StatefulGameEng for Apples {
                 profits: vec![profit],
                 num_games: 1,
                 data: serde_json::to_string(&parsed_state).unwrap(),
-                finished: parsed_state.state.len() == 9,
+                finished: parsed_state.state.len() == ROWS,
             })
         } else {
             parsed_state.current_multiplier = Decimal::ZERO;
@@ -168,7 +188,39 @@ impl StatefulGameEng for Apples {
         }
     }
 
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal> {
+        let data: ApplesData = serde_json::from_str(&bet.data)
+            .map_err(|e| {
+                error!("Error parsing Apples data `{:?}`: {:?}", bet.data, e);
+                e
+            })
+            .ok()?;
+
+        Some(
+            bet.amount
+                * self
+                    .multipliers
+                    .get(data.difficulty as usize)?
+                    .iter()
+                    .max()?,
+        )
+    }
+
+    fn resolve_data(&self, state: &crate::models::db_models::GameState) -> Option<String> {
+        let parsed_state: ApplesState = serde_json::from_str(&state.state).ok()?;
+        // no tile was picked yet
+        if parsed_state.current_multiplier.is_zero() {
+            return None;
+        }
+
+        serde_json::to_string(&ApplesContinueData {
+            tile: None,
+            cashout: true,
+        })
+        .ok()
+    }
+
     fn numbers_per_bet(&self) -> u64 {
-        1
+        ROWS as u64
     }
 }
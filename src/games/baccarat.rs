@@ -0,0 +1,235 @@
+use crate::models::json_requests::PropagatedBet;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use rust_decimal::Decimal;
+
+use super::{pick_card, Card, Round, RoundGameEng};
+
+#[derive(Deserialize, Serialize, Clone, Copy, ToSchema)]
+pub enum BaccaratBetType {
+    Player,
+    /// Pays the `banker_coef`, which includes the commission
+    Banker,
+    Tie,
+    /// The first two cards of the player have the same rank
+    PlayerPair,
+    /// The first two cards of the banker have the same rank
+    BankerPair,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BaccaratBet {
+    pub amount: Decimal,
+    pub bet: BaccaratBetType,
+}
+
+/// The profits of the result are the payouts of the bets in the same order, repeated for every
+/// played game
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BaccaratData {
+    pub bets: Vec<BaccaratBet>,
+}
+
+/// The cards are listed in the order they are dealt, the third card is drawn to the player first
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BaccaratRound {
+    pub player_cards: Vec<Card>,
+    pub banker_cards: Vec<Card>,
+    pub player_total: u8,
+    pub banker_total: u8,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BaccaratReturnData {
+    pub bets: Vec<BaccaratBet>,
+    pub rounds: Vec<BaccaratRound>,
+}
+
+/// The player and banker bets are returned on a tie
+///
+/// The outcomes are the total of the player times 10 plus the total of the banker
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Baccarat {
+    /// Amount of 52 card decks in the shoe
+    pub decks: u8,
+    pub player_coef: Decimal,
+    pub banker_coef: Decimal,
+    pub tie_coef: Decimal,
+    pub pair_coef: Decimal,
+}
+
+fn card_value(card: &Card) -> u8 {
+    if card.number >= 10 {
+        0
+    } else {
+        card.number
+    }
+}
+
+fn total(cards: &[Card]) -> u8 {
+    cards.iter().map(card_value).sum::<u8>() % 10
+}
+
+fn is_pair(cards: &[Card]) -> bool {
+    cards[0].number == cards[1].number
+}
+
+impl Baccarat {
+    /// Deals the hands of the player and the banker by the third card rules
+    pub fn deal(&self, random_numbers: &[u64]) -> Option<(Vec<Card>, Vec<Card>)> {
+        let mut deck: Vec<Card> = (0..self.decks)
+            .flat_map(|_| (0..4).flat_map(|suit| (1..=13).map(move |number| Card { number, suit })))
+            .collect();
+        let mut random_numbers = random_numbers.iter();
+        let mut draw = || -> Option<Card> {
+            if deck.is_empty() {
+                return None;
+            }
+            Some(pick_card(*random_numbers.next()?, &mut deck))
+        };
+
+        let mut player = Vec::with_capacity(3);
+        let mut banker = Vec::with_capacity(3);
+        player.push(draw()?);
+        banker.push(draw()?);
+        player.push(draw()?);
+        banker.push(draw()?);
+
+        let player_total = total(&player);
+        let banker_total = total(&banker);
+        // naturals
+        if player_total >= 8 || banker_total >= 8 {
+            return Some((player, banker));
+        }
+
+        let player_third = if player_total <= 5 {
+            let card = draw()?;
+            player.push(card);
+            Some(card_value(&card))
+        } else {
+            None
+        };
+
+        let banker_draws = match player_third {
+            None => banker_total <= 5,
+            Some(third) => match banker_total {
+                0..=2 => true,
+                3 => third != 8,
+                4 => (2..=7).contains(&third),
+                5 => (4..=7).contains(&third),
+                6 => (6..=7).contains(&third),
+                _ => false,
+            },
+        };
+        if banker_draws {
+            banker.push(draw()?);
+        }
+
+        Some((player, banker))
+    }
+}
+
+impl RoundGameEng for Baccarat {
+    const NAME: &'static str = "Baccarat";
+
+    type Data = BaccaratData;
+    /// The round and the payouts of the bets
+    type RoundData = (BaccaratRound, Vec<Decimal>);
+
+    fn play_round(
+        &self,
+        data: &BaccaratData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<(BaccaratRound, Vec<Decimal>)>> {
+        if data.bets.is_empty() || data.bets.iter().any(|b| b.amount <= Decimal::ZERO) {
+            return None;
+        }
+        let calculated_amount: Decimal = data.bets.iter().map(|b| b.amount).sum();
+        if calculated_amount != amount {
+            return None;
+        }
+
+        let (player_cards, banker_cards) = self.deal(random_numbers)?;
+        let player_total = total(&player_cards);
+        let banker_total = total(&banker_cards);
+        let tie = player_total == banker_total;
+
+        let payouts: Vec<Decimal> = data
+            .bets
+            .iter()
+            .map(|bet| match bet.bet {
+                BaccaratBetType::Player | BaccaratBetType::Banker if tie => bet.amount,
+                BaccaratBetType::Player if player_total > banker_total => {
+                    bet.amount * self.player_coef
+                }
+                BaccaratBetType::Banker if banker_total > player_total => {
+                    bet.amount * self.banker_coef
+                }
+                BaccaratBetType::Tie if tie => bet.amount * self.tie_coef,
+                BaccaratBetType::PlayerPair if is_pair(&player_cards) => {
+                    bet.amount * self.pair_coef
+                }
+                BaccaratBetType::BankerPair if is_pair(&banker_cards) => {
+                    bet.amount * self.pair_coef
+                }
+                _ => Decimal::ZERO,
+            })
+            .collect();
+
+        Some(Round {
+            outcome: player_total as u64 * 10 + banker_total as u64,
+            payout: payouts.iter().sum(),
+            data: (
+                BaccaratRound {
+                    player_cards,
+                    banker_cards,
+                    player_total,
+                    banker_total,
+                },
+                payouts,
+            ),
+        })
+    }
+
+    fn max_payout(&self, data: &BaccaratData, _: Decimal) -> Option<Decimal> {
+        // a bound of the payout, the bets on the player and the banker can't both win
+        Some(
+            data.bets
+                .iter()
+                .map(|bet| {
+                    let coef = match bet.bet {
+                        BaccaratBetType::Player => self.player_coef,
+                        BaccaratBetType::Banker => self.banker_coef,
+                        BaccaratBetType::Tie => self.tie_coef,
+                        BaccaratBetType::PlayerPair | BaccaratBetType::BankerPair => self.pair_coef,
+                    };
+                    // the player and banker bets are returned on a tie
+                    bet.amount * coef.max(Decimal::ONE)
+                })
+                .sum(),
+        )
+    }
+
+    fn round_profits(&self, round: &Round<(BaccaratRound, Vec<Decimal>)>) -> Vec<Decimal> {
+        round.data.1.clone()
+    }
+
+    fn return_data(
+        &self,
+        _: &PropagatedBet,
+        data: BaccaratData,
+        rounds: Vec<(BaccaratRound, Vec<Decimal>)>,
+    ) -> String {
+        serde_json::to_string(&BaccaratReturnData {
+            bets: data.bets,
+            rounds: rounds.into_iter().map(|(round, _)| round).collect(),
+        })
+        .unwrap()
+    }
+
+    fn numbers_per_round(&self) -> u64 {
+        6
+    }
+}
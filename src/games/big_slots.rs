@@ -21,6 +21,10 @@ pub struct BigSlotsData {
     use_free_spins: bool,
 }
 
+fn default_max_multiplier() -> Decimal {
+    Decimal::from(5000)
+}
+
 #[derive(Deserialize, Serialize, Clone)]
 pub struct BigSlots {
     pub tiles: Vec<HashMap<u8, Decimal>>,
@@ -28,6 +32,9 @@ pub struct BigSlots {
     pub multiplier_chance: u64,
     pub free_spins_prices: HashMap<i64, Decimal>,
     pub free_spins_reward_amount: u32,
+    /// The win of a game, free spins included, is capped at the stake times the multiplier
+    #[serde(default = "default_max_multiplier")]
+    pub max_multiplier: Decimal,
 }
 
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
@@ -314,6 +321,7 @@ impl StatefulGameEng for BigSlots {
 
         let (game_fields, total_win, multipliers, total_win_per_tumble, free_spins) =
             self.play_normal_bet(random_numbers, bet.amount);
+        let total_win = total_win.min(bet.amount * self.max_multiplier);
 
         if free_spins != 0 {
             return Some(GameResult {
@@ -380,6 +388,8 @@ impl StatefulGameEng for BigSlots {
             parsed_state.free_spins -= 1;
             let (game_fields, total_win, multipliers, total_win_per_tumble, free_spins) =
                 self.play_free_bet(random_numbers, state.amount);
+            let total_win =
+                total_win.min(state.amount * self.max_multiplier - parsed_state.total_win);
             parsed_state.free_spins += free_spins;
             parsed_state.game_fields = Some(game_fields);
             parsed_state.total_win += total_win;
@@ -409,6 +419,23 @@ impl StatefulGameEng for BigSlots {
         None
     }
 
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal> {
+        Some(bet.amount * self.max_multiplier)
+    }
+
+    fn resolve_data(&self, state: &crate::models::db_models::GameState) -> Option<String> {
+        let parsed_state: BigSlotsState = serde_json::from_str(&state.state).ok()?;
+        if parsed_state.free_spins == 0 {
+            return None;
+        }
+
+        serde_json::to_string(&BigSlotsContinueData {
+            buy_free_spins: false,
+            use_free_spins: true,
+        })
+        .ok()
+    }
+
     fn numbers_per_bet(&self) -> u64 {
         200
     }
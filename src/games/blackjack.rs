@@ -0,0 +1,379 @@
+use std::slice::Iter;
+
+use crate::models::{
+    db_models::{GameResult, GameState},
+    json_requests::{ContinueGame, PropagatedBet},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use rust_decimal::Decimal;
+use tracing::error;
+
+use super::{pick_card, Card, StatefulGameEng};
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BlackjackData {}
+
+#[derive(Deserialize, Serialize, Clone, Copy, ToSchema)]
+pub enum BlackjackAction {
+    Hit,
+    Stand,
+    /// Doubles the stake of the hand and draws the last card to it
+    Double,
+    /// Splits the pair into two hands with the same stake
+    Split,
+    /// Insures against a dealer blackjack for the half of the stake, only as the first action
+    /// when the dealer shows an ace
+    Insurance,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BlackjackContinueData {
+    pub action: BlackjackAction,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BlackjackHand {
+    pub cards: Vec<Card>,
+    pub stake: Decimal,
+    /// The hand was created by a split, 21 on it is not a blackjack
+    pub split: bool,
+    /// No more cards are drawn to the hand
+    pub finished: bool,
+}
+
+/// The dealer has no hole card, the second card of the dealer is drawn when all the hands are
+/// finished
+///
+/// The outcomes of a finished game are the results of the hands: `0` lost, `1` push, `2` won,
+/// `3` blackjack, followed by `1` if the insurance was paid or `0` if it was lost
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct BlackjackState {
+    /// Original stake of the bet
+    pub stake: Decimal,
+    pub hands: Vec<BlackjackHand>,
+    /// Hand the actions are applied to
+    pub active_hand: usize,
+    pub dealer_cards: Vec<Card>,
+    pub insurance: Option<Decimal>,
+}
+
+/// On a dealer blackjack only the original stake is lost, the stakes added by doubling and
+/// splitting are returned
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Blackjack {
+    /// Amount of 52 card decks in the shoe
+    pub decks: u8,
+    /// Multiplier of the stake paid for a blackjack, `2.5` for 3:2
+    pub blackjack_multiplier: Decimal,
+    /// Multiplier of the insurance paid on a dealer blackjack, `3` for 2:1
+    pub insurance_multiplier: Decimal,
+    pub dealer_hits_soft_17: bool,
+    pub double_after_split: bool,
+    /// Max amount of hands the bet can be split into
+    pub max_hands: usize,
+}
+
+/// Cards left in the shoe, drawn with the random numbers of the step
+struct Shoe<'a> {
+    deck: Vec<Card>,
+    random_numbers: Iter<'a, u64>,
+}
+
+impl<'a> Shoe<'a> {
+    fn draw(&mut self) -> Option<Card> {
+        if self.deck.is_empty() {
+            return None;
+        }
+
+        let rng = self.random_numbers.next()?;
+        Some(pick_card(*rng, &mut self.deck))
+    }
+}
+
+fn card_value(card: &Card) -> u32 {
+    card.number.min(10) as u32
+}
+
+/// Best value of the cards and whether an ace is counted as 11
+fn hand_value(cards: &[Card]) -> (u32, bool) {
+    let value: u32 = cards.iter().map(card_value).sum();
+    if cards.iter().any(|card| card.number == 1) && value + 10 <= 21 {
+        (value + 10, true)
+    } else {
+        (value, false)
+    }
+}
+
+fn is_blackjack(cards: &[Card]) -> bool {
+    cards.len() == 2 && hand_value(cards).0 == 21
+}
+
+impl Blackjack {
+    /// Shoe without the cards that are already dealt
+    fn shoe<'a>(&self, state: &BlackjackState, random_numbers: &'a [u64]) -> Shoe<'a> {
+        let mut deck: Vec<Card> = (0..self.decks)
+            .flat_map(|_| (0..4).flat_map(|suit| (1..=13).map(move |number| Card { number, suit })))
+            .collect();
+
+        for dealt in state
+            .hands
+            .iter()
+            .flat_map(|hand| hand.cards.iter())
+            .chain(state.dealer_cards.iter())
+        {
+            if let Some(position) = deck
+                .iter()
+                .position(|card| card.number == dealt.number && card.suit == dealt.suit)
+            {
+                deck.swap_remove(position);
+            }
+        }
+
+        Shoe {
+            deck,
+            random_numbers: random_numbers.iter(),
+        }
+    }
+
+    fn dealer_draws(&self, cards: &[Card]) -> bool {
+        let (value, soft) = hand_value(cards);
+        value < 17 || (value == 17 && soft && self.dealer_hits_soft_17)
+    }
+
+    fn insurance_available(state: &BlackjackState) -> bool {
+        state.insurance.is_none()
+            && state.dealer_cards.first().map(|card| card.number) == Some(1)
+            && state.hands.len() == 1
+            && state.hands[0].cards.len() == 2
+            && !state.hands[0].finished
+    }
+
+    /// Deals the split hands and moves to the next unfinished hand, the dealer plays when all
+    /// the hands are finished
+    fn advance(&self, mut state: BlackjackState, mut shoe: Shoe) -> Option<GameResult> {
+        while let Some(hand) = state.hands.get_mut(state.active_hand) {
+            if hand.cards.len() == 1 {
+                hand.cards.push(shoe.draw()?);
+                // split aces get one card each
+                if hand.cards[0].number == 1 {
+                    hand.finished = true;
+                }
+            }
+            if hand_value(&hand.cards).0 >= 21 {
+                hand.finished = true;
+            }
+            if !hand.finished {
+                return Some(GameResult {
+                    total_profit: Decimal::ZERO,
+                    outcomes: Vec::with_capacity(0),
+                    profits: vec![Decimal::ZERO],
+                    num_games: 1,
+                    data: serde_json::to_string(&state).unwrap(),
+                    finished: false,
+                });
+            }
+            state.active_hand += 1;
+        }
+
+        state.dealer_cards.push(shoe.draw()?);
+        let player_blackjack = state.hands.len() == 1 && is_blackjack(&state.hands[0].cards);
+        let all_busted = state
+            .hands
+            .iter()
+            .all(|hand| hand_value(&hand.cards).0 > 21);
+        if !is_blackjack(&state.dealer_cards) && !player_blackjack && !all_busted {
+            while self.dealer_draws(&state.dealer_cards) {
+                state.dealer_cards.push(shoe.draw()?);
+            }
+        }
+
+        Some(self.settle(state))
+    }
+
+    fn settle(&self, state: BlackjackState) -> GameResult {
+        let dealer_blackjack = is_blackjack(&state.dealer_cards);
+        let (dealer_value, _) = hand_value(&state.dealer_cards);
+
+        let mut outcomes = Vec::with_capacity(state.hands.len() + 1);
+        let mut profits = Vec::with_capacity(state.hands.len() + 1);
+        for (i, hand) in state.hands.iter().enumerate() {
+            let (value, _) = hand_value(&hand.cards);
+            let blackjack = !hand.split && is_blackjack(&hand.cards);
+
+            let (outcome, profit) = if dealer_blackjack {
+                if blackjack {
+                    (1, hand.stake)
+                } else if i == 0 {
+                    (0, hand.stake - state.stake)
+                } else {
+                    (0, hand.stake)
+                }
+            } else if value > 21 {
+                (0, Decimal::ZERO)
+            } else if blackjack {
+                (3, hand.stake * self.blackjack_multiplier)
+            } else if dealer_value > 21 || value > dealer_value {
+                (2, hand.stake * Decimal::TWO)
+            } else if value == dealer_value {
+                (1, hand.stake)
+            } else {
+                (0, Decimal::ZERO)
+            };
+            outcomes.push(outcome);
+            profits.push(profit);
+        }
+
+        if let Some(insurance) = state.insurance {
+            if dealer_blackjack {
+                outcomes.push(1);
+                profits.push(insurance * self.insurance_multiplier);
+            } else {
+                outcomes.push(0);
+                profits.push(Decimal::ZERO);
+            }
+        }
+
+        GameResult {
+            total_profit: profits.iter().sum(),
+            outcomes,
+            profits,
+            num_games: 1,
+            data: serde_json::to_string(&state).unwrap(),
+            finished: true,
+        }
+    }
+}
+
+impl StatefulGameEng for Blackjack {
+    fn start_playing(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
+        let _: BlackjackData = serde_json::from_str(&bet.data)
+            .map_err(|e| {
+                error!("Error parsing Blackjack data `{:?}`: {:?}", bet.data, e);
+                e
+            })
+            .ok()?;
+
+        let mut state = BlackjackState {
+            stake: bet.amount,
+            hands: Vec::with_capacity(1),
+            active_hand: 0,
+            dealer_cards: Vec::with_capacity(2),
+            insurance: None,
+        };
+        let mut shoe = self.shoe(&state, random_numbers);
+
+        let cards = vec![shoe.draw()?, shoe.draw()?];
+        state.dealer_cards.push(shoe.draw()?);
+        state.hands.push(BlackjackHand {
+            cards,
+            stake: bet.amount,
+            split: false,
+            finished: false,
+        });
+
+        self.advance(state, shoe)
+    }
+
+    fn continue_playing(
+        &self,
+        state: &GameState,
+        bet: &ContinueGame,
+        random_numbers: &[u64],
+    ) -> Option<GameResult> {
+        let data: BlackjackContinueData = serde_json::from_str(&bet.data)
+            .map_err(|e| {
+                error!("Error parsing Blackjack data `{:?}`: {:?}", bet.data, e);
+                e
+            })
+            .ok()?;
+        let mut parsed_state: BlackjackState = serde_json::from_str(&state.state)
+            .map_err(|e| {
+                error!("Error parsing Blackjack state`{:?}`: {:?}", state.state, e);
+                e
+            })
+            .ok()?;
+
+        let mut shoe = self.shoe(&parsed_state, random_numbers);
+        let active_hand = parsed_state.active_hand;
+        let hands_amount = parsed_state.hands.len();
+        let insurance_available = Self::insurance_available(&parsed_state);
+        let hand = parsed_state.hands.get_mut(active_hand)?;
+
+        match data.action {
+            BlackjackAction::Hit => {
+                hand.cards.push(shoe.draw()?);
+            }
+            BlackjackAction::Stand => {
+                hand.finished = true;
+            }
+            BlackjackAction::Double => {
+                if hand.cards.len() != 2 || (hand.split && !self.double_after_split) {
+                    return None;
+                }
+                hand.stake *= Decimal::TWO;
+                hand.cards.push(shoe.draw()?);
+                hand.finished = true;
+            }
+            BlackjackAction::Split => {
+                if hand.cards.len() != 2
+                    || card_value(&hand.cards[0]) != card_value(&hand.cards[1])
+                    || hands_amount >= self.max_hands
+                {
+                    return None;
+                }
+                // both hands get their second card when they are played
+                let split_hand = BlackjackHand {
+                    cards: vec![hand.cards.pop()?],
+                    stake: hand.stake,
+                    split: true,
+                    finished: false,
+                };
+                hand.split = true;
+                parsed_state.hands.insert(active_hand + 1, split_hand);
+            }
+            BlackjackAction::Insurance => {
+                if !insurance_available {
+                    return None;
+                }
+                parsed_state.insurance = Some(parsed_state.stake / Decimal::TWO);
+            }
+        }
+
+        self.advance(parsed_state, shoe)
+    }
+
+    fn continue_stake(&self, state: &GameState, bet: &ContinueGame) -> Option<Decimal> {
+        let data: BlackjackContinueData = serde_json::from_str(&bet.data).ok()?;
+        let parsed_state: BlackjackState = serde_json::from_str(&state.state).ok()?;
+        let hand = parsed_state.hands.get(parsed_state.active_hand)?;
+
+        Some(match data.action {
+            BlackjackAction::Hit | BlackjackAction::Stand => Decimal::ZERO,
+            BlackjackAction::Double | BlackjackAction::Split => hand.stake,
+            BlackjackAction::Insurance => parsed_state.stake / Decimal::TWO,
+        })
+    }
+
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal> {
+        // every hand doubled and won, or a blackjack, with the insurance paid
+        let hands = self
+            .blackjack_multiplier
+            .max(Decimal::from(self.max_hands * 4));
+        let insurance = self.insurance_multiplier / Decimal::TWO;
+
+        Some(bet.amount * (hands + insurance))
+    }
+
+    fn resolve_data(&self, _: &GameState) -> Option<String> {
+        serde_json::to_string(&BlackjackContinueData {
+            action: BlackjackAction::Stand,
+        })
+        .ok()
+    }
+
+    fn numbers_per_bet(&self) -> u64 {
+        32
+    }
+}
@@ -0,0 +1,234 @@
+use crate::models::{
+    db_models::{GameResult, GameState},
+    json_requests::{ContinueGame, PropagatedBet},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use tracing::error;
+
+use super::{pick_card, Card, StatefulGameEng};
+
+const RANKS: u8 = 13;
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct HiLoData {}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+pub enum HiLoAction {
+    /// The next card is strictly higher, aces are low
+    Higher,
+    /// The next card is strictly lower
+    Lower,
+    /// The next card has the same rank
+    Equal,
+    /// Draws the next card without changing the multiplier
+    Skip,
+    Cashout,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct HiLoContinueData {
+    pub action: HiLoAction,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct HiLoRound {
+    pub card: Card,
+    pub action: HiLoAction,
+}
+
+/// The outcomes of a finished game are the ranks of the drawn cards
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct HiLoState {
+    /// Card the next guess is made on
+    pub card: Card,
+    /// Previous cards and the actions taken on them
+    pub rounds: Vec<HiLoRound>,
+    pub current_multiplier: Decimal,
+}
+
+/// Every card is drawn from a full deck, so the chances of the guesses depend only on the
+/// current card
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct HiLo {
+    /// Part of the fair multiplier of every guess kept by the house, `0.01` for 99% RTP
+    pub house_edge: Decimal,
+    /// The game is cashed out when the multiplier reaches it
+    pub max_multiplier: Decimal,
+}
+
+fn draw(rng: u64) -> Card {
+    let mut deck: Vec<Card> = (0..4)
+        .flat_map(|suit| (1..=RANKS).map(move |number| Card { number, suit }))
+        .collect();
+
+    pick_card(rng, &mut deck)
+}
+
+impl HiLoState {
+    fn guessed(&self) -> bool {
+        self.rounds
+            .iter()
+            .any(|round| round.action != HiLoAction::Skip)
+    }
+
+    fn outcomes(&self) -> Vec<u64> {
+        self.rounds
+            .iter()
+            .map(|round| round.card.number as u64)
+            .chain(std::iter::once(self.card.number as u64))
+            .collect()
+    }
+}
+
+impl HiLo {
+    /// Multiplier of a correct guess on the card, `None` if the guess can't win
+    pub fn multiplier(&self, card: &Card, action: HiLoAction) -> Option<Decimal> {
+        let winning = match action {
+            HiLoAction::Higher => RANKS - card.number,
+            HiLoAction::Lower => card.number - 1,
+            HiLoAction::Equal => 1,
+            HiLoAction::Skip | HiLoAction::Cashout => return None,
+        };
+        if winning == 0 {
+            return None;
+        }
+
+        Some(
+            ((Decimal::ONE - self.house_edge) * Decimal::from(RANKS) / Decimal::from(winning))
+                .round_dp_with_strategy(4, RoundingStrategy::ToZero),
+        )
+    }
+
+    fn finish(&self, state: HiLoState, amount: Decimal, won: bool) -> GameResult {
+        let profit = if won {
+            amount * state.current_multiplier
+        } else {
+            Decimal::ZERO
+        };
+
+        GameResult {
+            total_profit: profit,
+            outcomes: state.outcomes(),
+            profits: vec![profit],
+            num_games: 1,
+            data: serde_json::to_string(&state).unwrap(),
+            finished: true,
+        }
+    }
+}
+
+impl StatefulGameEng for HiLo {
+    fn start_playing(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
+        let _: HiLoData = serde_json::from_str(&bet.data)
+            .map_err(|e| {
+                error!("Error parsing HiLo data `{:?}`: {:?}", bet.data, e);
+                e
+            })
+            .ok()?;
+
+        Some(GameResult {
+            total_profit: Decimal::ZERO,
+            outcomes: Vec::with_capacity(0),
+            profits: vec![Decimal::ZERO],
+            num_games: 1,
+            data: serde_json::to_string(&HiLoState {
+                card: draw(*random_numbers.first()?),
+                rounds: Vec::with_capacity(0),
+                current_multiplier: Decimal::ONE,
+            })
+            .unwrap(),
+            finished: false,
+        })
+    }
+
+    fn continue_playing(
+        &self,
+        state: &GameState,
+        bet: &ContinueGame,
+        random_numbers: &[u64],
+    ) -> Option<GameResult> {
+        let data: HiLoContinueData = serde_json::from_str(&bet.data)
+            .map_err(|e| {
+                error!("Error parsing HiLo data `{:?}`: {:?}", bet.data, e);
+                e
+            })
+            .ok()?;
+        let mut parsed_state: HiLoState = serde_json::from_str(&state.state)
+            .map_err(|e| {
+                error!("Error parsing HiLo state`{:?}`: {:?}", state.state, e);
+                e
+            })
+            .ok()?;
+
+        if data.action == HiLoAction::Cashout {
+            if !parsed_state.guessed() {
+                return None;
+            }
+            return Some(self.finish(parsed_state, state.amount, true));
+        }
+
+        let card = parsed_state.card;
+        let multiplier = match data.action {
+            HiLoAction::Skip => None,
+            action => Some(self.multiplier(&card, action)?),
+        };
+
+        let next_card = draw(*random_numbers.first()?);
+        parsed_state.rounds.push(HiLoRound {
+            card,
+            action: data.action,
+        });
+        parsed_state.card = next_card;
+
+        if let Some(multiplier) = multiplier {
+            let won = match data.action {
+                HiLoAction::Higher => next_card.number > card.number,
+                HiLoAction::Lower => next_card.number < card.number,
+                _ => next_card.number == card.number,
+            };
+            if !won {
+                return Some(self.finish(parsed_state, state.amount, false));
+            }
+
+            parsed_state.current_multiplier = (parsed_state.current_multiplier * multiplier)
+                .round_dp_with_strategy(4, RoundingStrategy::ToZero);
+            if parsed_state.current_multiplier >= self.max_multiplier {
+                parsed_state.current_multiplier = self.max_multiplier;
+                return Some(self.finish(parsed_state, state.amount, true));
+            }
+        }
+
+        Some(GameResult {
+            total_profit: Decimal::ZERO,
+            outcomes: Vec::with_capacity(0),
+            profits: vec![Decimal::ZERO],
+            num_games: 1,
+            data: serde_json::to_string(&parsed_state).unwrap(),
+            finished: false,
+        })
+    }
+
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal> {
+        Some(bet.amount * self.max_multiplier)
+    }
+
+    fn resolve_data(&self, state: &GameState) -> Option<String> {
+        let parsed_state: HiLoState = serde_json::from_str(&state.state).ok()?;
+        // nothing was won yet
+        if !parsed_state.guessed() {
+            return None;
+        }
+
+        serde_json::to_string(&HiLoContinueData {
+            action: HiLoAction::Cashout,
+        })
+        .ok()
+    }
+
+    fn numbers_per_bet(&self) -> u64 {
+        1
+    }
+}
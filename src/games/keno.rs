@@ -0,0 +1,111 @@
+use crate::models::json_requests::PropagatedBet;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use rust_decimal::Decimal;
+
+use crate::games::{Round, RoundGameEng};
+
+const NUMBERS: u8 = 40;
+const DRAWN: usize = 10;
+const MAX_PICKS: usize = 10;
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct KenoData {
+    /// From 1 to 10 distinct numbers in the range from 1 to 40
+    pub numbers: Vec<u8>,
+    pub risk: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct KenoReturnData {
+    pub numbers: Vec<u8>,
+    pub risk: u64,
+    pub draws: Vec<Vec<u8>>,
+}
+
+/// The outcomes are the amounts of the picked numbers hit in every game
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Keno {
+    /// Multipliers indexed by the risk, the amount of picks minus one and the amount of hits,
+    /// generated with `gen_keno_coefs.py`
+    pub multipliers: Vec<Vec<Vec<Decimal>>>,
+}
+
+impl Keno {
+    /// Draws 10 numbers without replacement, one random number per drawn number
+    pub fn draw(random_numbers: &[u64]) -> Vec<u8> {
+        let mut pool: Vec<u8> = (1..=NUMBERS).collect();
+
+        random_numbers
+            .iter()
+            .take(DRAWN)
+            .map(|rng| pool.swap_remove((rng % pool.len() as u64) as usize))
+            .collect()
+    }
+}
+
+impl RoundGameEng for Keno {
+    const NAME: &'static str = "Keno";
+
+    type Data = KenoData;
+    type RoundData = Vec<u8>;
+
+    fn play_round(
+        &self,
+        data: &KenoData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<Vec<u8>>> {
+        if data.numbers.is_empty() || data.numbers.len() > MAX_PICKS {
+            return None;
+        }
+        if data.numbers.iter().enumerate().any(|(i, number)| {
+            *number == 0 || *number > NUMBERS || data.numbers[..i].contains(number)
+        }) {
+            return None;
+        }
+        let multipliers = self
+            .multipliers
+            .get(data.risk as usize)?
+            .get(data.numbers.len() - 1)?;
+
+        let draw = Self::draw(random_numbers);
+        let hits = data
+            .numbers
+            .iter()
+            .filter(|number| draw.contains(number))
+            .count();
+
+        Some(Round {
+            outcome: hits as u64,
+            payout: amount * multipliers.get(hits)?,
+            data: draw,
+        })
+    }
+
+    fn max_payout(&self, data: &KenoData, amount: Decimal) -> Option<Decimal> {
+        if data.numbers.is_empty() || data.numbers.len() > MAX_PICKS {
+            return None;
+        }
+        let multipliers = self
+            .multipliers
+            .get(data.risk as usize)?
+            .get(data.numbers.len() - 1)?;
+
+        Some(amount * multipliers.iter().max()?)
+    }
+
+    fn return_data(&self, _: &PropagatedBet, data: KenoData, draws: Vec<Vec<u8>>) -> String {
+        serde_json::to_string(&KenoReturnData {
+            numbers: data.numbers,
+            risk: data.risk,
+            draws,
+        })
+        .unwrap()
+    }
+
+    fn numbers_per_round(&self) -> u64 {
+        DRAWN as u64
+    }
+}
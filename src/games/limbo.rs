@@ -0,0 +1,64 @@
+use crate::crash::crash_multiplier;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+
+use crate::games::{Round, RoundGameEng};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref MIN_MULTIPLIER: Decimal = Decimal::new(101, 2);
+    static ref MAX_MULTIPLIER: Decimal = Decimal::from(1_000_000);
+    static ref HUNDRED: Decimal = Decimal::from(100);
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct LimboData {
+    /// Target multiplier from 1.01 to 1000000 with at most 2 decimals
+    pub multiplier: Decimal,
+}
+
+/// The outcomes are the multipliers of the games times 100
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
+pub struct Limbo {
+    pub house_edge: Decimal,
+}
+
+impl RoundGameEng for Limbo {
+    const NAME: &'static str = "Limbo";
+
+    type Data = LimboData;
+    type RoundData = ();
+
+    fn play_round(
+        &self,
+        data: &LimboData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<()>> {
+        if data.multiplier < *MIN_MULTIPLIER
+            || data.multiplier > *MAX_MULTIPLIER
+            || data.multiplier.round_dp(2) != data.multiplier
+        {
+            return None;
+        }
+
+        let multiplier = crash_multiplier(random_numbers[0], self.house_edge, *MAX_MULTIPLIER);
+
+        Some(Round {
+            outcome: (multiplier * *HUNDRED).to_u64().unwrap(),
+            payout: if multiplier >= data.multiplier {
+                amount * data.multiplier
+            } else {
+                Decimal::ZERO
+            },
+            data: (),
+        })
+    }
+
+    fn max_payout(&self, data: &LimboData, amount: Decimal) -> Option<Decimal> {
+        Some(amount * data.multiplier)
+    }
+}
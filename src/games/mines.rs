@@ -296,6 +296,35 @@ impl StatefulGameEng for Mines {
         });
     }
 
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal> {
+        let data: MinesData = serde_json::from_str(&bet.data)
+            .map_err(|e| {
+                error!("Error parsing Mines data `{:?}`: {:?}", bet.data, e);
+                e
+            })
+            .ok()?;
+        if data.num_mines == 0 {
+            return None;
+        }
+
+        Some(
+            bet.amount
+                * self
+                    .multipliers
+                    .get(data.num_mines as usize - 1)?
+                    .iter()
+                    .max()?,
+        )
+    }
+
+    fn resolve_data(&self, _: &crate::models::db_models::GameState) -> Option<String> {
+        serde_json::to_string(&MinesContinueData {
+            tiles: None,
+            cashout: true,
+        })
+        .ok()
+    }
+
     fn numbers_per_bet(&self) -> u64 {
         25
     }
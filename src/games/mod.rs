@@ -40,6 +40,27 @@ pub use roulette::*;
 mod big_slots;
 pub use big_slots::*;
 
+mod blackjack;
+pub use blackjack::*;
+
+mod keno;
+pub use keno::*;
+
+mod hilo;
+pub use hilo::*;
+
+mod limbo;
+pub use limbo::*;
+
+mod baccarat;
+pub use baccarat::*;
+
+pub mod registry;
+
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use tracing::error;
+
 use crate::{
     db::DB,
     models::{
@@ -51,9 +72,141 @@ use crate::{
 pub trait GameEng {
     fn play(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult>;
 
+    /// Highest total payout of the bet, `None` if the bet data is not valid
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal>;
+
     fn numbers_per_bet(&self) -> u64;
 }
 
+/// Result of one round of a `RoundGameEng`
+pub struct Round<T> {
+    pub outcome: u64,
+    /// Whole amount paid for the round, including the stake
+    pub payout: Decimal,
+    /// Data of the round put in the result of the bet
+    pub data: T,
+}
+
+/// Stateless game made of independent rounds, played `num_games` times by `play_rounds`
+pub trait RoundGameEng {
+    /// Name of the game in the logs
+    const NAME: &'static str;
+
+    /// Bet data shared by all the rounds
+    type Data: DeserializeOwned;
+    type RoundData;
+
+    /// Plays one round with the stake of the round, `None` if the bet data is not valid
+    fn play_round(
+        &self,
+        data: &Self::Data,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<Self::RoundData>>;
+
+    /// Highest payout of one round with the stake of the round, `None` if the bet data is not
+    /// valid
+    fn max_payout(&self, data: &Self::Data, amount: Decimal) -> Option<Decimal>;
+
+    /// Profits of the round put in the result of the bet, the payout of the round by default
+    ///
+    /// The games with several bet spots return the payout of every spot in the order of the
+    /// spots in the bet data
+    fn round_profits(&self, round: &Round<Self::RoundData>) -> Vec<Decimal> {
+        vec![round.payout]
+    }
+
+    /// Data of the bet result, the bet data itself by default
+    fn return_data(
+        &self,
+        bet: &PropagatedBet,
+        _data: Self::Data,
+        _rounds: Vec<Self::RoundData>,
+    ) -> String {
+        bet.data.clone()
+    }
+
+    fn numbers_per_round(&self) -> u64 {
+        1
+    }
+}
+
+fn parse_data<G: RoundGameEng>(bet: &PropagatedBet) -> Option<G::Data> {
+    serde_json::from_str(&bet.data)
+        .map_err(|e| {
+            error!("Error parsing {} data `{:?}`: {:?}", G::NAME, bet.data, e);
+            e
+        })
+        .ok()
+}
+
+/// Plays the rounds of the bet until `num_games` are played or the net value of the played
+/// rounds reaches `stop_win` or `stop_loss`, only the stakes of the played rounds are taken
+///
+/// The outcomes hold the outcome of every played round and the profits hold the
+/// `round_profits` of every played round one after another
+pub fn play_rounds<G: RoundGameEng>(
+    game: &G,
+    bet: &PropagatedBet,
+    random_numbers: &[u64],
+) -> Option<GameResult> {
+    let data = parse_data::<G>(bet)?;
+
+    let mut total_profit = Decimal::ZERO;
+    let mut total_value = Decimal::ZERO;
+
+    let mut outcomes: Vec<u64> = Vec::with_capacity(bet.num_games as usize);
+    let mut profits: Vec<Decimal> = Vec::with_capacity(bet.num_games as usize);
+    let mut rounds: Vec<G::RoundData> = Vec::with_capacity(bet.num_games as usize);
+    for numbers in random_numbers
+        .chunks_exact(game.numbers_per_round() as usize)
+        .take(bet.num_games as usize)
+    {
+        let round = game.play_round(&data, bet.amount, numbers)?;
+
+        outcomes.push(round.outcome);
+        profits.extend(game.round_profits(&round));
+        total_profit += round.payout;
+        total_value += round.payout - bet.amount;
+        rounds.push(round.data);
+
+        if (!bet.stop_win.is_zero() && total_value >= bet.stop_win)
+            || (!bet.stop_loss.is_zero() && total_value <= bet.stop_loss)
+        {
+            break;
+        }
+    }
+
+    let games = outcomes.len();
+    if games == 0 {
+        return None;
+    }
+
+    Some(GameResult {
+        total_profit,
+        outcomes,
+        profits,
+        num_games: games as u32,
+        data: game.return_data(bet, data, rounds),
+        finished: true,
+    })
+}
+
+impl<G: RoundGameEng> GameEng for G {
+    fn play(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
+        play_rounds(self, bet, random_numbers)
+    }
+
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal> {
+        let data = parse_data::<G>(bet)?;
+        Some(RoundGameEng::max_payout(self, &data, bet.amount)? * Decimal::from(bet.num_games))
+    }
+
+    fn numbers_per_bet(&self) -> u64 {
+        self.numbers_per_round()
+    }
+}
+
 pub trait StatefulGameEng {
     fn start_playing(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult>;
 
@@ -64,5 +217,18 @@ pub trait StatefulGameEng {
         random_numbers: &[u64],
     ) -> Option<GameResult>;
 
+    /// Highest payout of the whole game, including the stakes the steps can add, `None` if the
+    /// bet data is not valid
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal>;
+
+    /// Stake the step adds to the game, e.g. doubling down, debited before the step is applied
+    fn continue_stake(&self, _state: &GameState, _bet: &ContinueGame) -> Option<Decimal> {
+        Some(Decimal::ZERO)
+    }
+
+    /// Continue data of the next step of an abandoned game played on behalf of the player,
+    /// `None` if the game can't be resolved and the stake should be returned
+    fn resolve_data(&self, state: &GameState) -> Option<String>;
+
     fn numbers_per_bet(&self) -> u64;
 }
@@ -1,17 +1,10 @@
-use crate::{
-    db::DB,
-    models::{
-        db_models::{Bet, GameResult},
-        json_requests::PropagatedBet,
-    },
-};
+use crate::models::json_requests::PropagatedBet;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use rust_decimal::Decimal;
-use tracing::error;
 
-use crate::games::GameEng;
+use crate::games::{Round, RoundGameEng};
 
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct PlinkoData {
@@ -56,69 +49,53 @@ impl Plinko {
     }
 }
 
-impl GameEng for Plinko {
-    fn play(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
-        let data: PlinkoData = serde_json::from_str(&bet.data)
-            .map_err(|e| {
-                error!("Error parsing Plinko data `{:?}`: {:?}", bet.data, e);
-                e
-            })
-            .ok()?;
+impl RoundGameEng for Plinko {
+    const NAME: &'static str = "Plinko";
 
+    type Data = PlinkoData;
+    type RoundData = Vec<u8>;
+
+    fn play_round(
+        &self,
+        data: &PlinkoData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<Vec<u8>>> {
         if data.num_rows < 8 || data.num_rows > 16 {
             return None;
         }
         if data.risk >= 3 {
             return None;
         }
-        let mut total_profit = Decimal::ZERO;
-        let mut total_value = Decimal::ZERO;
-        let mut games = 0;
-
-        let mut outcomes: Vec<u64> = Vec::with_capacity(random_numbers.len());
-        let mut paths: Vec<Vec<u8>> = Vec::with_capacity(random_numbers.len());
-        let mut profits: Vec<Decimal> = Vec::with_capacity(random_numbers.len());
-        for (_, number) in random_numbers.iter().enumerate() {
-            let (multiplier, path) = self.plinko_game(*number, data.num_rows, data.risk);
-            let payout = bet.amount * multiplier;
-
-            paths.push(path);
-            profits.push(payout);
-            games += 1;
-            outcomes.push(*number);
-
-            total_profit += payout;
-            total_value += payout - bet.amount;
-
-            if (!bet.stop_win.is_zero() && total_value >= bet.stop_win)
-                || (!bet.stop_loss.is_zero() && total_value <= bet.stop_loss)
-            {
-                break;
-            }
-        }
 
-        if games != bet.num_games as usize {
-            total_profit += Decimal::from(bet.num_games as usize - games) * bet.amount;
+        let number = random_numbers[0];
+        let (multiplier, path) = self.plinko_game(number, data.num_rows, data.risk);
+
+        Some(Round {
+            outcome: number,
+            payout: amount * multiplier,
+            data: path,
+        })
+    }
+
+    fn max_payout(&self, data: &PlinkoData, amount: Decimal) -> Option<Decimal> {
+        if data.num_rows < 8 || data.num_rows > 16 {
+            return None;
         }
+        let multipliers = self
+            .multipliers
+            .get(data.risk as usize)?
+            .get(data.num_rows as usize - 8)?;
+
+        Some(amount * multipliers.iter().max()?)
+    }
 
-        let return_data = serde_json::to_string(&PlinkoReturnData {
+    fn return_data(&self, _: &PropagatedBet, data: PlinkoData, paths: Vec<Vec<u8>>) -> String {
+        serde_json::to_string(&PlinkoReturnData {
             num_rows: data.num_rows,
             risk: data.risk,
             paths,
         })
-        .unwrap();
-
-        Some(GameResult {
-            total_profit,
-            outcomes,
-            profits,
-            num_games: games as u32,
-            data: return_data,
-            finished: true,
-        })
-    }
-
-    fn numbers_per_bet(&self) -> u64 {
-        1
+        .unwrap()
     }
 }
@@ -137,6 +137,15 @@ impl StatefulGameEng for Poker {
         })
     }
 
+    fn max_payout(&self, bet: &PropagatedBet) -> Option<Decimal> {
+        Some(bet.amount * self.multipliers.iter().max()?)
+    }
+
+    fn resolve_data(&self, _: &crate::models::db_models::GameState) -> Option<String> {
+        // the hand is evaluated without replacing any cards
+        serde_json::to_string(&PokerContinueData { to_replace: None }).ok()
+    }
+
     fn numbers_per_bet(&self) -> u64 {
         5
     }
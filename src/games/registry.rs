@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::Error;
+use utoipa::openapi::{RefOr, Schema};
+use utoipa::ToSchema;
+
+use crate::models::json_responses::GameSchema;
+
+use super::*;
+
+pub type SchemaFn = fn() -> (&'static str, RefOr<Schema>);
+
+pub enum GameParser {
+    Stateless(fn(&str) -> Result<Box<dyn GameEng>, Error>),
+    Stateful(fn(&str) -> Result<Box<dyn StatefulGameEng>, Error>),
+}
+
+/// Game type that can be played by the engines
+pub struct GameRegistration {
+    /// Names of the `Game` records played with this game type
+    pub names: &'static [&'static str],
+    /// Deserializer of the `Game.parameters`
+    pub parser: GameParser,
+    /// Schema of the bet data, for stateful games it's the data of the first step
+    pub bet_data: SchemaFn,
+    /// Schema of the data of the following steps of stateful games
+    pub continue_data: Option<SchemaFn>,
+    /// Schemas referenced by the bet and continue data
+    pub dependencies: &'static [SchemaFn],
+}
+
+fn stateless<G: GameEng + DeserializeOwned + 'static>(
+    params: &str,
+) -> Result<Box<dyn GameEng>, Error> {
+    Ok(Box::new(serde_json::from_str::<G>(params)?))
+}
+
+fn stateful<G: StatefulGameEng + DeserializeOwned + 'static>(
+    params: &str,
+) -> Result<Box<dyn StatefulGameEng>, Error> {
+    Ok(Box::new(serde_json::from_str::<G>(params)?))
+}
+
+fn schema<T: ToSchema<'static>>() -> (&'static str, RefOr<Schema>) {
+    T::schema()
+}
+
+pub static GAMES: &[GameRegistration] = &[
+    GameRegistration {
+        names: &["CoinFlip"],
+        parser: GameParser::Stateless(stateless::<CoinFlip>),
+        bet_data: schema::<CoinFlipData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Dice"],
+        parser: GameParser::Stateless(stateless::<Dice>),
+        bet_data: schema::<DiceData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["RPS"],
+        parser: GameParser::Stateless(stateless::<RPS>),
+        bet_data: schema::<RPSData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Race", "Thimbles", "CarRace"],
+        parser: GameParser::Stateless(stateless::<Race>),
+        bet_data: schema::<RaceData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Wheel"],
+        parser: GameParser::Stateless(stateless::<Wheel>),
+        bet_data: schema::<WheelData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Rocket"],
+        parser: GameParser::Stateless(stateless::<Rocket>),
+        bet_data: schema::<RocketData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Plinko"],
+        parser: GameParser::Stateless(stateless::<Plinko>),
+        bet_data: schema::<PlinkoData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Slots"],
+        parser: GameParser::Stateless(stateless::<Slots>),
+        bet_data: schema::<SlotsData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Roulette", "AmericanRoulette"],
+        parser: GameParser::Stateless(stateless::<Roulette>),
+        bet_data: schema::<RouletteData>,
+        continue_data: None,
+        dependencies: &[schema::<RouletteBet>, schema::<RouletteBetType>],
+    },
+    GameRegistration {
+        names: &["Keno"],
+        parser: GameParser::Stateless(stateless::<Keno>),
+        bet_data: schema::<KenoData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Limbo"],
+        parser: GameParser::Stateless(stateless::<Limbo>),
+        bet_data: schema::<LimboData>,
+        continue_data: None,
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Baccarat"],
+        parser: GameParser::Stateless(stateless::<Baccarat>),
+        bet_data: schema::<BaccaratData>,
+        continue_data: None,
+        dependencies: &[schema::<BaccaratBet>, schema::<BaccaratBetType>],
+    },
+    GameRegistration {
+        names: &["Mines"],
+        parser: GameParser::Stateful(stateful::<Mines>),
+        bet_data: schema::<MinesData>,
+        continue_data: Some(schema::<MinesContinueData>),
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Poker"],
+        parser: GameParser::Stateful(stateful::<Poker>),
+        bet_data: schema::<PokerData>,
+        continue_data: Some(schema::<PokerContinueData>),
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Apples"],
+        parser: GameParser::Stateful(stateful::<Apples>),
+        bet_data: schema::<ApplesData>,
+        continue_data: Some(schema::<ApplesContinueData>),
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["BigSlots"],
+        parser: GameParser::Stateful(stateful::<BigSlots>),
+        bet_data: schema::<BigSlotsData>,
+        continue_data: Some(schema::<BigSlotsContinueData>),
+        dependencies: &[],
+    },
+    GameRegistration {
+        names: &["Blackjack"],
+        parser: GameParser::Stateful(stateful::<Blackjack>),
+        bet_data: schema::<BlackjackData>,
+        continue_data: Some(schema::<BlackjackContinueData>),
+        dependencies: &[schema::<BlackjackAction>],
+    },
+    GameRegistration {
+        names: &["HiLo"],
+        parser: GameParser::Stateful(stateful::<HiLo>),
+        bet_data: schema::<HiLoData>,
+        continue_data: Some(schema::<HiLoContinueData>),
+        dependencies: &[schema::<HiLoAction>],
+    },
+];
+
+/// Finds the game type registered under the name
+pub fn find_game(name: &str) -> Option<&'static GameRegistration> {
+    GAMES.iter().find(|game| game.names.contains(&name))
+}
+
+impl GameRegistration {
+    pub fn is_stateful(&self) -> bool {
+        matches!(self.parser, GameParser::Stateful(_))
+    }
+
+    /// All the schemas of the bet data of the game, keyed by name
+    pub fn schemas(&self) -> BTreeMap<String, RefOr<Schema>> {
+        std::iter::once(&self.bet_data)
+            .chain(self.continue_data.iter())
+            .chain(self.dependencies.iter())
+            .map(|schema| {
+                let (name, schema) = schema();
+                (name.to_string(), schema)
+            })
+            .collect()
+    }
+
+    pub fn game_schema(&self) -> GameSchema {
+        GameSchema {
+            names: self.names.iter().map(|name| name.to_string()).collect(),
+            stateful: self.is_stateful(),
+            bet_data: (self.bet_data)().0.to_string(),
+            continue_data: self.continue_data.map(|schema| schema().0.to_string()),
+            schemas: self.schemas(),
+        }
+    }
+}
@@ -1,10 +1,3 @@
-use crate::{
-    db::DB,
-    models::{
-        db_models::{Bet, GameResult},
-        json_requests::PropagatedBet,
-    },
-};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use utoipa::ToSchema;
@@ -13,9 +6,8 @@ use rust_decimal::{
     prelude::{FromPrimitive, ToPrimitive},
     Decimal,
 };
-use tracing::error;
 
-use crate::games::GameEng;
+use crate::games::{Round, RoundGameEng};
 
 use lazy_static::lazy_static;
 
@@ -48,70 +40,43 @@ pub struct Rocket {
     pub profit_coef: Decimal,
 }
 
-impl GameEng for Rocket {
-    fn play(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
-        let data: RocketData = serde_json::from_str(&bet.data)
-            .map_err(|e| {
-                error!("Error parsing Dice data `{:?}`: {:?}", bet.data, e);
-                e
-            })
-            .ok()?;
+impl RoundGameEng for Rocket {
+    const NAME: &'static str = "Rocket";
+
+    type Data = RocketData;
+    type RoundData = ();
+
+    fn play_round(
+        &self,
+        data: &RocketData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<()>> {
         if data.multiplier > *DICE_UPPER_BOUNDARY || data.multiplier < *DICE_LOWER_BOUNDARY {
             return None;
         }
 
-        let mut total_profit = Decimal::ZERO;
-        let mut total_value = Decimal::ZERO;
-        let mut games = 0;
-
         let number_to_roll = *HUNDRED - (*NINTYNINE / data.multiplier);
-        let profit = bet.amount * data.multiplier;
-
-        let mut outcomes: Vec<u64> = Vec::with_capacity(random_numbers.len());
-        let mut profits: Vec<Decimal> = Vec::with_capacity(random_numbers.len());
-        for (game, number) in random_numbers.iter().enumerate() {
-            let number = remap(
-                Decimal::from_u64(*number).unwrap(),
-                Decimal::ZERO,
-                *U64_UPPER_BOUNDARY,
-                *DICE_LOWER_BOUNDARY,
-                *DICE_UPPER_BOUNDARY,
-            );
-            outcomes.push((number * *DICE_MULT).to_u64().unwrap());
-
-            if number >= number_to_roll {
-                total_profit += profit;
-                total_value += profit;
-                profits.push(profit);
+        let number = remap(
+            Decimal::from_u64(random_numbers[0]).unwrap(),
+            Decimal::ZERO,
+            *U64_UPPER_BOUNDARY,
+            *DICE_LOWER_BOUNDARY,
+            *DICE_UPPER_BOUNDARY,
+        );
+
+        Some(Round {
+            outcome: (number * *DICE_MULT).to_u64().unwrap(),
+            payout: if number >= number_to_roll {
+                amount * data.multiplier
             } else {
-                total_value -= bet.amount;
-                profits.push(Decimal::ZERO);
-            }
-
-            games = game + 1;
-
-            if (!bet.stop_win.is_zero() && total_value >= bet.stop_win)
-                || (!bet.stop_loss.is_zero() && total_value <= bet.stop_loss)
-            {
-                break;
-            }
-        }
-
-        if games != bet.num_games as usize {
-            total_profit += Decimal::from(bet.num_games as usize - games) * bet.amount;
-        }
-
-        Some(GameResult {
-            total_profit,
-            outcomes,
-            profits,
-            num_games: games as u32,
-            data: bet.data.clone(),
-            finished: true,
+                Decimal::ZERO
+            },
+            data: (),
         })
     }
 
-    fn numbers_per_bet(&self) -> u64 {
-        1
+    fn max_payout(&self, data: &RocketData, amount: Decimal) -> Option<Decimal> {
+        Some(amount * data.multiplier)
     }
 }
@@ -1,31 +1,54 @@
-use crate::models::{db_models::GameResult, json_requests::PropagatedBet};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use rust_decimal::Decimal;
-use tracing::error;
 
-use crate::games::GameEng;
+use crate::games::{Round, RoundGameEng};
 
+/// Number of the `00` pocket
+pub const DOUBLE_ZERO: u8 = 37;
+
+const RED_NUMBERS: [u8; 18] = [
+    1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36,
+];
+
+/// The numbers of the inside bets can be listed in any order, `37` is the `00`
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
 #[serde(tag = "type", content = "data")]
 pub enum RouletteBetType {
     /// false - red, true - black
     Color(bool),
     Zero,
+    /// Only on the double zero wheel
+    DoubleZero,
+    /// From 1 to 36
     Number(u8),
+    /// Split of two adjacent numbers, including the splits with the zeros
     Number2 {
         numbers: [u8; 2],
     },
+    /// Street of a row, or a trio with the zeros
+    Number3 {
+        numbers: [u8; 3],
+    },
+    /// Corner of four numbers, or the first four `0-1-2-3` on the single zero wheel
     Number4 {
         numbers: [u8; 4],
     },
-    /// false - 1-18
+    /// `0-00-1-2-3`, only on the double zero wheel
+    TopLine,
+    /// Six line of two adjacent rows
+    Number6 {
+        numbers: [u8; 6],
+    },
+    /// true - 1-18
     Number18(bool),
     /// false - odd
     OddEven(bool),
-    /// 0 - 1-12, 1 - 13-24
+    /// 0 - 1-12, 1 - 13-24, 2 - 25-36
     Number12(u8),
+    /// 0 - 1, 4, .., 34, 1 - 2, 5, .., 35, 2 - 3, 6, .., 36
+    Column(u8),
 }
 
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
@@ -34,114 +57,248 @@ pub struct RouletteBet {
     pub bet: RouletteBetType,
 }
 
+/// The profits of the result are the payouts of the bets in the same order, repeated for every
+/// played game
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct RouletteData {
     bets: Vec<RouletteBet>,
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+pub enum RouletteWheel {
+    /// European wheel with 37 pockets
+    #[default]
+    SingleZero,
+    /// American wheel with 38 pockets
+    DoubleZero,
+}
+
+fn default_num3_coef() -> Decimal {
+    Decimal::from(12)
+}
+
+fn default_num5_coef() -> Decimal {
+    Decimal::from(7)
+}
+
+fn default_num6_coef() -> Decimal {
+    Decimal::from(6)
+}
+
+/// The wheel and the coefficients of the streets, the top line and the six lines default to
+/// the ones of the single zero wheel, paying 36 divided by the numbers covered
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct Roulette {
+    #[serde(default)]
+    pub wheel: RouletteWheel,
+    /// Multiplier of the bets on `0` and `00`
     pub zero_coef: Decimal,
     pub num_coef: Decimal,
     pub num2_coef: Decimal,
+    #[serde(default = "default_num3_coef")]
+    pub num3_coef: Decimal,
     pub num4_coef: Decimal,
-    pub num18_coef: Decimal,
+    #[serde(default = "default_num5_coef")]
+    pub num5_coef: Decimal,
+    #[serde(default = "default_num6_coef")]
+    pub num6_coef: Decimal,
+    /// Multiplier of the dozens and the columns
     pub num12_coef: Decimal,
+    /// Multiplier of the even money bets
+    pub num18_coef: Decimal,
 }
 
-impl GameEng for Roulette {
-    fn play(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
-        let data: RouletteData = serde_json::from_str(&bet.data)
-            .map_err(|e| {
-                error!("Error parsing Roulette data `{:?}`: {:?}", bet.data, e);
-                e
-            })
-            .ok()?;
+impl Roulette {
+    fn pockets(&self) -> u64 {
+        match self.wheel {
+            RouletteWheel::SingleZero => 37,
+            RouletteWheel::DoubleZero => 38,
+        }
+    }
 
-        let calculated_amount: Decimal = data.bets.iter().map(|b| b.amount).sum();
-        if calculated_amount != bet.amount {
-            return None;
+    /// Sorted numbers of the legal inside bets covering 2, 3, 4 or 6 numbers
+    fn inside_bets(&self) -> Vec<Vec<u8>> {
+        let mut bets = Vec::new();
+        for n in 1..=36u8 {
+            if n % 3 != 0 {
+                bets.push(vec![n, n + 1]);
+            }
+            if n <= 33 {
+                bets.push(vec![n, n + 3]);
+            }
+            if n % 3 == 1 {
+                bets.push(vec![n, n + 1, n + 2]);
+                if n <= 31 {
+                    bets.push((n..n + 6).collect());
+                }
+            }
+            if n % 3 != 0 && n <= 32 {
+                bets.push(vec![n, n + 1, n + 3, n + 4]);
+            }
         }
 
-        let outcome = random_numbers[0] % 36;
+        match self.wheel {
+            RouletteWheel::SingleZero => {
+                bets.extend([
+                    vec![0, 1],
+                    vec![0, 2],
+                    vec![0, 3],
+                    vec![0, 1, 2],
+                    vec![0, 2, 3],
+                    vec![0, 1, 2, 3],
+                ]);
+            }
+            RouletteWheel::DoubleZero => {
+                bets.extend([
+                    vec![0, 1],
+                    vec![0, 2],
+                    vec![2, DOUBLE_ZERO],
+                    vec![3, DOUBLE_ZERO],
+                    vec![0, DOUBLE_ZERO],
+                    vec![0, 1, 2],
+                    vec![0, 2, DOUBLE_ZERO],
+                    vec![2, 3, DOUBLE_ZERO],
+                ]);
+            }
+        }
+
+        bets
+    }
 
-        let mut profit = Decimal::ZERO;
+    fn is_inside_bet(&self, numbers: &[u8]) -> bool {
+        let mut numbers = numbers.to_vec();
+        numbers.sort_unstable();
+        self.inside_bets().contains(&numbers)
+    }
 
-        for bet in data.bets {
-            match bet.bet {
-                RouletteBetType::Color(color) => {
-                    if outcome != 0 && (color && outcome % 2 == 0) || (!color && outcome % 2 == 1) {
-                        profit += bet.amount * self.num18_coef;
-                    }
+    /// Payout of the bet on the outcome, `None` if the bet is not legal
+    fn payout(&self, bet: &RouletteBet, outcome: u8) -> Option<Decimal> {
+        let is_number = (1..=36).contains(&outcome);
+        let (won, coef) = match &bet.bet {
+            RouletteBetType::Color(color) => (
+                is_number && RED_NUMBERS.contains(&outcome) != *color,
+                self.num18_coef,
+            ),
+            RouletteBetType::Zero => (outcome == 0, self.zero_coef),
+            RouletteBetType::DoubleZero => {
+                if self.wheel != RouletteWheel::DoubleZero {
+                    return None;
                 }
-                RouletteBetType::Zero => {
-                    if outcome == 0 {
-                        profit += bet.amount * self.zero_coef;
-                    }
+                (outcome == DOUBLE_ZERO, self.zero_coef)
+            }
+            RouletteBetType::Number(number) => {
+                if !(1..=36).contains(number) {
+                    return None;
                 }
-                RouletteBetType::Number(number) => {
-                    if outcome == number as u64 {
-                        profit += bet.amount * self.num_coef;
-                    }
+                (outcome == *number, self.num_coef)
+            }
+            RouletteBetType::Number2 { numbers } => {
+                if !self.is_inside_bet(numbers) {
+                    return None;
                 }
-                RouletteBetType::Number2 { numbers } => {
-                    if numbers.contains(&(outcome as u8)) {
-                        profit += bet.amount * self.num2_coef;
-                    }
+                (numbers.contains(&outcome), self.num2_coef)
+            }
+            RouletteBetType::Number3 { numbers } => {
+                if !self.is_inside_bet(numbers) {
+                    return None;
                 }
-                RouletteBetType::Number4 { numbers } => {
-                    if numbers.contains(&(outcome as u8)) {
-                        profit += bet.amount * self.num4_coef;
-                    }
+                (numbers.contains(&outcome), self.num3_coef)
+            }
+            RouletteBetType::Number4 { numbers } => {
+                if !self.is_inside_bet(numbers) {
+                    return None;
                 }
-                RouletteBetType::Number18(sector) => {
-                    if (sector && 1 <= outcome && outcome <= 18)
-                        || (!sector && 19 <= outcome && outcome <= 36)
-                    {
-                        profit += bet.amount * self.num18_coef;
-                    }
+                (numbers.contains(&outcome), self.num4_coef)
+            }
+            RouletteBetType::TopLine => {
+                if self.wheel != RouletteWheel::DoubleZero {
+                    return None;
                 }
-                RouletteBetType::OddEven(is_even) => {
-                    if outcome != 0 && (!is_even && outcome % 2 == 1)
-                        || (is_even && outcome % 2 == 0)
-                    {
-                        profit += bet.amount * self.num18_coef;
-                    }
+                (outcome <= 3 || outcome == DOUBLE_ZERO, self.num5_coef)
+            }
+            RouletteBetType::Number6 { numbers } => {
+                if !self.is_inside_bet(numbers) {
+                    return None;
                 }
-                RouletteBetType::Number12(sector) => match sector {
-                    0 => {
-                        if 1 <= outcome && outcome <= 12 {
-                            profit += bet.amount * self.num12_coef;
-                        }
-                    }
-                    1 => {
-                        if 13 <= outcome && outcome <= 24 {
-                            profit += bet.amount * self.num12_coef;
-                        }
-                    }
-                    2 => {
-                        if 25 <= outcome && outcome <= 36 {
-                            profit += bet.amount * self.num12_coef;
-                        }
-                    }
-                    _ => {
-                        return None;
-                    }
-                },
+                (numbers.contains(&outcome), self.num6_coef)
+            }
+            RouletteBetType::Number18(low) => {
+                (is_number && (outcome <= 18) == *low, self.num18_coef)
             }
+            RouletteBetType::OddEven(is_even) => {
+                (is_number && (outcome % 2 == 0) == *is_even, self.num18_coef)
+            }
+            RouletteBetType::Number12(sector) => {
+                if *sector > 2 {
+                    return None;
+                }
+                (is_number && (outcome - 1) / 12 == *sector, self.num12_coef)
+            }
+            RouletteBetType::Column(column) => {
+                if *column > 2 {
+                    return None;
+                }
+                (is_number && (outcome - 1) % 3 == *column, self.num12_coef)
+            }
+        };
+
+        Some(if won {
+            bet.amount * coef
+        } else {
+            Decimal::ZERO
+        })
+    }
+}
+
+impl RoundGameEng for Roulette {
+    const NAME: &'static str = "Roulette";
+
+    type Data = RouletteData;
+    type RoundData = Vec<Decimal>;
+
+    fn play_round(
+        &self,
+        data: &RouletteData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<Vec<Decimal>>> {
+        if data.bets.is_empty() || data.bets.iter().any(|b| b.amount <= Decimal::ZERO) {
+            return None;
+        }
+        let calculated_amount: Decimal = data.bets.iter().map(|b| b.amount).sum();
+        if calculated_amount != amount {
+            return None;
         }
 
-        Some(GameResult {
-            total_profit: profit,
-            outcomes: vec![outcome],
-            profits: vec![profit],
-            num_games: 1,
-            data: bet.data.clone(),
-            finished: true,
+        let outcome = (random_numbers[0] % self.pockets()) as u8;
+
+        let payouts = data
+            .bets
+            .iter()
+            .map(|bet| self.payout(bet, outcome))
+            .collect::<Option<Vec<Decimal>>>()?;
+
+        Some(Round {
+            outcome: outcome as u64,
+            payout: payouts.iter().sum(),
+            data: payouts,
         })
     }
 
-    fn numbers_per_bet(&self) -> u64 {
-        1
+    fn max_payout(&self, data: &RouletteData, _: Decimal) -> Option<Decimal> {
+        (0..self.pockets())
+            .map(|outcome| {
+                data.bets
+                    .iter()
+                    .map(|bet| self.payout(bet, outcome as u8))
+                    .sum::<Option<Decimal>>()
+            })
+            .collect::<Option<Vec<Decimal>>>()?
+            .into_iter()
+            .max()
+    }
+
+    fn round_profits(&self, round: &Round<Vec<Decimal>>) -> Vec<Decimal> {
+        round.data.clone()
     }
 }
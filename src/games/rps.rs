@@ -1,17 +1,9 @@
-use crate::{
-    db::DB,
-    models::{
-        db_models::{Bet, GameResult},
-        json_requests::PropagatedBet,
-    },
-};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use rust_decimal::Decimal;
-use tracing::error;
 
-use crate::games::GameEng;
+use crate::games::{Round, RoundGameEng};
 
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct RPSData {
@@ -55,70 +47,37 @@ pub struct RPS {
     pub draw_coef: Decimal,
 }
 
-impl GameEng for RPS {
-    fn play(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
-        let data: RPSData = serde_json::from_str(&bet.data)
-            .map_err(|e| {
-                error!("Error parsing RPS data `{:?}`: {:?}", bet.data, e);
-                e
-            })
-            .ok()?;
-        if data.action > 2 {
-            return None;
-        }
-
-        let mut total_profit = Decimal::ZERO;
-        let mut total_value = Decimal::ZERO;
-        let mut games = 0;
-
-        let profit = bet.amount * self.profit_coef;
-        let draw = bet.amount * self.draw_coef;
-
-        let mut outcomes: Vec<u64> = Vec::with_capacity(random_numbers.len());
-        let mut profits: Vec<Decimal> = Vec::with_capacity(random_numbers.len());
-        for (game, number) in random_numbers.iter().enumerate() {
-            let action = number % 3;
-            outcomes.push(action);
+impl RoundGameEng for RPS {
+    const NAME: &'static str = "RPS";
 
-            let rps_result = rps_outcome(data.action as u64, action);
+    type Data = RPSData;
+    type RoundData = ();
 
-            if rps_result == 2 {
-                total_profit += draw;
-                total_value += draw;
-                profits.push(draw);
-            } else if rps_result == 1 {
-                total_profit += profit;
-                total_value += profit;
-                profits.push(profit);
-            } else {
-                profits.push(Decimal::ZERO);
-                total_value -= bet.amount;
-            }
-
-            games = game + 1;
-
-            if (!bet.stop_win.is_zero() && total_value >= bet.stop_win)
-                || (!bet.stop_loss.is_zero() && total_value <= bet.stop_loss)
-            {
-                break;
-            }
-        }
-
-        if games != bet.num_games as usize {
-            total_profit += Decimal::from(bet.num_games as usize - games) * bet.amount;
+    fn play_round(
+        &self,
+        data: &RPSData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<()>> {
+        if data.action > 2 {
+            return None;
         }
 
-        Some(GameResult {
-            total_profit,
-            outcomes,
-            profits,
-            num_games: games as u32,
-            data: bet.data.clone(),
-            finished: true,
+        let action = random_numbers[0] % 3;
+        let payout = match rps_outcome(data.action as u64, action) {
+            2 => amount * self.draw_coef,
+            1 => amount * self.profit_coef,
+            _ => Decimal::ZERO,
+        };
+
+        Some(Round {
+            outcome: action,
+            payout,
+            data: (),
         })
     }
 
-    fn numbers_per_bet(&self) -> u64 {
-        1
+    fn max_payout(&self, _: &RPSData, amount: Decimal) -> Option<Decimal> {
+        Some(amount * self.profit_coef.max(self.draw_coef))
     }
 }
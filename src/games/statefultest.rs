@@ -34,6 +34,20 @@ impl StatefulGameEng for StatefullTest {
         1
     }
 
+    /// The test game can be continued as long as it's won, so its payout has no bound and its
+    /// bets are not accepted
+    fn max_payout(&self, _: &PropagatedBet) -> Option<Decimal> {
+        None
+    }
+
+    fn resolve_data(&self, _: &crate::models::db_models::GameState) -> Option<String> {
+        serde_json::to_string(&StatefullTestData {
+            num: None,
+            end_game: true,
+        })
+        .ok()
+    }
+
     fn start_playing(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
         let data: StatefullTestData = serde_json::from_str(&bet.data)
             .map_err(|e| {
@@ -1,17 +1,9 @@
-use crate::{
-    db::DB,
-    models::{
-        db_models::{Bet, GameResult},
-        json_requests::PropagatedBet,
-    },
-};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use rust_decimal::Decimal;
-use tracing::error;
 
-use crate::games::GameEng;
+use crate::games::{Round, RoundGameEng};
 
 #[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct WheelData {
@@ -26,69 +18,42 @@ pub struct Wheel {
     pub max_num_sectors: u32,
 }
 
-impl GameEng for Wheel {
-    fn play(&self, bet: &PropagatedBet, random_numbers: &[u64]) -> Option<GameResult> {
-        let data: WheelData = serde_json::from_str(&bet.data)
-            .map_err(|e| {
-                error!("Error parsing Wheel data `{:?}`: {:?}", bet.data, e);
-                e
-            })
-            .ok()?;
+impl RoundGameEng for Wheel {
+    const NAME: &'static str = "Wheel";
 
+    type Data = WheelData;
+    type RoundData = ();
+
+    fn play_round(
+        &self,
+        data: &WheelData,
+        amount: Decimal,
+        random_numbers: &[u64],
+    ) -> Option<Round<()>> {
         if data.risk > self.max_risk || data.num_sectors > self.max_num_sectors {
             return None;
         }
 
-        let multipliers = &self.multipliers[data.risk as usize][data.num_sectors as usize];
-
-        let mut total_profit = Decimal::ZERO;
-        let mut total_value = Decimal::ZERO;
-        let mut games = 0;
-
+        let multipliers = self
+            .multipliers
+            .get(data.risk as usize)?
+            .get(data.num_sectors as usize)?;
         let num_sectors = (data.num_sectors + 1) * 10;
+        let sector = random_numbers[0] % num_sectors as u64;
 
-        let mut outcomes: Vec<u64> = Vec::with_capacity(random_numbers.len());
-        let mut profits: Vec<Decimal> = Vec::with_capacity(random_numbers.len());
-        for (game, number) in random_numbers.iter().enumerate() {
-            let sector = number % num_sectors as u64;
-            outcomes.push(sector);
-
-            let multiplier = multipliers[sector as usize];
-
-            if multiplier.is_zero() {
-                total_value -= bet.amount;
-                profits.push(Decimal::ZERO);
-            } else {
-                let profit = bet.amount * multiplier;
-                total_profit += profit;
-                total_value += profit;
-                profits.push(profit);
-            }
-
-            games = game + 1;
-
-            if (!bet.stop_win.is_zero() && total_value >= bet.stop_win)
-                || (!bet.stop_loss.is_zero() && total_value <= bet.stop_loss)
-            {
-                break;
-            }
-        }
-
-        if games != bet.num_games as usize {
-            total_profit += Decimal::from(bet.num_games as usize - games) * bet.amount;
-        }
-
-        Some(GameResult {
-            total_profit,
-            outcomes,
-            profits,
-            num_games: games as u32,
-            data: bet.data.clone(),
-            finished: true,
+        Some(Round {
+            outcome: sector,
+            payout: amount * multipliers.get(sector as usize)?,
+            data: (),
         })
     }
 
-    fn numbers_per_bet(&self) -> u64 {
-        1
+    fn max_payout(&self, data: &WheelData, amount: Decimal) -> Option<Decimal> {
+        let multipliers = self
+            .multipliers
+            .get(data.risk as usize)?
+            .get(data.num_sectors as usize)?;
+
+        Some(amount * multipliers.iter().max()?)
     }
 }
@@ -1,4 +1,4 @@
-use crate::{config, models::json_responses::Bets};
+use crate::{config, game_engine, models::json_responses::Bets};
 
 use super::*;
 
@@ -17,7 +17,10 @@ use super::*;
             ("game_name" = String, Path, description = "Name of the game")
         ),
     )]
-pub async fn get_bets_for_game(game_name: String, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_bets_for_game<D: BetStore>(
+    game_name: String,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let bets = db
         .fetch_bets_for_gamename(&game_name, *config::PAGE_SIZE)
         .await
@@ -42,10 +45,10 @@ pub async fn get_bets_for_game(game_name: String, db: DB) -> Result<WarpResponse
             ("last_id" = Option<i64>, Path, description = "last bet id")
         ),
     )]
-pub async fn get_user_bets(
+pub async fn get_user_bets<D: BetStore>(
     user_id: i64,
     last_id: Option<i64>,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let bets = db
         .fetch_bets_for_user(user_id, last_id, *config::PAGE_SIZE)
@@ -71,10 +74,10 @@ pub async fn get_user_bets(
             ("last_id" = Option<i64>, Path, description = "last bet id")
         ),
     )]
-pub async fn get_user_bets_inc(
+pub async fn get_user_bets_inc<D: BetStore>(
     user_id: i64,
     last_id: Option<i64>,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let bets = db
         .fetch_bets_for_user_inc(user_id, last_id, *config::PAGE_SIZE)
@@ -96,7 +99,7 @@ pub async fn get_user_bets_inc(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_all_last_bets(db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_all_last_bets<D: BetStore>(db: D) -> Result<WarpResponse, warp::Rejection> {
     let bets = db
         .fetch_all_latest_bets(*config::PAGE_SIZE)
         .await
@@ -104,3 +107,38 @@ pub async fn get_all_last_bets(db: DB) -> Result<WarpResponse, warp::Rejection>
 
     Ok(gen_arbitrary_response(ResponseBody::Bets(Bets { bets })))
 }
+
+/// Verify bet
+///
+/// Replays the bet using its revealed seeds and checks that the stored outcomes and profits match
+#[utoipa::path(
+        tag="bets",
+        get,
+        path = "/api/bets/verify/{bet_id}",
+        responses(
+            (status = 200, description = "Bet verification", body = BetVerification),
+            (status = 400, description = "Server seed is not revealed yet", body = ErrorText),
+            (status = 500, description = "Internal server error", body = ErrorText),
+        ),
+        params(
+            ("bet_id" = i64, Path, description = "Id of the bet")
+        ),
+    )]
+pub async fn verify_bet<D: SeedStore + BetStore>(
+    bet_id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
+    let bet = db
+        .fetch_bet_by_id(bet_id)
+        .await
+        .map_err(|e| reject::custom(ApiError::DbError(e)))?
+        .ok_or(reject::custom(ApiError::BetDoesntExist(bet_id)))?;
+
+    let verification = game_engine::replay_bet(&db, &bet)
+        .await
+        .map_err(reject::custom)?;
+
+    Ok(gen_arbitrary_response(ResponseBody::BetVerification(
+        verification,
+    )))
+}
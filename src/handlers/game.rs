@@ -8,10 +8,13 @@ use std::{
 };
 
 use crate::{
+    autobet,
     config::PASSWORD_SALT,
-    models::json_responses::{Games, Seed, UuidToken},
+    game_engine,
+    games::registry::GAMES,
+    models::json_responses::{BetRejected, Games, Seed, UuidToken},
     tools::{self, blake_hash_256},
-    ChannelType, EngineBetSender, WsData, WsDataFeedReceiver, WsDataFeedSender, WsEventSender,
+    AutoBetEvent, AutoBetEventSender, ChannelType, CrashEvent, CrashEventSender, EngineBetSender, StatefulEngineBetRouter, WsData, WsDataFeedReceiver, WsDataFeedSender, WsEventSender,
     WsManagerEvent, WsManagerEventSender,
 };
 
@@ -110,6 +113,9 @@ pub async fn websockets_handler(
     db: DB,
     manager_writer: WsManagerEventSender,
     engine_sender: EngineBetSender,
+    stateful_engine_sender: StatefulEngineBetRouter,
+    crash_sender: CrashEventSender,
+    autobet_sender: AutoBetEventSender,
 ) {
     let (data_feed_tx, mut data_feed): (WsDataFeedSender, WsDataFeedReceiver) = unbounded_channel();
 
@@ -263,18 +269,117 @@ pub async fn websockets_handler(
                                     if let Some(user_id) = user_id{
                                         bet.user_id.replace(user_id);
                                         bet.uuid.replace(uuid.clone());
-                                        if let Err(_) = engine_sender.send(EnginePropagatedBet::ContinueGame(bet)).await{
+                                        // the steps don't wait behind the stateless bets
+                                        if let Err(_) = stateful_engine_sender.send(EnginePropagatedBet::ContinueGame(bet)){
                                             break;
                                         };
                                     }
 
                                 }
+                                WebsocketsIncommingMessage::SubscribeCrash => {
+                                    if let Err(_) = manager_writer.send(WsManagerEvent::SubscribeChannel { id: uuid.clone(), channel: ChannelType::CrashRound }){
+                                        break;
+                                    }
+                                },
+                                WebsocketsIncommingMessage::UnsubscribeCrash => {
+                                    if let Err(_) = manager_writer.send(WsManagerEvent::UnsubscribeChannel { id: uuid.clone(), channel: ChannelType::CrashRound }){
+                                        break;
+                                    }
+                                },
+                                WebsocketsIncommingMessage::SubscribeJackpot => {
+                                    if let Err(_) = manager_writer.send(WsManagerEvent::SubscribeChannel { id: uuid.clone(), channel: ChannelType::Jackpot }){
+                                        break;
+                                    }
+                                    let response = match db.fetch_jackpots().await {
+                                        Ok(jackpots) => ResponseBody::Jackpots(jackpots),
+                                        Err(e) => ResponseBody::ErrorText(ErrorText { error: format!("{:?}", e) }),
+                                    };
+                                    if let Err(e) = ws_tx.send(Message::text(serde_json::to_string(&response).unwrap())).await{
+                                        error!("Error on socket `{:?}`: `{:?}`",ws_tx,e);
+                                        break;
+                                    }
+                                },
+                                WebsocketsIncommingMessage::UnsubscribeJackpot => {
+                                    if let Err(_) = manager_writer.send(WsManagerEvent::UnsubscribeChannel { id: uuid.clone(), channel: ChannelType::Jackpot }){
+                                        break;
+                                    }
+                                },
+                                WebsocketsIncommingMessage::CrashBet(mut bet) => {
+                                    if let Some(user_id) = user_id{
+                                        bet.user_id.replace(user_id);
+                                        bet.uuid.replace(uuid.clone());
+                                        if let Err(_) = crash_sender.send(CrashEvent::Bet(bet)){
+                                            break;
+                                        };
+                                    }
+                                },
+                                WebsocketsIncommingMessage::CrashCashout(mut cashout) => {
+                                    if let Some(user_id) = user_id{
+                                        cashout.user_id.replace(user_id);
+                                        cashout.uuid.replace(uuid.clone());
+                                        if let Err(_) = crash_sender.send(CrashEvent::Cashout(cashout)){
+                                            break;
+                                        };
+                                    }
+                                },
+                                WebsocketsIncommingMessage::StartAutoBet(mut request) => {
+                                    if let Some(user_id) = user_id{
+                                        request.user_id.replace(user_id);
+                                        request.uuid.replace(uuid.clone());
+                                        let response = match autobet::start_session(&db, &request).await {
+                                            Ok(session) => {
+                                                if let Err(_) = autobet_sender.send(AutoBetEvent::Start(session.clone())){
+                                                    break;
+                                                }
+                                                ResponseBody::AutoBet(session)
+                                            }
+                                            Err((reason_code, message)) => ResponseBody::BetRejected(BetRejected { uuid: uuid.clone(), reason_code, message }),
+                                        };
+                                        if let Err(e) = ws_tx.send(Message::text(serde_json::to_string(&response).unwrap())).await{
+                                            error!("Error on socket `{:?}`: `{:?}`",ws_tx,e);
+                                            break;
+                                        }
+                                    }
+                                },
+                                WebsocketsIncommingMessage::StopAutoBet { session_id } => {
+                                    if let Some(user_id) = user_id{
+                                        let response = match db.stop_auto_bet(session_id, user_id, "Stopped by the player").await {
+                                            Ok(Some(session)) => {
+                                                if let Err(_) = autobet_sender.send(AutoBetEvent::Stop(session.id)){
+                                                    break;
+                                                }
+                                                ResponseBody::AutoBet(session)
+                                            }
+                                            Ok(None) => ResponseBody::ErrorText(ErrorText { error: "Active auto bet session not found".into() }),
+                                            Err(e) => ResponseBody::ErrorText(ErrorText { error: format!("{:?}", e) }),
+                                        };
+                                        if let Err(e) = ws_tx.send(Message::text(serde_json::to_string(&response).unwrap())).await{
+                                            error!("Error on socket `{:?}`: `{:?}`",ws_tx,e);
+                                            break;
+                                        }
+                                    }
+                                },
+                                WebsocketsIncommingMessage::GetAutoBets => {
+                                    if let Some(user_id) = user_id{
+                                        if let Err(e) = db.rebind_auto_bets(user_id, &uuid).await {
+                                            error!("Error binding auto bet sessions to `{}`: {:?}", uuid, e);
+                                        }
+                                        let response = match db.fetch_user_auto_bets(user_id, autobet::AUTO_BETS_HISTORY).await {
+                                            Ok(sessions) => ResponseBody::AutoBets(sessions),
+                                            Err(e) => ResponseBody::ErrorText(ErrorText { error: format!("{:?}", e) }),
+                                        };
+                                        if let Err(e) = ws_tx.send(Message::text(serde_json::to_string(&response).unwrap())).await{
+                                            error!("Error on socket `{:?}`: `{:?}`",ws_tx,e);
+                                            break;
+                                        }
+                                    }
+                                },
                                 WebsocketsIncommingMessage::GetState(request) => {
                                     if let Some(user_id) = user_id {
                                         if let Ok(Some(state)) = db.fetch_game_state(request.game_id, user_id, request.coin_id).await{
                                             if let Err(e) = ws_tx
                                                 .send(Message::text(
-                                                    serde_json::to_string(&ResponseBody::State(state))
+                                                    serde_json::to_string(&ResponseBody::State(state.public()))
                                                         .unwrap(),
                                                 ))
                                                 .await
@@ -292,6 +397,21 @@ pub async fn websockets_handler(
                                     }
                                 }
 
+                                WebsocketsIncommingMessage::VerifyBet { bet_id } => {
+                                    let response = match db.fetch_bet_by_id(bet_id).await {
+                                        Ok(Some(bet)) => match game_engine::replay_bet(&db, &bet).await {
+                                            Ok(verification) => ResponseBody::BetVerification(verification),
+                                            Err(e) => ResponseBody::ErrorText(ErrorText { error: e.to_string() }),
+                                        },
+                                        Ok(None) => ResponseBody::ErrorText(ErrorText { error: ApiError::BetDoesntExist(bet_id).to_string() }),
+                                        Err(e) => ResponseBody::ErrorText(ErrorText { error: format!("{:?}", e) }),
+                                    };
+                                    if let Err(e) = ws_tx.send(Message::text(serde_json::to_string(&response).unwrap())).await{
+                                        error!("Error on socket `{:?}`: `{:?}`",ws_tx,e);
+                                        break;
+                                    }
+                                }
+
                                 WebsocketsIncommingMessage::GetUuid => {
                                     if let Err(e) = ws_tx
                                         .send(Message::text(
@@ -377,12 +497,15 @@ pub async fn websockets_handler(
 /// Get all games
 ///
 /// Get all games records
+///
+/// Returns all the games along with the bet limits for every game and coin and the schemas of
+/// the bet data of every game type
 #[utoipa::path(
         tag="game",
         get,
         path = "/api/game/list",
         responses(
-            (status = 200, description = "All games records", body = Game),
+            (status = 200, description = "All games records", body = Games),
             (status = 500, description = "Internal server error", body = ErrorText),
         )
     )]
@@ -391,6 +514,14 @@ pub async fn get_all_games(db: DB) -> Result<WarpResponse, warp::Rejection> {
         .fetch_all_games()
         .await
         .map_err(|e| reject::custom(ApiError::DbError(e)))?;
+    let limits = db
+        .fetch_all_game_limits()
+        .await
+        .map_err(|e| reject::custom(ApiError::DbError(e)))?;
 
-    Ok(gen_arbitrary_response(ResponseBody::Games(Games { games })))
+    Ok(gen_arbitrary_response(ResponseBody::Games(Games {
+        games,
+        limits,
+        schemas: GAMES.iter().map(|game| game.game_schema()).collect(),
+    })))
 }
@@ -1,3 +1,5 @@
+use crate::communication::EngineBetSender;
+use crate::metrics;
 use crate::models::{
     db_models::TimeBoundaries,
     json_responses::{LeaderboardResponse, PromTokens},
@@ -87,3 +89,17 @@ pub async fn get_totals(db: DB) -> Result<WarpResponse, warp::Rejection> {
         .map_err(|e| reject::custom(ApiError::DbError(e)))?;
     Ok(gen_arbitrary_response(ResponseBody::Totals(totals)))
 }
+
+/// Metrics of the engines, the websocket manager, the db pool and the payment callbacks
+/// in the Prometheus text format
+pub async fn get_metrics(
+    db: DB,
+    engine_sender: EngineBetSender,
+) -> Result<WarpResponse, warp::Rejection> {
+    Ok(HttpResponse::builder()
+        .status(200)
+        .header("Content-Type", "text/plain; version=0.0.4")
+        .body(metrics::gather(&db, &engine_sender))
+        .unwrap()
+        .into_response())
+}
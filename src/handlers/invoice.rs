@@ -4,7 +4,7 @@ use crate::models::db_models::BillineInvoiceStatus;
 use crate::models::json_responses::{BillineCreateInvoiceResponse, Prices};
 use crate::models::{db_models::Invoice, json_responses::OneTimeToken};
 use crate::tools::blake_hash;
-use crate::{config, WsManagerEvent, WsManagerEventSender};
+use crate::{config, metrics, WsManagerEvent, WsManagerEventSender};
 
 use billine::CallbackIframe;
 use p2way::P2Way;
@@ -39,6 +39,7 @@ pub async fn invoice_callback(
     db: DB,
     manager_writer: WsManagerEventSender,
 ) -> Result<WarpResponse, warp::Rejection> {
+    metrics::invoice_callback("thedex", &format!("{:?}", invoice.status));
     match invoice.status {
         thedex::models::InvoiceStatus::Successful => {
             if let Some(order_id) = &invoice.order_id {
@@ -121,8 +122,10 @@ pub async fn invoice_billine_callback(
             "Billine signatire is wrong: {:?} != {:?}",
             calc_signature, invoice.co_sign
         );
+        metrics::invoice_callback("billine", "BadSignature");
         return Err(reject::custom(ApiError::UpdateAmountsError));
     }
+    metrics::invoice_callback("billine", &format!("{:?}", invoice.co_inv_st));
     match invoice.co_inv_st {
         billine::Status::Success => {
             db.billine_invoice_update_status(&invoice.co_order_no, BillineInvoiceStatus::Success)
@@ -475,8 +478,10 @@ pub async fn p2way_callback(
     info!("P2Way callback {:?}, address: {:?}", data, address);
     if !config::P2WAY_SECRETKEY_HASH.eq(&data.data.merchant_secret_key) {
         info!("P2Way callback rejected, bad api_key");
+        metrics::invoice_callback("p2way", "BadApiKey");
         return Err(reject::custom(ApiError::UpdateAmountsError));
     }
+    metrics::invoice_callback("p2way", &format!("{:?}", data.data.order_state));
 
     match data.data.order_state {
         p2way::OrderState::Success => db
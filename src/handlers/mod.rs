@@ -1,4 +1,4 @@
-use crate::db::DB;
+use crate::db::{BetStore, PartnerStore, SeedStore, UserStore, WalletStore, DB};
 use crate::errors::ApiError;
 use crate::models::json_requests::{self, CreateInvoice, Login};
 
@@ -30,9 +30,9 @@ use super::*;
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn register_partner(
+pub async fn register_partner<D: PartnerStore>(
     data: json_requests::RegisterPartner,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let mut hasher = Blake2b512::new();
 
@@ -78,9 +78,9 @@ pub async fn register_partner(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn submit_question(
+pub async fn submit_question<D: PartnerStore>(
     _data: json_requests::SubmitQuestion,
-    _db: DB,
+    _db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     //db.submit_question(&data.name, &data.email, &data.message)
     //    .await
@@ -102,10 +102,10 @@ pub async fn submit_question(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn add_contacts(
+pub async fn add_contacts<D: PartnerStore>(
     partner_id: i64,
     data: json_requests::AddPartnerContacts,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     db.add_partner_contacts(
         partner_id,
@@ -134,10 +134,10 @@ pub async fn add_contacts(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn submit_withdrawal(
+pub async fn submit_withdrawal<D: PartnerStore>(
     partner_id: i64,
     data: WithdrawRequest,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     db.create_withdraw_request(partner_id, &data)
         .await
@@ -159,10 +159,10 @@ pub async fn submit_withdrawal(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn add_partner_site(
+pub async fn add_partner_site<D: PartnerStore>(
     partner_id: i64,
     data: json_requests::AddPartnerSite,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     db.add_partner_site(partner_id, &data.url, &data.name)
         .await
@@ -184,10 +184,10 @@ pub async fn add_partner_site(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn add_partner_subid(
+pub async fn add_partner_subid<D: PartnerStore>(
     partner_id: i64,
     data: json_requests::AddPartnerSubid,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     db.add_partner_subid(data.internal_site_id, partner_id, &data.url, &data.name)
         .await
@@ -213,11 +213,11 @@ pub async fn add_partner_subid(
             ("sub_id" = i64, Path, description = "Relative subid ofthe site, registered on partner's account"),
         ),
     )]
-pub async fn click_partner_subid(
+pub async fn click_partner_subid<D: PartnerStore>(
     partner_id: i64,
     site_id: i64,
     sub_id: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let subid = db
         .get_subid(partner_id, site_id, sub_id)
@@ -244,10 +244,10 @@ pub async fn click_partner_subid(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn connect_wallet(
+pub async fn connect_wallet<D: PartnerStore>(
     user_id: i64,
     data: json_requests::ConnectWallet,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let time = chrono::offset::Utc::now();
     let subid = db
@@ -274,7 +274,10 @@ pub async fn connect_wallet(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_partner(partner_id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_partner<D: PartnerStore>(
+    partner_id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let basic = db
         .get_partner(partner_id)
         .await
@@ -321,9 +324,9 @@ pub async fn get_partner(partner_id: i64, db: DB) -> Result<WarpResponse, warp::
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_partner_contacts(
+pub async fn get_partner_contacts<D: PartnerStore>(
     partner_id: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let contacts = db
         .get_partner_contacts(partner_id)
@@ -350,10 +353,10 @@ pub async fn get_partner_contacts(
             ("time_boundaries" = TimeBoundaries, Path, description = "Time boundaries in which to fetch connected wallets"),
         ),
     )]
-pub async fn get_partner_connected_wallets(
+pub async fn get_partner_connected_wallets<D: PartnerStore>(
     partner_id: i64,
     time_boundaries: TimeBoundaries,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let connected_wallets = db
         .get_partner_connected_wallets_amount(partner_id, time_boundaries)
@@ -380,10 +383,10 @@ pub async fn get_partner_connected_wallets(
             ("time_boundaries" = TimeBoundaries, Path, description = "Time boundaries in which to fetch connected wallets"),
         ),
     )]
-pub async fn get_partner_connected_wallets_with_deposits_amount(
+pub async fn get_partner_connected_wallets_with_deposits_amount<D: PartnerStore>(
     partner_id: i64,
     time_boundaries: TimeBoundaries,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let connected_wallets = db
         .get_partner_connected_wallets_with_deposits_amount(partner_id, time_boundaries)
@@ -410,10 +413,10 @@ pub async fn get_partner_connected_wallets_with_deposits_amount(
             ("time_boundaries" = TimeBoundaries, Path, description = "Time boundaries in which to fetch connected wallets"),
         ),
     )]
-pub async fn get_partner_connected_wallets_info(
+pub async fn get_partner_connected_wallets_info<D: PartnerStore + BetStore>(
     partner_id: i64,
     time_boundaries: TimeBoundaries,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let connected_wallets = db
         .get_partner_connected_wallets_info(partner_id, time_boundaries)
@@ -468,12 +471,12 @@ pub async fn get_partner_connected_wallets_info(
             ("step" = u64, Path, description = "Step from start to end"),
         ),
     )]
-pub async fn get_partner_connected_wallets_exact_date(
+pub async fn get_partner_connected_wallets_exact_date<D: PartnerStore>(
     partner_id: i64,
     begin: u64,
     end: u64,
     step: u64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let capacity = ((end - begin) / step) as usize;
     if capacity > 100 {
@@ -519,12 +522,12 @@ pub async fn get_partner_connected_wallets_exact_date(
             ("step" = u64, Path, description = "Step from start to end"),
         ),
     )]
-pub async fn get_partner_connected_wallets_betted_exact_date(
+pub async fn get_partner_connected_wallets_betted_exact_date<D: PartnerStore>(
     partner_id: i64,
     begin: u64,
     end: u64,
     step: u64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let capacity = ((end - begin) / step) as usize;
     if capacity > 100 {
@@ -564,7 +567,10 @@ pub async fn get_partner_connected_wallets_betted_exact_date(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_connected_totals(user_id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_connected_totals<D: PartnerStore + BetStore>(
+    user_id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let totals = db
         .fetch_user_totals(user_id)
         .await
@@ -588,10 +594,10 @@ pub async fn get_connected_totals(user_id: i64, db: DB) -> Result<WarpResponse,
             ("time_boundaries" = TimeBoundaries, Path, description = "Time boundaries in which to fetch withdrawal requests"),
         ),
     )]
-pub async fn get_withdrawal_requests(
+pub async fn get_withdrawal_requests<D: PartnerStore>(
     partner_id: i64,
     time_boundaries: TimeBoundaries,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let withdrawals = db
         .get_partner_withdrawal_requests(partner_id, time_boundaries)
@@ -621,12 +627,12 @@ pub async fn get_withdrawal_requests(
             ("step" = u64, Path, description = "Step from start to end"),
         ),
     )]
-pub async fn get_partner_clicks_exact_date(
+pub async fn get_partner_clicks_exact_date<D: PartnerStore>(
     partner_id: i64,
     begin: u64,
     end: u64,
     step: u64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let capacity = ((end - begin) / step) as usize;
     if capacity > 100 {
@@ -666,7 +672,10 @@ pub async fn get_partner_clicks_exact_date(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_partner_sites(partner_id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_partner_sites<D: PartnerStore>(
+    partner_id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let sites = db
         .get_partner_sites(partner_id)
         .await
@@ -700,10 +709,10 @@ pub async fn get_partner_sites(partner_id: i64, db: DB) -> Result<WarpResponse,
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn delete_partner_contacts(
+pub async fn delete_partner_contacts<D: PartnerStore>(
     partner_id: i64,
     contacts: json_requests::DeletePartnerContacts,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     db.delete_partner_contacts(partner_id, &contacts.contacts)
         .await
@@ -728,11 +737,11 @@ pub async fn delete_partner_contacts(
             ("sub_id" = i64, Path, description = "Relative subid ofthe site, registered on partner's account"),
         ),
     )]
-pub async fn get_clicks(
+pub async fn get_clicks<D: PartnerStore>(
     partner_id: i64,
     site_id: i64,
     sub_id: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let clicks = db
         .get_subid_clicks(partner_id, site_id, sub_id)
@@ -757,10 +766,10 @@ pub async fn get_clicks(
             ("site_id" = i64, Path, description = "Relative id of the site, registered on partner's account"),
         ),
     )]
-pub async fn get_site_clicks(
+pub async fn get_site_clicks<D: PartnerStore>(
     partner_id: i64,
     site_id: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let clicks = db
         .get_site_clicks(partner_id, site_id)
@@ -782,7 +791,10 @@ pub async fn get_site_clicks(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_partner_clicks(partner_id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_partner_clicks<D: PartnerStore>(
+    partner_id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let clicks = db
         .get_partner_clicks(partner_id)
         .await
@@ -804,10 +816,10 @@ pub async fn get_partner_clicks(partner_id: i64, db: DB) -> Result<WarpResponse,
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn partner_change_password(
+pub async fn partner_change_password<D: PartnerStore>(
     partner_id: i64,
     data: ChangePasswordRequest,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let old_hashed = blake_hash(&data.old_password);
     let new_hashed = blake_hash(&data.new_password);
@@ -835,7 +847,10 @@ pub async fn partner_change_password(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn login_partner(login: Login, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn login_partner<D: PartnerStore>(
+    login: Login,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let hashed_password = blake_hash(&login.password);
     let partner = db
         .login_partner(&login.login, &hashed_password)
@@ -1,8 +1,13 @@
 use crate::jwt;
 use crate::models::db_models::OauthProvider;
-use crate::models::json_responses::{Amounts, LatestGames, Seed, UserStripped};
+use crate::models::json_responses::{
+    Amounts, LatestGames, Seed, SeedHistory, SeedPairInfo, UserStripped,
+};
 use crate::tools::blake_hash;
-use crate::{config::PASSWORD_SALT, models::json_responses::AccessToken};
+use crate::{
+    config::{self, PASSWORD_SALT},
+    models::json_responses::AccessToken,
+};
 use base64::{engine::general_purpose, Engine as _};
 use blake2::{Blake2b512, Digest};
 
@@ -31,9 +36,9 @@ use crate::oauth_providers;
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn register_user(
+pub async fn register_user<D: UserStore + WalletStore>(
     data: json_requests::RegisterUser,
-    db: DB,
+    db: D,
     hcap: hcaptcha::HCaptcha,
 ) -> Result<WarpResponse, warp::Rejection> {
     let captcha_response = hcap
@@ -95,10 +100,10 @@ pub async fn register_user(
         )
 
     )]
-pub async fn register_referal_link(
+pub async fn register_referal_link<D: UserStore>(
     link_name: String,
     referal: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     db.create_referal_link(referal, &link_name)
         .await
@@ -123,10 +128,10 @@ pub async fn register_referal_link(
         )
 
     )]
-pub async fn register_referal(
+pub async fn register_referal<D: UserStore>(
     link_name: String,
     referal: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let referal_link = db
         .fetch_referal_link(&link_name)
@@ -153,9 +158,9 @@ pub async fn register_referal(
         ),
 
     )]
-pub async fn login_google(
+pub async fn login_google<D: UserStore + WalletStore>(
     query: oauth_providers::google::CodeResponse,
-    db: DB,
+    db: D,
     google: oauth_providers::google::GoogleOauth,
 ) -> Result<WarpResponse, warp::Rejection> {
     let code = if let Some(code) = query.code {
@@ -285,7 +290,10 @@ pub async fn login_google(
         )
 
     )]
-pub async fn refresh_token(token: String, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn refresh_token<D: UserStore>(
+    token: String,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let parts = token.split('.').collect::<Vec<&str>>();
     if parts.len() != 3 {
         return Err(reject::custom(ApiError::MalformedToken));
@@ -382,7 +390,10 @@ pub async fn refresh_token(token: String, db: DB) -> Result<WarpResponse, warp::
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn login_user(login: Login, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn login_user<D: UserStore>(
+    login: Login,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let hashed_password = blake_hash(&login.password);
     let user = db
         .login_user(&login.login, &hashed_password)
@@ -449,7 +460,7 @@ pub async fn login_user(login: Login, db: DB) -> Result<WarpResponse, warp::Reje
             ("user_id" = i64, Path, description = "User id")
         )
     )]
-pub async fn get_amounts(id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_amounts<D: WalletStore>(id: i64, db: D) -> Result<WarpResponse, warp::Rejection> {
     let amounts = db
         .fetch_amounts(id)
         .await
@@ -475,7 +486,10 @@ pub async fn get_amounts(id: i64, db: DB) -> Result<WarpResponse, warp::Rejectio
             ("user_id" = i64, Path, description = "User id")
         )
     )]
-pub async fn get_latest_games(id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_latest_games<D: BetStore>(
+    id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let games = db
         .latest_games(id)
         .await
@@ -501,7 +515,10 @@ pub async fn get_latest_games(id: i64, db: DB) -> Result<WarpResponse, warp::Rej
             ("user_id" = i64, Path, description = "User id")
         )
     )]
-pub async fn get_users_totals(id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_users_totals<D: BetStore>(
+    id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let totals = db
         .fetch_user_totals(id)
         .await
@@ -523,10 +540,10 @@ pub async fn get_users_totals(id: i64, db: DB) -> Result<WarpResponse, warp::Rej
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn change_username(
+pub async fn change_username<D: UserStore>(
     data: ChangeNickname,
     id: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     db.change_username(id, &data.nickname)
         .await
@@ -548,10 +565,10 @@ pub async fn change_username(
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn change_password(
+pub async fn change_password<D: UserStore>(
     data: ChangePasswordRequest,
     id: i64,
-    db: DB,
+    db: D,
 ) -> Result<WarpResponse, warp::Rejection> {
     let mut hasher = Blake2b512::new();
 
@@ -595,7 +612,7 @@ pub async fn change_password(
             ("user_id" = i64, Path, description = "User id")
         )
     )]
-pub async fn get_user(id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_user<D: UserStore>(id: i64, db: D) -> Result<WarpResponse, warp::Rejection> {
     let user = db
         .fetch_user(id)
         .await
@@ -623,7 +640,10 @@ pub async fn get_user(id: i64, db: DB) -> Result<WarpResponse, warp::Rejection>
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_client_seed(id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_client_seed<D: SeedStore>(
+    id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let seed = db
         .fetch_current_user_seed(id)
         .await
@@ -646,7 +666,10 @@ pub async fn get_client_seed(id: i64, db: DB) -> Result<WarpResponse, warp::Reje
             (status = 500, description = "Internal server error", body = ErrorText),
         ),
     )]
-pub async fn get_server_seed(id: i64, db: DB) -> Result<WarpResponse, warp::Rejection> {
+pub async fn get_server_seed<D: SeedStore>(
+    id: i64,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
     let seed = db
         .fetch_current_server_seed(id)
         .await
@@ -658,3 +681,36 @@ pub async fn get_server_seed(id: i64, db: DB) -> Result<WarpResponse, warp::Reje
         },
     )))
 }
+
+/// Get user seed history
+///
+/// Gets the client and server seed pairs of the user with the bets played with them, newest
+/// server seeds first, the server seeds are shown once revealed
+#[utoipa::path(
+        tag="user",
+        get,
+        path = "/api/user/seed/history/{page}",
+        responses(
+            (status = 200, description = "Seed history", body = SeedHistory),
+            (status = 500, description = "Internal server error", body = ErrorText),
+        ),
+        params(
+            ("page" = Option<i64>, Path, description = "Page number starting from 0")
+        ),
+    )]
+pub async fn get_seed_history<D: SeedStore>(
+    id: i64,
+    page: Option<i64>,
+    db: D,
+) -> Result<WarpResponse, warp::Rejection> {
+    let seeds = db
+        .fetch_seed_history(id, page.unwrap_or_default().max(0), *config::PAGE_SIZE)
+        .await
+        .map_err(|e| reject::custom(ApiError::DbError(e)))?;
+
+    Ok(gen_arbitrary_response(ResponseBody::SeedHistory(
+        SeedHistory {
+            seeds: seeds.into_iter().map(SeedPairInfo::from).collect(),
+        },
+    )))
+}
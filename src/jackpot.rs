@@ -0,0 +1,41 @@
+use crate::communication::{WsManagerEvent, WsManagerEventSender};
+use crate::config::JACKPOT_ODDS;
+use crate::models::db_models::SettledBet;
+use crate::tools::blake_hash_256_u64;
+use lazy_static::lazy_static;
+use rust_decimal::Decimal;
+use tracing::error;
+
+lazy_static! {
+    static ref U64_RANGE: Decimal = Decimal::from(u64::MAX) + Decimal::ONE;
+}
+
+/// Random number of the jackpot roll of the bet, drawn from the seeds and the nonce of the bet
+/// apart from the random numbers of the game
+pub fn jackpot_roll(client_seed: &str, server_seed: &str, nonce: u64) -> u64 {
+    blake_hash_256_u64(&format!("jackpot:{nonce}:{client_seed}:{server_seed}"))
+}
+
+/// The roll maps uniformly to `u` in `[0, 1)`, the jackpot is won if `u` is less than
+/// `stake / price / JACKPOT_ODDS`, so the chance grows with the stake
+pub fn is_jackpot_won(roll: u64, stake: Decimal, price: Decimal) -> bool {
+    if stake <= Decimal::ZERO || price <= Decimal::ZERO {
+        return false;
+    }
+
+    Decimal::from(roll) / *U64_RANGE < stake / price / *JACKPOT_ODDS
+}
+
+/// Streams the new pool of the coin and announces the win of the settled bet
+pub fn propagate_jackpot(manager_sender: &WsManagerEventSender, settled: SettledBet) {
+    if let Some(jackpot) = settled.jackpot {
+        if let Err(e) = manager_sender.send(WsManagerEvent::PropagateJackpot(jackpot)) {
+            error!("Error propagating jackpot: {:?}", e);
+        }
+    }
+    if let Some(win) = settled.jackpot_win {
+        if let Err(e) = manager_sender.send(WsManagerEvent::PropagateJackpotWin(win)) {
+            error!("Error propagating jackpot win: {:?}", e);
+        }
+    }
+}
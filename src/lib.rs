@@ -0,0 +1,23 @@
+#![recursion_limit = "600"]
+
+pub mod api_documentation;
+pub mod autobet;
+pub mod communication;
+pub mod config;
+pub mod crash;
+pub mod db;
+pub mod errors;
+pub mod filters;
+pub mod game_engine;
+pub mod games;
+pub mod handlers;
+pub mod jackpot;
+pub mod jwt;
+pub mod metrics;
+pub mod models;
+pub mod oauth_providers;
+pub mod rejection_handler;
+pub mod tools;
+
+pub use communication::*;
+pub use db::DB;
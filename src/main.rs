@@ -1,17 +1,25 @@
 #![recursion_limit = "600"]
+
 use std::{io, sync::Arc};
 
-use crate::api_documentation::{serve_swagger, ApiDoc};
-use crate::communication::*;
-use crate::game_engine::{Engine, StatefulGameEngine};
+use backend::api_documentation::{serve_swagger, ApiDoc};
+use backend::autobet::AutoBetManager;
+use backend::communication::*;
+use backend::config::{self, DatabaseSettings};
+use backend::crash::CrashEngine;
+use backend::db::DB;
+use backend::game_engine::{
+    parse_statefull_games, parse_stateless_games, Engine, GameStateSweeper, GamesReloader,
+    StatefulGameEngine,
+};
+use backend::rejection_handler::handle_rejection;
+use backend::{filters, oauth_providers};
 //use api_documentation::{serve_swagger, ApiDoc};
-use config::DatabaseSettings;
-use db::DB;
 use futures::future::join_all;
-use rejection_handler::handle_rejection;
 use std::env;
 use thedex::TheDex;
 use tokio::signal;
+use tokio::sync::watch;
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::{fmt, prelude::__tracing_subscriber_SubscriberExt, EnvFilter};
 use utoipa::OpenApi;
@@ -19,21 +27,6 @@ use utoipa_swagger_ui::Config;
 use warp::hyper::header::HeaderName;
 use warp::Filter;
 
-mod api_documentation;
-mod communication;
-mod config;
-mod db;
-mod errors;
-mod filters;
-mod game_engine;
-pub mod games;
-mod handlers;
-mod jwt;
-mod models;
-mod oauth_providers;
-mod rejection_handler;
-mod tools;
-
 #[tokio::main]
 async fn main() {
     //load .env file
@@ -76,6 +69,7 @@ async fn main() {
     debug!("Connecting to DB with settings {:?}", db_settings);
 
     let db = DB::new(&db_settings).await;
+    db.migrate().await.expect("Error migrating the database");
 
     info!(
         "The rest api is starting on the {:?}:{:?}",
@@ -132,7 +126,42 @@ async fn main() {
 
     let (engine_tx, engine_rx) = async_channel::unbounded();
 
-    let (stateful_engine_tx, stateful_engine_rx) = unbounded_channel::<EnginePropagatedBet>();
+    let (crash_tx, crash_rx) = unbounded_channel::<CrashEvent>();
+
+    let (autobet_tx, autobet_rx) = unbounded_channel::<AutoBetEvent>();
+
+    let games = db
+        .fetch_all_games()
+        .await
+        .expect("Error fetching games from db");
+    let (stateless_games_tx, stateless_games_rx) = watch::channel(Arc::new(
+        parse_stateless_games(&games).expect("Error parsing stateless games"),
+    ));
+    let (stateful_games_tx, stateful_games_rx) = watch::channel(Arc::new(
+        parse_statefull_games(&games).expect("Error parsing stateful games"),
+    ));
+    let games_reloader =
+        GamesReloader::new(db.clone(), stateless_games_tx, stateful_games_tx).run();
+
+    info!("Starting `{}` stateful engines", *config::STATEFUL_ENGINES);
+    let mut stateful_engine_txs = Vec::with_capacity(*config::STATEFUL_ENGINES as usize);
+    let mut statefull_engines: Vec<_> = Vec::with_capacity(*config::STATEFUL_ENGINES as usize);
+    for shard in 0..*config::STATEFUL_ENGINES as usize {
+        let (stateful_engine_tx, stateful_engine_rx) = unbounded_channel::<EnginePropagatedBet>();
+        stateful_engine_txs.push(stateful_engine_tx);
+        statefull_engines.push(
+            StatefulGameEngine::new(
+                db.clone(),
+                ws_manager_tx.clone(),
+                stateful_engine_rx,
+                stateful_games_rx.clone(),
+                shard,
+            )
+            .run(),
+        );
+    }
+    let statefull_engines_handle = join_all(statefull_engines);
+    let stateful_engine_tx = StatefulEngineBetRouter::new(stateful_engine_txs);
 
     info!("Starting `{}` engines", *config::ENGINES);
     let mut engines: Vec<_> = Vec::with_capacity(*config::ENGINES as usize);
@@ -143,17 +172,18 @@ async fn main() {
                 ws_manager_tx.clone(),
                 engine_rx.clone(),
                 stateful_engine_tx.clone(),
+                stateless_games_rx.clone(),
             )
-            .await
             .run(),
         );
     }
     let engines_handle = join_all(engines);
 
-    let statefull_engine =
-        StatefulGameEngine::new(db.clone(), ws_manager_tx.clone(), stateful_engine_rx)
-            .await
-            .run();
+    let game_state_sweeper = GameStateSweeper::new(db.clone(), stateful_engine_tx.clone()).run();
+
+    let crash_engine = CrashEngine::new(db.clone(), ws_manager_tx.clone(), crash_rx).run();
+
+    let autobet_manager = AutoBetManager::new(db.clone(), engine_tx.clone(), autobet_rx).run();
 
     info!("Server started, waiting for CTRL+C");
     tokio::select! {
@@ -161,7 +191,7 @@ async fn main() {
             warn!("WS Manager stopped: `{:?}`", r);
         }
         _ = warp::serve(
-            filters::init_filters(db, dex, p2way, ws_manager_tx, engine_tx, hcap, google, dexs).or(api_doc)
+            filters::init_filters(db, dex, p2way, ws_manager_tx, engine_tx, stateful_engine_tx, crash_tx, autobet_tx, hcap, google, dexs).or(api_doc)
             .or(swagger_ui).recover(handle_rejection).with(cors),
         )
         .run((*config::SERVER_HOST, *config::SERVER_PORT)) => {},
@@ -171,8 +201,20 @@ async fn main() {
         _ = engines_handle => {
             warn!("Engine stopped");
         }
-        _ = statefull_engine => {
+        _ = statefull_engines_handle => {
             warn!("Statefull engine stopped");
         }
+        _ = games_reloader => {
+            warn!("Games reloader stopped");
+        }
+        _ = game_state_sweeper => {
+            warn!("Game state sweeper stopped");
+        }
+        _ = crash_engine => {
+            warn!("Crash engine stopped");
+        }
+        _ = autobet_manager => {
+            warn!("Auto bet manager stopped");
+        }
     }
 }
@@ -0,0 +1,99 @@
+use crate::communication::EngineBetSender;
+use crate::models::json_responses::BetRejectionReason;
+use crate::DB;
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
+    Encoder, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder,
+};
+use tracing::error;
+
+lazy_static! {
+    pub static ref BETS_PROCESSED: IntCounterVec = register_int_counter_vec!(
+        "bets_processed_total",
+        "Bets settled by the engines",
+        &["game_id"]
+    )
+    .unwrap();
+    pub static ref BETS_REJECTED: IntCounterVec = register_int_counter_vec!(
+        "bets_rejected_total",
+        "Bets rejected by the engines",
+        &["game_id", "reason"]
+    )
+    .unwrap();
+    pub static ref SETTLEMENT_DURATION: HistogramVec = register_histogram_vec!(
+        "bet_settlement_duration_seconds",
+        "Time of settling the bet in the db",
+        &["engine"],
+        vec![0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5]
+    )
+    .unwrap();
+    pub static ref ENGINE_QUEUE_DEPTH: IntGauge = register_int_gauge!(
+        "engine_queue_depth",
+        "Bets waiting for the stateless engines"
+    )
+    .unwrap();
+    pub static ref STATEFUL_ENGINE_QUEUE_DEPTH: IntGaugeVec = register_int_gauge_vec!(
+        "stateful_engine_queue_depth",
+        "Bets waiting for the stateful engine",
+        &["shard"]
+    )
+    .unwrap();
+    pub static ref WS_FEEDS: IntGauge =
+        register_int_gauge!("ws_feeds", "Websocket feeds connected to the manager").unwrap();
+    pub static ref WS_SUBSCRIPTIONS: IntGaugeVec = register_int_gauge_vec!(
+        "ws_subscriptions",
+        "Subscriptions of the feeds to the channels",
+        &["channel"]
+    )
+    .unwrap();
+    pub static ref DB_POOL_CONNECTIONS: IntGauge =
+        register_int_gauge!("db_pool_connections", "Open connections of the db pool").unwrap();
+    pub static ref DB_POOL_IDLE_CONNECTIONS: IntGauge = register_int_gauge!(
+        "db_pool_idle_connections",
+        "Idle connections of the db pool"
+    )
+    .unwrap();
+    pub static ref DB_POOL_MAX_CONNECTIONS: IntGauge =
+        register_int_gauge!("db_pool_max_connections", "Max connections of the db pool").unwrap();
+    pub static ref INVOICE_CALLBACKS: IntCounterVec = register_int_counter_vec!(
+        "invoice_callbacks_total",
+        "Callbacks of the payment providers",
+        &["provider", "status"]
+    )
+    .unwrap();
+}
+
+pub fn bet_processed(game_id: i64) {
+    BETS_PROCESSED
+        .with_label_values(&[&game_id.to_string()])
+        .inc();
+}
+
+pub fn bet_rejected(game_id: i64, reason: BetRejectionReason) {
+    BETS_REJECTED
+        .with_label_values(&[&game_id.to_string(), &format!("{:?}", reason)])
+        .inc();
+}
+
+pub fn invoice_callback(provider: &str, status: &str) {
+    INVOICE_CALLBACKS
+        .with_label_values(&[provider, status])
+        .inc();
+}
+
+/// Samples the gauges that are not tracked on the fly and encodes all the metrics
+/// in the Prometheus text format
+pub fn gather(db: &DB, engine_sender: &EngineBetSender) -> String {
+    let (connections, idle, max_connections) = db.pool_usage();
+    DB_POOL_CONNECTIONS.set(connections as i64);
+    DB_POOL_IDLE_CONNECTIONS.set(idle as i64);
+    DB_POOL_MAX_CONNECTIONS.set(max_connections as i64);
+    ENGINE_QUEUE_DEPTH.set(engine_sender.len() as i64);
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        error!("Error encoding metrics: {:?}", e);
+    }
+    String::from_utf8(buffer).unwrap()
+}
@@ -137,6 +137,30 @@ pub mod db_models {
         pub id: i64,
         pub name: String,
         pub parameters: String,
+        /// Seconds after the last step of an unfinished game it's resolved, never if `None`
+        pub state_timeout: Option<i64>,
+    }
+
+    /// Hash chain of the crash rounds, the seed is kept secret
+    #[derive(Deserialize, Serialize, Clone, Debug)]
+    pub struct CrashChain {
+        pub id: i64,
+        pub seed: String,
+        pub commitment: String,
+        pub length: i64,
+        pub position: i64,
+        /// Public salt of the rounds, `None` until it's published
+        pub salt: Option<String>,
+    }
+
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug)]
+    pub struct GameLimits {
+        pub game_id: i64,
+        pub coin_id: i64,
+        pub min_bet: Decimal,
+        pub max_bet: Decimal,
+        pub max_num_games: i64,
+        pub max_payout: Decimal,
     }
 
     #[derive(Deserialize, Serialize, ToSchema, Debug, Clone)]
@@ -168,6 +192,18 @@ pub mod db_models {
         pub user_seed: String,
     }
 
+    /// Client and server seeds the user played with, the server seed is not hashed
+    #[derive(Deserialize, Serialize, Clone, Debug)]
+    pub struct SeedPair {
+        pub userseed_id: i64,
+        pub user_seed: String,
+        pub serverseed_id: i64,
+        pub server_seed: String,
+        pub revealed: bool,
+        pub bets_amount: i64,
+        pub bet_ids: Vec<i64>,
+    }
+
     #[derive(Deserialize, Serialize, Clone, ToSchema)]
     pub struct ReferalLink {
         pub id: i64,
@@ -195,12 +231,14 @@ pub mod db_models {
         pub profit: Decimal,
         pub num_games: i32,
         pub outcomes: String,
-        pub profits: Vec<Decimal>,
+        pub profits: String,
 
         pub bet_info: String,
         pub state: Option<String>,
+        pub steps: Option<String>,
 
         pub uuid: String,
+        pub nonce: i64,
 
         pub game_id: i64,
         pub user_id: i64,
@@ -219,8 +257,10 @@ pub mod db_models {
 
         pub bet_info: String,
         pub state: String,
+        pub steps: String,
 
         pub uuid: String,
+        pub nonce: i64,
 
         pub game_id: i64,
         pub user_id: i64,
@@ -229,6 +269,88 @@ pub mod db_models {
         pub serverseed_id: i64,
     }
 
+    impl GameState {
+        /// State shown to the player, the `hidden` part of the game state is revealed only in
+        /// the bet when the game is finished
+        pub fn public(mut self) -> Self {
+            if let Ok(serde_json::Value::Object(mut state)) =
+                serde_json::from_str::<serde_json::Value>(&self.state)
+            {
+                if state.remove("hidden").is_some() {
+                    self.state = serde_json::Value::Object(state).to_string();
+                }
+            }
+            self
+        }
+    }
+
+    /// Auto bet session, every bet of the session is played as a regular bet
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug)]
+    pub struct AutoBet {
+        pub id: i64,
+        #[serde(with = "ts_seconds")]
+        pub timestamp: DateTime<Utc>,
+
+        pub game_id: i64,
+        pub user_id: i64,
+        pub coin_id: i64,
+        pub uuid: String,
+
+        pub data: String,
+        /// Serialized `AutoBetStrategy`
+        pub strategy: String,
+        pub base_amount: Decimal,
+        /// Stake of the next bet
+        pub amount: Decimal,
+        pub max_bets: i64,
+        pub stop_win: Decimal,
+        pub stop_loss: Decimal,
+
+        pub bets: i64,
+        pub profit: Decimal,
+        pub active: bool,
+        pub stop_reason: Option<String>,
+    }
+
+    /// Jackpot pool of the coin
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug)]
+    pub struct Jackpot {
+        pub coin_id: i64,
+        pub amount: Decimal,
+    }
+
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug)]
+    pub struct JackpotWin {
+        pub id: i64,
+        #[serde(with = "ts_seconds")]
+        pub timestamp: DateTime<Utc>,
+        pub bet_id: i64,
+        pub user_id: i64,
+        pub coin_id: i64,
+        pub amount: Decimal,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct SettledBet {
+        pub bet_id: i64,
+        /// Pool after the stake of the bet was added, `None` if the bet doesn't take part in the
+        /// jackpot
+        pub jackpot: Option<Jackpot>,
+        pub jackpot_win: Option<JackpotWin>,
+    }
+
+    /// One move of a stateful game, kept to be able to replay the game later
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug, Default)]
+    pub struct GameStep {
+        pub data: String,
+        pub nonce: u64,
+        pub userseed_id: i64,
+        pub serverseed_id: i64,
+        /// Stake added to the game by the step
+        #[serde(default)]
+        pub stake: Decimal,
+    }
+
     #[derive(Deserialize, Serialize, Clone, ToSchema, Debug, Default)]
     pub struct UserTotals {
         pub bets_amount: i64,
@@ -400,10 +522,11 @@ pub mod json_responses {
     use crate::WsData;
 
     use self::db_models::{
-        Amount, Bet, Coin, Game, GameState, Invoice, Leaderboard, PartnerContact, PartnerInfo,
-        PartnerSite, PartnerSiteInfo, PlayerTotals, RefClicks, SiteSubId, Totals, UserTotals,
-        Withdrawal,
+        Amount, AutoBet, Bet, Coin, Game, GameLimits, GameState, Invoice, Jackpot, JackpotWin,
+        Leaderboard, PartnerContact, PartnerInfo, PartnerSite, PartnerSiteInfo, PlayerTotals,
+        RefClicks, SeedPair, SiteSubId, Totals, UserTotals, Withdrawal,
     };
+    use crate::tools::blake_hash;
 
     // use super::db_models::{
     //     AmountConnectedWallets, Bet, BetInfo, BlockExplorerUrl, Game, GameAbi, Leaderboard,
@@ -414,7 +537,9 @@ pub mod json_responses {
     use chrono::serde::ts_seconds;
     use chrono::{DateTime, Utc};
     use rust_decimal::Decimal;
+    use std::collections::BTreeMap;
     use thedex::models::Price;
+    use utoipa::openapi::{RefOr, Schema};
 
     #[derive(Serialize, Deserialize, ToSchema)]
     pub enum Status {
@@ -462,6 +587,15 @@ pub mod json_responses {
         Bet(BetExpanded),
         State(GameState),
         ServerSeedHidden(Seed),
+        SeedHistory(SeedHistory),
+        BetVerification(BetVerification),
+        BetRejected(BetRejected),
+        CrashRound(CrashRoundUpdate),
+        AutoBet(AutoBet),
+        AutoBets(Vec<AutoBet>),
+        Jackpot(Jackpot),
+        Jackpots(Vec<Jackpot>),
+        JackpotWin(JackpotWin),
         // Abi(GameAbi),
         Totals(Totals),
         LatestGames(LatestGames),
@@ -531,9 +665,13 @@ pub mod json_responses {
             match value {
                 WsData::NewBet(bet) => ResponseBody::Bet(bet),
                 WsData::ServerSeed(seed) => ResponseBody::ServerSeedHidden(Seed { seed }),
-                WsData::StateUpdate(state) => ResponseBody::State(state),
+                WsData::StateUpdate(state) => ResponseBody::State(state.public()),
                 WsData::NewMessage(m) => ResponseBody::ChatMessage(m),
                 WsData::Invoice(invoice) => ResponseBody::Invoice(invoice),
+                WsData::BetRejected(rejected) => ResponseBody::BetRejected(rejected),
+                WsData::CrashRound(update) => ResponseBody::CrashRound(update),
+                WsData::Jackpot(jackpot) => ResponseBody::Jackpot(jackpot),
+                WsData::JackpotWin(win) => ResponseBody::JackpotWin(win),
             }
         }
     }
@@ -545,9 +683,13 @@ pub mod json_responses {
                 WsData::ServerSeed(seed) => {
                     ResponseBody::ServerSeedHidden(Seed { seed: seed.clone() })
                 }
-                WsData::StateUpdate(state) => ResponseBody::State(state.clone()),
+                WsData::StateUpdate(state) => ResponseBody::State(state.clone().public()),
                 WsData::NewMessage(m) => ResponseBody::ChatMessage(m.clone()),
                 WsData::Invoice(invoice) => ResponseBody::Invoice(invoice.clone()),
+                WsData::BetRejected(rejected) => ResponseBody::BetRejected(rejected.clone()),
+                WsData::CrashRound(update) => ResponseBody::CrashRound(update.clone()),
+                WsData::Jackpot(jackpot) => ResponseBody::Jackpot(jackpot.clone()),
+                WsData::JackpotWin(win) => ResponseBody::JackpotWin(win.clone()),
             }
         }
     }
@@ -565,6 +707,23 @@ pub mod json_responses {
     #[derive(Serialize, Deserialize, Clone, ToSchema)]
     pub struct Games {
         pub games: Vec<Game>,
+        pub limits: Vec<GameLimits>,
+        pub schemas: Vec<GameSchema>,
+    }
+
+    /// Schemas of the bet data of a game type
+    #[derive(Serialize, Deserialize, Clone, ToSchema)]
+    pub struct GameSchema {
+        /// Names of the games played with the game type
+        pub names: Vec<String>,
+        pub stateful: bool,
+        /// Name of the schema of the bet data
+        pub bet_data: String,
+        /// Name of the schema of the continue data of stateful games
+        pub continue_data: Option<String>,
+        /// Bet data schemas keyed by name
+        #[schema(value_type = Object)]
+        pub schemas: BTreeMap<String, RefOr<Schema>>,
     }
 
     #[derive(Serialize, Deserialize, Clone, ToSchema)]
@@ -582,6 +741,41 @@ pub mod json_responses {
         pub seed: String,
     }
 
+    #[derive(Serialize, Deserialize, Clone, ToSchema)]
+    pub struct SeedPairInfo {
+        pub userseed_id: i64,
+        pub user_seed: String,
+        pub serverseed_id: i64,
+        /// Hash of the server seed shown to the user while the seed was in use
+        pub server_seed_hash: String,
+        /// Plain server seed, available once the seed is revealed
+        pub server_seed: Option<String>,
+        pub revealed: bool,
+        pub bets_amount: i64,
+        /// Ids of the bets played with the seeds in increasing order
+        pub bet_ids: Vec<i64>,
+    }
+
+    impl From<SeedPair> for SeedPairInfo {
+        fn from(pair: SeedPair) -> Self {
+            Self {
+                userseed_id: pair.userseed_id,
+                user_seed: pair.user_seed,
+                serverseed_id: pair.serverseed_id,
+                server_seed_hash: blake_hash(&pair.server_seed),
+                server_seed: pair.revealed.then_some(pair.server_seed),
+                revealed: pair.revealed,
+                bets_amount: pair.bets_amount,
+                bet_ids: pair.bet_ids,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Clone, ToSchema)]
+    pub struct SeedHistory {
+        pub seeds: Vec<SeedPairInfo>,
+    }
+
     #[derive(Serialize, Deserialize, Clone, ToSchema)]
     pub struct UserStripped {
         pub id: i64,
@@ -850,6 +1044,87 @@ pub mod json_responses {
         pub coin_id: i64,
         pub userseed_id: i64,
         pub serverseed_id: i64,
+        pub nonce: i64,
+    }
+
+    #[derive(Deserialize, Serialize, Clone, Copy, ToSchema, Debug, PartialEq, Eq)]
+    pub enum BetRejectionReason {
+        TooManyGames,
+        StakeTooLow,
+        StakeTooHigh,
+        /// The highest payout of the bet is above the max payout of the game
+        PayoutTooHigh,
+        InsufficientBalance,
+        MissingSeed,
+        InvalidBet,
+        LimitsNotFound,
+        GameNotFound,
+        GameAlreadyStarted,
+        GameNotStarted,
+        BettingClosed,
+        InternalError,
+    }
+
+    /// Events of the crash round propagated to the `CrashRound` channel
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug)]
+    #[serde(tag = "phase")]
+    pub enum CrashRoundUpdate {
+        /// Bets are accepted for the next `betting_window` milliseconds
+        Betting {
+            round_id: i64,
+            chain_commitment: String,
+            /// Public salt mixed into the hashes of the rounds of the chain
+            chain_salt: String,
+            betting_window: u64,
+        },
+        Started {
+            round_id: i64,
+        },
+        Tick {
+            round_id: i64,
+            multiplier: Decimal,
+        },
+        NewBet {
+            round_id: i64,
+            user_id: i64,
+            coin_id: i64,
+            amount: Decimal,
+        },
+        CashedOut {
+            round_id: i64,
+            user_id: i64,
+            coin_id: i64,
+            multiplier: Decimal,
+            profit: Decimal,
+        },
+        /// The round is over, hashing the `hash` gives the hash of the previous round
+        Busted {
+            round_id: i64,
+            crash_point: Decimal,
+            hash: String,
+        },
+    }
+
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug)]
+    pub struct BetRejected {
+        pub uuid: String,
+        pub reason_code: BetRejectionReason,
+        pub message: String,
+    }
+
+    #[derive(Deserialize, Serialize, Clone, ToSchema, Debug)]
+    pub struct BetVerification {
+        pub bet_id: i64,
+        pub game_id: i64,
+        pub user_seed: String,
+        pub server_seed: String,
+        pub server_seed_hash: String,
+        pub nonce: i64,
+        pub outcomes: String,
+        pub profits: String,
+        pub replayed_outcomes: String,
+        pub replayed_profits: String,
+        pub verified: bool,
     }
 
     #[derive(Deserialize, Serialize, ToSchema)]
@@ -1006,10 +1281,38 @@ pub mod json_requests {
         MakeBet(PropagatedBet),
         ContinueGame(ContinueGame),
         GetState(GetState),
+        VerifyBet { bet_id: i64 },
         GetUuid,
         SubscribeChatRoom { room: i64 },
         UnsubscribeChatRoom { room: i64 },
         NewMessage(ChatMessage),
+        SubscribeCrash,
+        UnsubscribeCrash,
+        CrashBet(CrashBet),
+        CrashCashout(CrashCashout),
+        StartAutoBet(AutoBetStart),
+        StopAutoBet { session_id: i64 },
+        /// Also binds the active sessions to the socket, e.g. after a reconnect
+        GetAutoBets,
+        SubscribeJackpot,
+        UnsubscribeJackpot,
+    }
+
+    #[derive(Deserialize, Serialize, ToSchema, Debug, Clone)]
+    pub struct CrashBet {
+        pub coin_id: i64,
+        pub amount: Decimal,
+        /// Multiplier at which the bet is cashed out automatically, lost if the round crashes at it
+        pub auto_cashout: Option<Decimal>,
+        pub user_id: Option<i64>,
+        pub uuid: Option<String>,
+    }
+
+    #[derive(Deserialize, Serialize, ToSchema, Debug, Clone)]
+    pub struct CrashCashout {
+        pub coin_id: i64,
+        pub user_id: Option<i64>,
+        pub uuid: Option<String>,
     }
 
     #[derive(Deserialize, Serialize, ToSchema, Debug, Clone)]
@@ -1018,6 +1321,39 @@ pub mod json_requests {
         pub coin_id: i64,
     }
 
+    /// How the stake changes after every bet of an auto bet session, a bet is won when it
+    /// pays out more than its stake and a push, paying out the stake back, keeps the stake
+    #[derive(Deserialize, Serialize, ToSchema, Debug, Clone)]
+    #[serde(tag = "type")]
+    pub enum AutoBetStrategy {
+        /// Every bet is played with the base stake
+        Fixed,
+        /// Doubles the stake on a loss, resets it on a win
+        Martingale,
+        /// Doubles the stake on a win, resets it on a loss
+        ReverseMartingale,
+        /// Increases the stake by `percent` on a loss, resets it on a win
+        IncreaseOnLoss { percent: Decimal },
+    }
+
+    #[derive(Deserialize, Serialize, ToSchema, Debug, Clone)]
+    pub struct AutoBetStart {
+        /// Only stateless games can be auto played
+        pub game_id: i64,
+        pub coin_id: i64,
+        /// Base stake of the session
+        pub amount: Decimal,
+        pub data: String,
+        pub strategy: AutoBetStrategy,
+        pub max_bets: i64,
+        /// The session stops when its profit reaches it, zero to disable
+        pub stop_win: Decimal,
+        /// The session stops when its profit drops to it, negative, zero to disable
+        pub stop_loss: Decimal,
+        pub user_id: Option<i64>,
+        pub uuid: Option<String>,
+    }
+
     #[derive(Deserialize, Serialize, ToSchema)]
     pub struct RegisterPartner {
         pub name: String,
@@ -0,0 +1,375 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use backend::communication::{
+    EngineBetReciever, EngineBetSender, EnginePropagatedBet, StatefulEngineBetReciever,
+    StatefulEngineBetRouter, WsManagerEvent, WsManagerEventReceiver, WsManagerEventSender,
+};
+use backend::db::{BetStore, GameStateStore, MemoryDB, SeedStore, UserStore, WalletStore};
+use backend::game_engine::{
+    parse_statefull_games, parse_stateless_games, Engine, StatefulGameEngine,
+};
+use backend::models::db_models::{Coin, Game, GameLimits, GameState, OauthProvider, User};
+use backend::models::json_requests::{ContinueGame, PropagatedBet};
+use backend::models::json_responses::BetRejectionReason;
+use backend::tools::blake_hash;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tokio::sync::{mpsc, watch};
+
+const MINES_PARAMETERS: &str = r#"{"max_reveal":[24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24,24],"multipliers":[["1.0312","1.076"]]}"#;
+
+/// Runs the stateless and the stateful engine on their own thread, as the games they play are
+/// not `Send`
+#[allow(clippy::arc_with_non_send_sync)]
+fn run_engines(
+    db: MemoryDB,
+    games: Vec<Game>,
+    manager_sender: WsManagerEventSender,
+    bet_receiver: EngineBetReciever,
+    stateful_bet_sender: StatefulEngineBetRouter,
+    stateful_bet_receiver: StatefulEngineBetReciever,
+) {
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async move {
+            let (_stateless_sender, stateless_receiver) =
+                watch::channel(Arc::new(parse_stateless_games(&games).unwrap()));
+            let (_stateful_sender, stateful_receiver) =
+                watch::channel(Arc::new(parse_statefull_games(&games).unwrap()));
+            tokio::join!(
+                Engine::new(
+                    db.clone(),
+                    manager_sender.clone(),
+                    bet_receiver,
+                    stateful_bet_sender,
+                    stateless_receiver,
+                )
+                .run(),
+                StatefulGameEngine::new(
+                    db,
+                    manager_sender,
+                    stateful_bet_receiver,
+                    stateful_receiver,
+                    0,
+                )
+                .run(),
+            );
+        });
+    });
+}
+
+struct Setup {
+    db: MemoryDB,
+    user: User,
+    coin: Coin,
+    coinflip: Game,
+    mines: Game,
+    bet_sender: EngineBetSender,
+    stateful_bet_sender: StatefulEngineBetRouter,
+    manager_receiver: WsManagerEventReceiver,
+}
+
+async fn setup() -> Setup {
+    dotenvy::dotenv().ok();
+
+    let db = MemoryDB::new();
+    let coin = db.add_coin("DraxBonus", Decimal::ONE);
+    let coinflip = db.add_game("CoinFlip", r#"{"profit_coef":"1.98"}"#, None);
+    let mines = db.add_game("Mines", MINES_PARAMETERS, None);
+    for game in [&coinflip, &mines] {
+        db.add_game_limits(GameLimits {
+            game_id: game.id,
+            coin_id: coin.id,
+            min_bet: dec!(0.5),
+            max_bet: dec!(1000),
+            max_num_games: 100,
+            max_payout: dec!(10000),
+        });
+    }
+
+    let user = db
+        .register_user(
+            "player",
+            "player",
+            OauthProvider::Local,
+            &blake_hash("password"),
+        )
+        .await
+        .unwrap();
+    db.init_amount(user.id, coin.id, dec!(100)).await.unwrap();
+    db.new_user_seed(user.id, "user seed").await.unwrap();
+    db.new_server_seed(user.id, "server seed").await.unwrap();
+
+    let (manager_sender, manager_receiver) = mpsc::unbounded_channel();
+    let (bet_sender, bet_receiver) = async_channel::unbounded();
+    let (stateful_sender, stateful_bet_receiver) = mpsc::unbounded_channel();
+    let stateful_bet_sender = StatefulEngineBetRouter::new(vec![stateful_sender]);
+    run_engines(
+        db.clone(),
+        vec![coinflip.clone(), mines.clone()],
+        manager_sender,
+        bet_receiver,
+        stateful_bet_sender.clone(),
+        stateful_bet_receiver,
+    );
+
+    Setup {
+        db,
+        user,
+        coin,
+        coinflip,
+        mines,
+        bet_sender,
+        stateful_bet_sender,
+        manager_receiver,
+    }
+}
+
+/// Waits for the next bet, state or rejection sent to the manager
+async fn next_event(receiver: &mut WsManagerEventReceiver) -> WsManagerEvent {
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .expect("No event from the engine")
+            .expect("Manager channel closed");
+        match event {
+            WsManagerEvent::PropagateBet(_)
+            | WsManagerEvent::PropagateState(_)
+            | WsManagerEvent::RejectBet(_) => return event,
+            _ => continue,
+        }
+    }
+}
+
+fn new_bet(setup: &Setup, game: &Game, amount: Decimal, data: &str) -> EnginePropagatedBet {
+    EnginePropagatedBet::NewBet(PropagatedBet {
+        game_id: game.id,
+        amount,
+        coin_id: setup.coin.id,
+        user_id: Some(setup.user.id),
+        uuid: Some("uuid".into()),
+        data: data.into(),
+        stop_loss: Decimal::ZERO,
+        stop_win: Decimal::ZERO,
+        num_games: 1,
+    })
+}
+
+async fn balance(setup: &Setup) -> Decimal {
+    setup
+        .db
+        .fetch_amount(setup.user.id, setup.coin.id)
+        .await
+        .unwrap()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn stateless_bet_is_settled() {
+    let mut setup = setup().await;
+    let bet = new_bet(
+        &setup,
+        &setup.coinflip,
+        Decimal::ONE,
+        r#"{"is_heads":true}"#,
+    );
+    setup.bet_sender.send(bet).await.unwrap();
+
+    let bet = match next_event(&mut setup.manager_receiver).await {
+        WsManagerEvent::PropagateBet(bet) => bet,
+        event => panic!("Unexpected event: {:?}", event),
+    };
+    assert_eq!(bet.amount, Decimal::ONE);
+    assert_eq!(balance(&setup).await, dec!(99) + bet.profit);
+
+    let bets = setup
+        .db
+        .fetch_bets_for_user(setup.user.id, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(bets.len(), 1);
+    assert_eq!(bets[0].id, bet.id);
+}
+
+#[tokio::test]
+async fn stopped_bet_takes_only_played_rounds() {
+    let mut setup = setup().await;
+    let bet = EnginePropagatedBet::NewBet(PropagatedBet {
+        game_id: setup.coinflip.id,
+        amount: Decimal::ONE,
+        coin_id: setup.coin.id,
+        user_id: Some(setup.user.id),
+        uuid: Some("uuid".into()),
+        data: r#"{"is_heads":true}"#.into(),
+        stop_loss: dec!(-5),
+        stop_win: dec!(0.5),
+        num_games: 10,
+    });
+    setup.bet_sender.send(bet).await.unwrap();
+
+    let bet = match next_event(&mut setup.manager_receiver).await {
+        WsManagerEvent::PropagateBet(bet) => bet,
+        event => panic!("Unexpected event: {:?}", event),
+    };
+    assert!(bet.num_games < 10);
+    assert_eq!(
+        balance(&setup).await,
+        dec!(100) - Decimal::from(bet.num_games) + bet.profit
+    );
+}
+
+#[tokio::test]
+async fn stateless_bet_is_rejected() {
+    let mut setup = setup().await;
+
+    let bet = new_bet(&setup, &setup.coinflip, dec!(101), r#"{"is_heads":true}"#);
+    setup.bet_sender.send(bet).await.unwrap();
+    match next_event(&mut setup.manager_receiver).await {
+        WsManagerEvent::RejectBet(rejected) => assert!(matches!(
+            rejected.reason_code,
+            BetRejectionReason::InsufficientBalance
+        )),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+
+    let bet = new_bet(&setup, &setup.coinflip, dec!(0.1), r#"{"is_heads":true}"#);
+    setup.bet_sender.send(bet).await.unwrap();
+    match next_event(&mut setup.manager_receiver).await {
+        WsManagerEvent::RejectBet(rejected) => assert!(matches!(
+            rejected.reason_code,
+            BetRejectionReason::StakeTooLow
+        )),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+
+    let bet = EnginePropagatedBet::NewBet(PropagatedBet {
+        game_id: setup.coinflip.id,
+        amount: dec!(100),
+        coin_id: setup.coin.id,
+        user_id: Some(setup.user.id),
+        uuid: Some("uuid".into()),
+        data: r#"{"is_heads":true}"#.into(),
+        stop_loss: Decimal::ZERO,
+        stop_win: Decimal::ZERO,
+        num_games: 60,
+    });
+    setup.bet_sender.send(bet).await.unwrap();
+    match next_event(&mut setup.manager_receiver).await {
+        WsManagerEvent::RejectBet(rejected) => assert!(matches!(
+            rejected.reason_code,
+            BetRejectionReason::PayoutTooHigh
+        )),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+
+    assert_eq!(balance(&setup).await, dec!(100));
+    assert!(setup
+        .db
+        .fetch_bets_for_user(setup.user.id, None, 10)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn stateful_game_is_played_to_the_end() {
+    let mut setup = setup().await;
+
+    let mut tiles = [false; 25];
+    tiles[0] = true;
+    let data = serde_json::json!({"num_mines": 1, "tiles": tiles, "cashout": false});
+    let bet = new_bet(&setup, &setup.mines, Decimal::ONE, &data.to_string());
+    setup.bet_sender.send(bet).await.unwrap();
+
+    match next_event(&mut setup.manager_receiver).await {
+        WsManagerEvent::PropagateState(state) => assert_eq!(state.amount, Decimal::ONE),
+        event => panic!("Unexpected event: {:?}", event),
+    }
+    assert_eq!(balance(&setup).await, dec!(99));
+    assert!(setup
+        .db
+        .fetch_game_state(setup.mines.id, setup.user.id, setup.coin.id)
+        .await
+        .unwrap()
+        .is_some());
+
+    let cashout = EnginePropagatedBet::ContinueGame(ContinueGame {
+        game_id: setup.mines.id,
+        coin_id: setup.coin.id,
+        user_id: Some(setup.user.id),
+        uuid: Some("uuid".into()),
+        data: r#"{"tiles":null,"cashout":true}"#.into(),
+    });
+    setup.stateful_bet_sender.send(cashout).unwrap();
+
+    let bet = match next_event(&mut setup.manager_receiver).await {
+        WsManagerEvent::PropagateBet(bet) => bet,
+        event => panic!("Unexpected event: {:?}", event),
+    };
+    assert_eq!(bet.profit, dec!(1.0312));
+    assert_eq!(balance(&setup).await, dec!(99) + dec!(1.0312));
+    assert!(setup
+        .db
+        .fetch_game_state(setup.mines.id, setup.user.id, setup.coin.id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn expired_state_of_a_missing_game_is_refunded() {
+    let setup = setup().await;
+    let removed = setup.db.add_game("Removed", "{}", Some(1));
+    assert!(setup
+        .db
+        .start_game_state(
+            dec!(5),
+            &GameState {
+                id: 0,
+                timestamp: chrono::Utc::now(),
+                amount: dec!(5),
+                bet_info: "{}".into(),
+                state: "{}".into(),
+                steps: "[]".into(),
+                uuid: "uuid".into(),
+                nonce: 0,
+                game_id: removed.id,
+                user_id: setup.user.id,
+                coin_id: setup.coin.id,
+                userseed_id: 0,
+                serverseed_id: 0,
+            },
+        )
+        .await
+        .unwrap());
+    assert_eq!(balance(&setup).await, dec!(95));
+
+    let state = setup
+        .db
+        .fetch_game_state(removed.id, setup.user.id, setup.coin.id)
+        .await
+        .unwrap()
+        .unwrap();
+    setup
+        .stateful_bet_sender
+        .send(EnginePropagatedBet::ResolveGame(state))
+        .unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while balance(&setup).await != dec!(100) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("The expired state was not refunded");
+    assert!(setup
+        .db
+        .fetch_game_state(removed.id, setup.user.id, setup.coin.id)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,201 @@
+use backend::db::{MemoryDB, SeedStore, UserStore, WalletStore};
+use backend::filters;
+use backend::models::db_models::{Coin, OauthProvider, User};
+use backend::oauth_providers::google::GoogleOauth;
+use backend::rejection_handler::handle_rejection;
+use backend::tools::blake_hash;
+use rust_decimal_macros::dec;
+use serde_json::{json, Value};
+use warp::http::StatusCode;
+use warp::Filter;
+
+async fn setup() -> (MemoryDB, User, Coin) {
+    dotenvy::dotenv().ok();
+
+    let db = MemoryDB::new();
+    let coin = db.add_coin("DraxBonus", dec!(1));
+    let user = db
+        .register_user(
+            "player",
+            "player",
+            OauthProvider::Local,
+            &blake_hash("password"),
+        )
+        .await
+        .unwrap();
+    db.init_amount(user.id, coin.id, dec!(100)).await.unwrap();
+    db.new_user_seed(user.id, "user seed").await.unwrap();
+    db.new_server_seed(user.id, "server seed").await.unwrap();
+    (db, user, coin)
+}
+
+fn body(response: &warp::http::Response<warp::hyper::body::Bytes>) -> Value {
+    serde_json::from_slice(response.body()).unwrap()
+}
+
+#[tokio::test]
+async fn user_amounts_and_seeds() {
+    let (db, user, coin) = setup().await;
+    let api = filters::user(
+        db.clone(),
+        hcaptcha::HCaptcha::new(String::new()),
+        GoogleOauth::new(),
+    )
+    .recover(handle_rejection);
+
+    let response = warp::test::request()
+        .path(&format!("/user/amounts/{}", user.id))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let amounts = body(&response);
+    assert_eq!(amounts["body"]["type"], "Amounts");
+    assert_eq!(amounts["body"]["amounts"][0]["name"], coin.name);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/user/login")
+        .json(&json!({"login": "player", "password": "wrong"}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/user/login")
+        .json(&json!({"login": "player", "password": "password"}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let token = body(&response)["body"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    let response = warp::test::request()
+        .path("/user/seed/client")
+        .reply(&api)
+        .await;
+    assert_ne!(response.status(), StatusCode::OK);
+
+    let response = warp::test::request()
+        .path("/user/seed/client")
+        .header("Authorization", format!("Bearer {}", token))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(&response)["body"]["seed"], "user seed");
+
+    let response = warp::test::request()
+        .path("/user/seed/history")
+        .header("Authorization", format!("Bearer {}", token))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let history = body(&response);
+    assert_eq!(history["body"]["seeds"][0]["user_seed"], "user seed");
+    assert_eq!(history["body"]["seeds"][0]["revealed"], false);
+
+    // the pairs replaced without a bet are kept
+    db.new_user_seed(user.id, "new user seed").await.unwrap();
+    db.reveal_last_seed(user.id).await.unwrap();
+    db.new_server_seed(user.id, "new server seed")
+        .await
+        .unwrap();
+    let response = warp::test::request()
+        .path("/user/seed/history")
+        .header("Authorization", format!("Bearer {}", token))
+        .reply(&api)
+        .await;
+    let seeds = body(&response)["body"]["seeds"].clone();
+    let pairs: Vec<(&str, bool)> = seeds
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|pair| {
+            (
+                pair["user_seed"].as_str().unwrap(),
+                pair["revealed"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("new user seed", false),
+            ("new user seed", true),
+            ("user seed", true)
+        ]
+    );
+    assert_eq!(seeds[1]["server_seed"], "server seed");
+}
+
+#[tokio::test]
+async fn bets_of_the_user() {
+    let (db, user, _) = setup().await;
+    let api = filters::bets(db).recover(handle_rejection);
+
+    let response = warp::test::request()
+        .path(&format!("/bets/user/{}", user.id))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(&response)["body"]["bets"], json!([]));
+}
+
+#[tokio::test]
+async fn partner_registration_and_login() {
+    let (db, _, _) = setup().await;
+    let api = filters::partners::partners(db).recover(handle_rejection);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/partner/register")
+        .json(&json!({
+            "name": "partner",
+            "country": "country",
+            "traffic_source": "source",
+            "users_amount_a_month": 100,
+            "main_wallet": "wallet",
+            "login": "partner",
+            "password": "password",
+            "language": null,
+        }))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/partner/register")
+        .json(&json!({
+            "name": "partner",
+            "country": "country",
+            "traffic_source": "source",
+            "users_amount_a_month": 100,
+            "main_wallet": "wallet",
+            "login": "partner",
+            "password": "password",
+            "language": null,
+        }))
+        .reply(&api)
+        .await;
+    assert_ne!(response.status(), StatusCode::OK);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/partner/login")
+        .json(&json!({"login": "partner", "password": "wrong"}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/partner/login")
+        .json(&json!({"login": "partner", "password": "password"}))
+        .reply(&api)
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body(&response)["body"]["type"], "AccessToken");
+}
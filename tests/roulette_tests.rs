@@ -0,0 +1,222 @@
+use backend::games::{Roulette, RouletteData, RouletteWheel, RoundGameEng, DOUBLE_ZERO};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::{json, Value};
+
+const RED_NUMBERS: [u64; 18] = [
+    1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36,
+];
+
+fn roulette(wheel: &str) -> Roulette {
+    serde_json::from_value(json!({
+        "wheel": wheel,
+        "zero_coef": "36",
+        "num_coef": "36",
+        "num2_coef": "18",
+        "num3_coef": "12",
+        "num4_coef": "9",
+        "num5_coef": "7",
+        "num6_coef": "6",
+        "num12_coef": "3",
+        "num18_coef": "2"
+    }))
+    .unwrap()
+}
+
+/// Payout of a bet of 1 when the ball lands on `outcome`, `None` if the bet is rejected
+fn payout(roulette: &Roulette, bet: &Value, outcome: u64) -> Option<Decimal> {
+    let data: RouletteData = serde_json::from_value(json!({
+        "bets": [{"amount": "1", "bet": bet}]
+    }))
+    .unwrap();
+    roulette
+        .play_round(&data, Decimal::ONE, &[outcome])
+        .map(|round| round.payout)
+}
+
+/// Checks that the bet pays `coef` on every winning outcome of the wheel and nothing otherwise
+fn assert_pays(roulette: &Roulette, pockets: u64, bet: Value, winning: &[u64], coef: Decimal) {
+    for outcome in 0..pockets {
+        let expected = if winning.contains(&outcome) {
+            coef
+        } else {
+            Decimal::ZERO
+        };
+        assert_eq!(
+            payout(roulette, &bet, outcome),
+            Some(expected),
+            "bet {} on outcome {}",
+            bet,
+            outcome
+        );
+    }
+}
+
+/// Bets legal on both wheels, with their winning numbers and multipliers
+fn common_bets() -> Vec<(Value, Vec<u64>, Decimal)> {
+    let numbers = 1..=36u64;
+    vec![
+        (
+            json!({"type": "Color", "data": false}),
+            RED_NUMBERS.to_vec(),
+            dec!(2),
+        ),
+        (
+            json!({"type": "Color", "data": true}),
+            numbers
+                .clone()
+                .filter(|n| !RED_NUMBERS.contains(n))
+                .collect(),
+            dec!(2),
+        ),
+        (json!({"type": "Zero"}), vec![0], dec!(36)),
+        (json!({"type": "Number", "data": 17}), vec![17], dec!(36)),
+        (
+            json!({"type": "Number2", "data": {"numbers": [20, 17]}}),
+            vec![17, 20],
+            dec!(18),
+        ),
+        (
+            json!({"type": "Number3", "data": {"numbers": [13, 14, 15]}}),
+            vec![13, 14, 15],
+            dec!(12),
+        ),
+        (
+            json!({"type": "Number4", "data": {"numbers": [17, 18, 20, 21]}}),
+            vec![17, 18, 20, 21],
+            dec!(9),
+        ),
+        (
+            json!({"type": "Number6", "data": {"numbers": [31, 32, 33, 34, 35, 36]}}),
+            vec![31, 32, 33, 34, 35, 36],
+            dec!(6),
+        ),
+        (
+            json!({"type": "Number18", "data": true}),
+            (1..=18).collect(),
+            dec!(2),
+        ),
+        (
+            json!({"type": "Number18", "data": false}),
+            (19..=36).collect(),
+            dec!(2),
+        ),
+        (
+            json!({"type": "OddEven", "data": true}),
+            numbers.clone().filter(|n| n % 2 == 0).collect(),
+            dec!(2),
+        ),
+        (
+            json!({"type": "OddEven", "data": false}),
+            numbers.clone().filter(|n| n % 2 == 1).collect(),
+            dec!(2),
+        ),
+        (
+            json!({"type": "Number12", "data": 1}),
+            (13..=24).collect(),
+            dec!(3),
+        ),
+        (
+            json!({"type": "Column", "data": 2}),
+            numbers.filter(|n| n % 3 == 0).collect(),
+            dec!(3),
+        ),
+    ]
+}
+
+#[test]
+fn single_zero_wheel_pays_every_bet() {
+    let roulette = roulette("SingleZero");
+
+    let mut bets = common_bets();
+    bets.extend([
+        (
+            json!({"type": "Number2", "data": {"numbers": [0, 3]}}),
+            vec![0, 3],
+            dec!(18),
+        ),
+        (
+            json!({"type": "Number3", "data": {"numbers": [0, 1, 2]}}),
+            vec![0, 1, 2],
+            dec!(12),
+        ),
+        (
+            json!({"type": "Number4", "data": {"numbers": [0, 1, 2, 3]}}),
+            vec![0, 1, 2, 3],
+            dec!(9),
+        ),
+    ]);
+    for (bet, winning, coef) in bets {
+        assert_pays(&roulette, 37, bet, &winning, coef);
+    }
+
+    for bet in [
+        json!({"type": "DoubleZero"}),
+        json!({"type": "TopLine"}),
+        json!({"type": "Number2", "data": {"numbers": [0, DOUBLE_ZERO]}}),
+        json!({"type": "Number3", "data": {"numbers": [1, 2, 4]}}),
+    ] {
+        assert_eq!(payout(&roulette, &bet, 0), None, "bet {}", bet);
+    }
+}
+
+#[test]
+fn double_zero_wheel_pays_every_bet() {
+    let roulette = roulette("DoubleZero");
+    let double_zero = DOUBLE_ZERO as u64;
+
+    let mut bets = common_bets();
+    bets.extend([
+        (json!({"type": "DoubleZero"}), vec![double_zero], dec!(36)),
+        (
+            json!({"type": "Number2", "data": {"numbers": [DOUBLE_ZERO, 0]}}),
+            vec![0, double_zero],
+            dec!(18),
+        ),
+        (
+            json!({"type": "Number3", "data": {"numbers": [2, 3, DOUBLE_ZERO]}}),
+            vec![2, 3, double_zero],
+            dec!(12),
+        ),
+        (
+            json!({"type": "TopLine"}),
+            vec![0, 1, 2, 3, double_zero],
+            dec!(7),
+        ),
+    ]);
+    for (bet, winning, coef) in bets {
+        assert_pays(&roulette, 38, bet, &winning, coef);
+    }
+
+    for bet in [
+        json!({"type": "Number4", "data": {"numbers": [0, 1, 2, 3]}}),
+        json!({"type": "Number2", "data": {"numbers": [0, 3]}}),
+        json!({"type": "Number", "data": 0}),
+    ] {
+        assert_eq!(payout(&roulette, &bet, 0), None, "bet {}", bet);
+    }
+}
+
+#[test]
+fn parameters_without_the_new_fields_are_parsed() {
+    let roulette: Roulette = serde_json::from_str(
+        r#"{
+            "zero_coef":"52",
+            "num_coef":"34.8148",
+            "num2_coef":"17.3752",
+            "num4_coef":"8.6957",
+            "num12_coef":"2.8986",
+            "num18_coef":"1.9322"
+        }"#,
+    )
+    .unwrap();
+
+    assert!(roulette.wheel == RouletteWheel::SingleZero);
+    assert_eq!(roulette.num3_coef, dec!(12));
+    assert_eq!(roulette.num5_coef, dec!(7));
+    assert_eq!(roulette.num6_coef, dec!(6));
+    assert_eq!(
+        payout(&roulette, &json!({"type": "Zero"}), 37),
+        Some(dec!(52))
+    );
+}